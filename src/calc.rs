@@ -2,5 +2,26 @@ pub mod variable_type;
 pub mod scalar;
 pub mod vector;
 pub mod matrix;
+pub mod cmatrix;
+pub mod sparse;
 pub mod complex;
+pub mod decomposition;
+pub mod rational;
+pub mod elimination;
+pub mod bigint;
+pub mod bigdecimal;
+pub mod units;
+pub mod interval;
+pub mod measurement;
+pub mod polynomial;
+pub mod boolean;
+pub mod text;
+pub mod datetime;
+pub mod quaternion;
+pub mod tensor;
+pub mod integer;
+pub mod modular;
+pub mod number_theory;
+pub mod radix;
+pub mod list;
 pub mod variable_data;
\ No newline at end of file
@@ -0,0 +1,779 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use std::str::FromStr;
+
+use crate::{binary_unit, conversion_error, format_error, operator_error, operation_error, core::{io::BinaryUnit, errors::Error}};
+use crate::io::sesssion::session_settings;
+use super::{variable_type::VariableType, bigint::BigInt};
+
+/// A decimal floating point number, `mantissa * 10^exponent`, with an unbounded mantissa.
+/// Every operation rounds its result (half to even) to a number of significant digits, which the operators and the plain methods take from `session_settings`.
+/// The `_with` methods take the precision explicitly. All of them are correctly rounded.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigDecimal {
+    mantissa: BigInt,
+    exponent: i64
+}
+impl Debug for BigDecimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(BigDecimal:{})", self)
+    }
+}
+impl Display for BigDecimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.mantissa.abs().to_string();
+        let len = digits.len() as i64;
+        let point = len + self.exponent;
+
+        //Plain notation for moderately sized values, scientific otherwise.
+        let body = if self.exponent >= 0 && point <= 30 {
+            format!("{}{}", digits, "0".repeat(self.exponent as usize))
+        }
+        else if self.exponent < 0 && point > 0 {
+            format!("{}.{}", &digits[..point as usize], &digits[point as usize..])
+        }
+        else if self.exponent < 0 && point > -6 {
+            format!("0.{}{}", "0".repeat((-point) as usize), digits)
+        }
+        else if len == 1 {
+            format!("{}e{}", digits, point - 1)
+        }
+        else {
+            format!("{}.{}e{}", &digits[..1], &digits[1..], point - 1)
+        };
+
+        f.pad_integral(!self.mantissa.is_negative(), "", &body)
+    }
+}
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |x: &Self| if x.is_zero() { 0 } else if x.is_negative() { -1 } else { 1 };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal => (),
+            ord => return ord
+        }
+        if self.is_zero() {
+            return Ordering::Equal;
+        }
+
+        //Same sign: compare magnitudes by their leading digit position first, so the alignment below stays small.
+        let magnitude = match self.top().cmp(&other.top()) {
+            Ordering::Equal => {
+                //With equal leading positions, the exponents differ by less than the longer mantissa's length, so the alignment always fits.
+                let e = self.exponent.min(other.exponent);
+                let a = self.mantissa.abs() * pow10((self.exponent - e) as u64).unwrap();
+                let b = other.mantissa.abs() * pow10((other.exponent - e) as u64).unwrap();
+                a.cmp(&b)
+            },
+            ord => ord
+        };
+
+        if self.is_negative() { magnitude.reverse() } else { magnitude }
+    }
+}
+impl From<BigDecimal> for Vec<BinaryUnit> {
+    /// Encoded as the exponent, followed by the units of the mantissa.
+    fn from(value: BigDecimal) -> Self {
+        let mut result = vec![ binary_unit!(value.exponent) ];
+        result.extend(Vec::<BinaryUnit>::from(value.mantissa));
+
+        result
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for BigDecimal {
+    type Error = Error;
+    fn try_from(mut value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.is_empty() {
+            return Err(conversion_error!("expected at least 3 units, got 0"));
+        }
+
+        let mantissa = BigInt::try_from(value.split_off(1))?;
+        let exponent: i64 = value.pop().unwrap().try_into()?;
+        Self::checked_parts(mantissa, exponent).map_err(|_| conversion_error!("the exponent {} is out of range", exponent))
+    }
+}
+impl VariableType for BigDecimal {
+    fn required_units(&self) -> usize {
+        1 + self.mantissa.required_units()
+    }
+}
+impl From<i64> for BigDecimal {
+    fn from(value: i64) -> Self {
+        Self::from_parts(BigInt::from(value), 0)
+    }
+}
+impl From<BigInt> for BigDecimal {
+    fn from(value: BigInt) -> Self {
+        Self::from_parts(value, 0)
+    }
+}
+impl TryFrom<f64> for BigDecimal {
+    type Error = Error;
+    /// Converts exactly, since every finite `f64` is a terminating decimal.
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            return Err(conversion_error!("{} has no decimal representation", value));
+        }
+        else if value == 0.0 {
+            return Ok(Self::default());
+        }
+
+        let bits = value.to_bits();
+        let biased = ((bits >> 52) & 0x7FF) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        let (significand, exp2) = if biased == 0 { (fraction, -1074) } else { (fraction | (1 << 52), biased - 1075) };
+
+        let mut mantissa = BigInt::from(significand);
+        if value < 0.0 {
+            mantissa = -mantissa;
+        }
+        let power = u32::try_from(exp2.unsigned_abs()).map_err(|_| conversion_error!("the binary exponent of {} is out of range", value))?;
+        if exp2 >= 0 {
+            Ok( Self::from_parts(mantissa * BigInt::from(2i64).pow(power), 0) )
+        }
+        else {
+            //m * 2^-k = m * 5^k * 10^-k
+            Ok( Self::from_parts(mantissa * BigInt::from(5i64).pow(power), exp2) )
+        }
+    }
+}
+impl From<&BigDecimal> for f64 {
+    fn from(value: &BigDecimal) -> Self {
+        //The standard parser is correctly rounded, so go through scientific notation.
+        format!("{}e{}", value.mantissa, value.exponent).parse().unwrap_or(f64::NAN)
+    }
+}
+impl FromStr for BigDecimal {
+    type Err = Error;
+    /// Parses plain or scientific notation, such as `-12.5` or `1.25e-3`. The digits are kept exactly.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let (body, exp) = match trimmed.find(['e', 'E']) {
+            Some(i) => (&trimmed[..i], trimmed[i + 1..].parse::<i64>().map_err(|_| format_error!(s, "invalid exponent"))?),
+            None => (trimmed, 0)
+        };
+        let (negative, body) = match body.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, body.strip_prefix('+').unwrap_or(body))
+        };
+        let (whole, frac) = body.split_once('.').unwrap_or((body, ""));
+        if whole.is_empty() && frac.is_empty() {
+            return Err(format_error!(s, "no digits were given"));
+        }
+        if !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(format_error!(s, "expected only decimal digits around the point"));
+        }
+
+        let mut mantissa: BigInt = format!("0{}{}", whole, frac).parse()?;
+        if negative {
+            mantissa = -mantissa;
+        }
+        let exponent = exp.checked_sub(frac.len() as i64).ok_or_else(|| format_error!(s, "the exponent is out of range"))?;
+        Self::checked_parts(mantissa, exponent).map_err(|_| format_error!(s, "the exponent is out of range"))
+    }
+}
+impl Neg for BigDecimal {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            mantissa: -self.mantissa,
+            exponent: self.exponent
+        }
+    }
+}
+impl Add for BigDecimal {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.add_with(&rhs, session_settings.decimal_precision())
+    }
+}
+impl Sub for BigDecimal {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.sub_with(&rhs, session_settings.decimal_precision())
+    }
+}
+impl Mul for BigDecimal {
+    type Output = Result<Self, Error>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.mul_with(&rhs, session_settings.decimal_precision())
+    }
+}
+impl Div for BigDecimal {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_with(&rhs, session_settings.decimal_precision())
+    }
+}
+
+/// The largest exponent magnitude a `BigDecimal` may have, so that its leading digit position and its written form always fit in an `i64`.
+const MAX_EXPONENT: i64 = 1 << 60;
+/// The largest precision the arithmetic accepts. Scaling to more digits than this would need a power of ten beyond what `BigInt::pow` takes.
+const MAX_PRECISION: usize = u32::MAX as usize;
+
+fn check_precision(precision: usize) -> Result<(), Error> {
+    if precision > MAX_PRECISION {
+        return Err(operation_error!("round", "a precision of {} digits is more than the {} supported", precision, MAX_PRECISION));
+    }
+
+    Ok(())
+}
+/// `10^n`, failing when `n` does not fit in the `u32` that `BigInt::pow` takes.
+fn pow10(n: u64) -> Result<BigInt, Error> {
+    let n = u32::try_from(n).map_err(|_| operation_error!("scale", "10^{} is too large to represent", n))?;
+    Ok( BigInt::from(10i64).pow(n) )
+}
+fn digit_count(m: &BigInt) -> u64 {
+    if m.is_zero() { 0 } else { m.abs().to_string().len() as u64 }
+}
+fn isqrt(n: &BigInt) -> BigInt {
+    if n.is_zero() {
+        return BigInt::zero();
+    }
+
+    //Newton's method from above converges monotonically to the floor of the root.
+    let mut x = pow10(digit_count(n).div_ceil(2)).unwrap();
+    loop {
+        let y = (x.clone() + (n.clone() / x.clone()).unwrap()).div_rem(&BigInt::from(2i64)).unwrap().0;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+/// The number of extra digits the transcendental functions start with, before Ziv's strategy widens them.
+const GUARD_DIGITS: usize = 10;
+
+/// Evaluates `approx` at increasing working precisions until the result is certain to round the same way as the true value.
+/// Each evaluation must be accurate to within a few hundred units in its last place. Fails if the rounding is still uncertain after the last retry, which happens when the true value lies on (or extremely close to) a rounding boundary.
+fn correctly_rounded<F>(precision: usize, approx: F) -> Result<BigDecimal, Error> where F: Fn(usize) -> Result<BigDecimal, Error> {
+    check_precision(precision)?;
+    let mut guard = GUARD_DIGITS;
+    let mut value = approx(precision + guard)?;
+    for _ in 0..6 {
+        if value.is_zero() {
+            return Ok(value);
+        }
+
+        let error = BigDecimal::from_parts(BigInt::one(), value.top() - (precision + guard) as i64 + 3);
+        let low = value.add_exact(&-error.clone())?.round(precision);
+        let high = value.add_exact(&error)?.round(precision);
+        if low == high {
+            return Ok(low);
+        }
+
+        guard *= 2;
+        value = approx(precision + guard)?;
+    }
+
+    Err(operation_error!("round", "could not determine the correctly rounded value of {} to {} digits", value, precision))
+}
+
+/// Generates the variants of the elementary functions that read their precision from the session.
+macro_rules! session_precision {
+    ($($name: ident => $with: ident), *) => {
+        $(
+            pub fn $name(&self) -> Result<Self, Error> {
+                self.$with(session_settings.decimal_precision())
+            }
+        )*
+    }
+}
+
+impl BigDecimal {
+    fn from_parts(mut mantissa: BigInt, mut exponent: i64) -> Self {
+        if mantissa.is_zero() {
+            return Self::default();
+        }
+
+        let ten = BigInt::from(10i64);
+        loop {
+            let (q, r) = mantissa.div_rem(&ten).unwrap();
+            if !r.is_zero() {
+                break;
+            }
+            mantissa = q;
+            exponent += 1;
+        }
+
+        Self {
+            mantissa,
+            exponent
+        }
+    }
+    /// Like `from_parts`, but rejects an exponent outside of `MAX_EXPONENT` before normalizing it.
+    fn checked_parts(mantissa: BigInt, exponent: i64) -> Result<Self, Error> {
+        if !(-MAX_EXPONENT..=MAX_EXPONENT).contains(&exponent) {
+            return Err(operation_error!("scale", "the exponent {} is outside of ±{}", exponent, MAX_EXPONENT));
+        }
+
+        Self::from_parts(mantissa, exponent).in_range()
+    }
+    /// The position of the leading digit, so that `10^top <= |self| < 10^(top + 1)`.
+    fn top(&self) -> i64 {
+        self.exponent + digit_count(&self.mantissa) as i64 - 1
+    }
+    /// Checks that a result stays within `MAX_EXPONENT`. Intermediate values may stray a little past it, since every exponent computed from in-range operands still fits in an `i64`.
+    fn in_range(self) -> Result<Self, Error> {
+        if !self.is_zero() && (self.exponent < -MAX_EXPONENT || self.top() > MAX_EXPONENT) {
+            return Err(operation_error!("scale", "the result's exponent {} is outside of ±{}", self.exponent, MAX_EXPONENT));
+        }
+
+        Ok(self)
+    }
+    /// The exact sum, which fails when the exponents are too far apart to align.
+    fn add_exact(&self, rhs: &Self) -> Result<Self, Error> {
+        let e = self.exponent.min(rhs.exponent);
+        let a = self.mantissa.clone() * pow10(self.exponent.abs_diff(e))?;
+        let b = rhs.mantissa.clone() * pow10(rhs.exponent.abs_diff(e))?;
+
+        Ok( Self::from_parts(a + b, e) )
+    }
+    fn halve(&self, precision: usize) -> Result<Self, Error> {
+        self.mul_with(&Self::from_parts(BigInt::from(5i64), -1), precision)
+    }
+    fn small(n: i64) -> Self {
+        Self::from(n)
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+    pub fn one() -> Self {
+        Self::from(1i64)
+    }
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.is_negative()
+    }
+    pub fn abs(&self) -> Self {
+        Self {
+            mantissa: self.mantissa.abs(),
+            exponent: self.exponent
+        }
+    }
+    pub fn to_f64(&self) -> f64 {
+        f64::from(self)
+    }
+    /// The number of significant digits currently stored.
+    pub fn significant_digits(&self) -> usize {
+        digit_count(&self.mantissa) as usize
+    }
+
+    /// Rounds to `precision` significant digits, with ties going to the even neighbour.
+    pub fn round(&self, precision: usize) -> Self {
+        let precision = precision.max(1) as u64;
+        let digits = digit_count(&self.mantissa);
+        if digits <= precision {
+            return self.clone();
+        }
+
+        let drop = digits - precision;
+        let unit = pow10(drop).unwrap();
+        let (mut q, r) = self.mantissa.div_rem(&unit).unwrap();
+        let step = if self.is_negative() { BigInt::from(-1i64) } else { BigInt::one() };
+        match (r.abs() * BigInt::from(2i64)).cmp(&unit) {
+            Ordering::Greater => q = q + step,
+            Ordering::Equal if !(q.clone() % BigInt::from(2i64)).unwrap().is_zero() => q = q + step,
+            _ => ()
+        }
+
+        Self::from_parts(q, self.exponent + drop as i64)
+    }
+    /// Rounds to the nearest integer, with ties going to the even neighbour. Fails if the exponent is too large to expand.
+    pub fn round_to_integer(&self) -> Result<BigInt, Error> {
+        if self.exponent >= 0 {
+            return Ok( self.mantissa.clone() * pow10(self.exponent as u64)? );
+        }
+
+        let digits = (self.top() + 1).max(0) as usize;
+        let rounded = if digits == 0 {
+            //Below one half in magnitude, unless exactly 0.5...
+            let half = Self::from_parts(BigInt::from(5i64), -1);
+            if self.abs() > half { Self::from(if self.is_negative() { -1i64 } else { 1 }) } else { Self::zero() }
+        }
+        else {
+            self.round(digits)
+        };
+
+        Ok( rounded.mantissa * pow10(rounded.exponent.max(0) as u64)? )
+    }
+
+    pub fn add_with(&self, rhs: &Self, precision: usize) -> Result<Self, Error> {
+        check_precision(precision)?;
+        if self.is_zero() || rhs.is_zero() {
+            return Ok( if self.is_zero() { rhs.round(precision) } else { self.round(precision) } );
+        }
+
+        //When one operand lies entirely below the rounding position of the other, it only matters through its sign.
+        //Replacing it by a tiny stand in keeps the exact sum (and so the rounding) from growing without bound.
+        let (large, small) = if self.top() >= rhs.top() { (self, rhs) } else { (rhs, self) };
+        let limit = large.exponent.min(large.top() - precision as i64 - 2);
+        if small.top() < limit - 1 {
+            let sign = if small.is_negative() { -1i64 } else { 1 };
+            return large.add_exact(&Self::from_parts(BigInt::from(sign), limit - 2))?.round(precision).in_range();
+        }
+
+        self.add_exact(rhs)?.round(precision).in_range()
+    }
+    pub fn sub_with(&self, rhs: &Self, precision: usize) -> Result<Self, Error> {
+        self.add_with(&-rhs.clone(), precision)
+    }
+    /// The rounded product, which fails when its exponent is out of range.
+    pub fn mul_with(&self, rhs: &Self, precision: usize) -> Result<Self, Error> {
+        check_precision(precision)?;
+        Self::from_parts(self.mantissa.clone() * rhs.mantissa.clone(), self.exponent + rhs.exponent).round(precision).in_range()
+    }
+    pub fn div_with(&self, rhs: &Self, precision: usize) -> Result<Self, Error> {
+        check_precision(precision)?;
+        if rhs.is_zero() {
+            return Err(operator_error!('/', self, rhs));
+        }
+        else if self.is_zero() {
+            return Ok(Self::zero());
+        }
+
+        //Scale so the quotient carries at least two digits past the precision, then record any remainder as a sticky digit.
+        let shift = (precision as i64 + 2 + digit_count(&rhs.mantissa) as i64 - digit_count(&self.mantissa) as i64 + 1).max(0);
+        let (mut q, r) = (self.mantissa.clone() * pow10(shift as u64)?).div_rem(&rhs.mantissa)?;
+        let mut exponent = self.exponent - rhs.exponent - shift;
+        if !r.is_zero() {
+            let sticky = if q.is_negative() { BigInt::from(-1i64) } else { BigInt::one() };
+            q = q * BigInt::from(10i64) + sticky;
+            exponent -= 1;
+        }
+
+        Self::from_parts(q, exponent).round(precision).in_range()
+    }
+    pub fn sqrt_with(&self, precision: usize) -> Result<Self, Error> {
+        check_precision(precision)?;
+        if self.is_negative() {
+            return Err(operation_error!("sqrt", "{} is negative", self));
+        }
+        else if self.is_zero() {
+            return Ok(Self::zero());
+        }
+
+        let mut shift = (2 * (precision as i64 + 2) - digit_count(&self.mantissa) as i64 + 1).max(0);
+        if (self.exponent - shift).rem_euclid(2) != 0 {
+            shift += 1;
+        }
+
+        let n = self.mantissa.clone() * pow10(shift as u64)?;
+        let mut root = isqrt(&n);
+        let mut exponent = (self.exponent - shift) / 2;
+        if root.clone() * root.clone() != n {
+            root = root * BigInt::from(10i64) + BigInt::one();
+            exponent -= 1;
+        }
+
+        Ok( Self::from_parts(root, exponent).round(precision) )
+    }
+
+    /// `pi` to `precision` significant digits.
+    pub fn pi_with(precision: usize) -> Result<Self, Error> {
+        correctly_rounded(precision, Self::pi_approx)
+    }
+    pub fn pi() -> Result<Self, Error> {
+        Self::pi_with(session_settings.decimal_precision())
+    }
+
+    pub fn exp_with(&self, precision: usize) -> Result<Self, Error> {
+        if self.is_zero() {
+            return Ok(Self::one());
+        }
+        else if self.top() > 15 {
+            return Err(operation_error!("exp", "{} is too large in magnitude", self));
+        }
+
+        correctly_rounded(precision, |wp| self.exp_approx(wp))
+    }
+    pub fn ln_with(&self, precision: usize) -> Result<Self, Error> {
+        if self.is_negative() || self.is_zero() {
+            return Err(operation_error!("ln", "{} is not positive", self));
+        }
+        else if *self == Self::one() {
+            return Ok(Self::zero());
+        }
+
+        correctly_rounded(precision, |wp| self.ln_approx(wp))
+    }
+    pub fn sin_with(&self, precision: usize) -> Result<Self, Error> {
+        if self.is_zero() {
+            return Ok(Self::zero());
+        }
+
+        correctly_rounded(precision, |wp| Ok(self.sin_cos_approx(wp)?.0))
+    }
+    pub fn cos_with(&self, precision: usize) -> Result<Self, Error> {
+        correctly_rounded(precision, |wp| Ok(self.sin_cos_approx(wp)?.1))
+    }
+    pub fn tan_with(&self, precision: usize) -> Result<Self, Error> {
+        if self.is_zero() {
+            return Ok(Self::zero());
+        }
+
+        correctly_rounded(precision, |wp| {
+            let (sin, cos) = self.sin_cos_approx(wp)?;
+            sin.div_with(&cos, wp)
+        })
+    }
+    pub fn atan_with(&self, precision: usize) -> Result<Self, Error> {
+        correctly_rounded(precision, |wp| self.atan_approx(wp))
+    }
+    pub fn asin_with(&self, precision: usize) -> Result<Self, Error> {
+        let one = Self::one();
+        if self.abs() > one {
+            return Err(operation_error!("asin", "{} is outside of [-1, 1]", self));
+        }
+        else if self.abs() == one {
+            let half_pi = Self::pi_with(precision + 2)?.halve(precision + 2)?;
+            return Ok( (if self.is_negative() { -half_pi } else { half_pi }).round(precision) );
+        }
+
+        //asin(x) = atan(x / sqrt((1 - x)(1 + x))), where both factors are computed exactly.
+        correctly_rounded(precision, |wp| {
+            let denom = one.add_exact(&-self.clone())?.mul_with(&one.add_exact(self)?, wp)?.sqrt_with(wp)?;
+            self.div_with(&denom, wp)?.atan_approx(wp)
+        })
+    }
+    pub fn acos_with(&self, precision: usize) -> Result<Self, Error> {
+        let one = Self::one();
+        if self.abs() > one {
+            return Err(operation_error!("acos", "{} is outside of [-1, 1]", self));
+        }
+        else if *self == -one.clone() {
+            return Self::pi_with(precision);
+        }
+
+        //acos(x) = 2 atan(sqrt((1 - x) / (1 + x))), which avoids cancellation near both ends.
+        correctly_rounded(precision, |wp| {
+            let ratio = one.add_exact(&-self.clone())?.div_with(&one.add_exact(self)?, wp)?;
+            ratio.sqrt_with(wp)?.atan_approx(wp)?.mul_with(&Self::small(2), wp)
+        })
+    }
+
+    session_precision!(sqrt => sqrt_with, exp => exp_with, ln => ln_with, sin => sin_with, cos => cos_with, tan => tan_with, atan => atan_with, asin => asin_with, acos => acos_with);
+
+    //The approximations below are accurate to a few units in the last place of the working precision `wp`.
+
+    /// `sum (-1)^n z^(2n+1) / (2n+1)`, for small `|z|`.
+    fn atan_series(z: &Self, wp: usize) -> Result<Self, Error> {
+        let z2 = z.mul_with(z, wp)?;
+        let mut power = z.clone();
+        let mut sum = z.clone();
+        let limit = -(wp as i64) - 2 + z.top().min(0);
+        for n in 1.. {
+            power = -power.mul_with(&z2, wp)?;
+            let term = power.div_with(&Self::small(2 * n + 1), wp)?;
+            if term.is_zero() || term.top() < limit {
+                break;
+            }
+            sum = sum.add_with(&term, wp)?;
+        }
+
+        Ok( sum )
+    }
+    /// `sum z^(2n+1) / (2n+1)`, for small `|z|`.
+    fn atanh_series(z: &Self, wp: usize) -> Result<Self, Error> {
+        let z2 = z.mul_with(z, wp)?;
+        let mut power = z.clone();
+        let mut sum = z.clone();
+        let limit = -(wp as i64) - 2 + z.top().min(0);
+        for n in 1.. {
+            power = power.mul_with(&z2, wp)?;
+            let term = power.div_with(&Self::small(2 * n + 1), wp)?;
+            if term.is_zero() || term.top() < limit {
+                break;
+            }
+            sum = sum.add_with(&term, wp)?;
+        }
+
+        Ok( sum )
+    }
+    fn pi_approx(wp: usize) -> Result<Self, Error> {
+        //Machin's formula: pi = 16 atan(1/5) - 4 atan(1/239)
+        let wp = wp + 3;
+        let a = Self::atan_series(&Self::one().div_with(&Self::small(5), wp)?, wp)?;
+        let b = Self::atan_series(&Self::one().div_with(&Self::small(239), wp)?, wp)?;
+
+        a.mul_with(&Self::small(16), wp)?.sub_with(&b.mul_with(&Self::small(4), wp)?, wp)
+    }
+    fn exp_approx(&self, wp: usize) -> Result<Self, Error> {
+        //Halve the argument until it is tiny, sum the Taylor series, then square back up. Each squaring doubles the relative error, so carry extra digits for them.
+        let halvings = (((self.top() + 1).max(0) as f64 * 3.33).ceil() as u32) + 8;
+        let wp = wp + (halvings as f64 * 0.302).ceil() as usize + 3;
+        let r = self.div_with(&Self::from(BigInt::from(2i64).pow(halvings)), wp)?;
+
+        let mut term = Self::one();
+        let mut sum = Self::one();
+        for n in 1.. {
+            term = term.mul_with(&r, wp)?.div_with(&Self::small(n), wp)?;
+            if term.is_zero() || term.top() < -(wp as i64) - 2 {
+                break;
+            }
+            sum = sum.add_with(&term, wp)?;
+        }
+        for _ in 0..halvings {
+            sum = sum.mul_with(&sum, wp)?;
+        }
+
+        Ok( sum )
+    }
+    fn ln_approx(&self, wp: usize) -> Result<Self, Error> {
+        let half = Self::from_parts(BigInt::from(5i64), -1);
+        let two = Self::small(2);
+        let ln_near_one = |y: &Self, wp: usize| {
+            //ln(y) = 2 atanh((y - 1) / (y + 1))
+            let z = y.add_exact(&-Self::one())?.div_with(&y.add_exact(&Self::one())?, wp)?;
+            Self::atanh_series(&z, wp)?.mul_with(&two, wp)
+        };
+        if *self >= half && *self < two {
+            return ln_near_one(self, wp + 3);
+        }
+
+        //Otherwise, self = y * 2^j * 10^e with y in [0.75, 1.5). The terms can cancel by about as many digits as e has, so carry those too.
+        let e = self.top();
+        let wp = wp + 5 + (e.unsigned_abs().max(1) as f64).log10().ceil() as usize;
+        let mut y = Self::from_parts(self.mantissa.clone(), self.exponent - e);
+        let mut j = 0i64;
+        let three_halves = Self::from_parts(BigInt::from(15i64), -1);
+        while y >= three_halves {
+            y = y.halve(wp + 2)?;
+            j += 1;
+        }
+
+        let ln2 = Self::atanh_series(&Self::one().div_with(&Self::small(3), wp)?, wp)?.mul_with(&two, wp)?;
+        let ln10 = ln2.mul_with(&Self::small(3), wp)?.add_with(&Self::atanh_series(&Self::one().div_with(&Self::small(9), wp)?, wp)?.mul_with(&two, wp)?, wp)?;
+
+        ln10.mul_with(&Self::small(e), wp)?
+            .add_with(&ln2.mul_with(&Self::small(j), wp)?, wp)?
+            .add_with(&ln_near_one(&y, wp)?, wp)
+    }
+    /// Returns `(sin, cos)` of `self`.
+    fn sin_cos_approx(&self, wp: usize) -> Result<(Self, Self), Error> {
+        if self.is_zero() {
+            return Ok( (Self::zero(), Self::one()) );
+        }
+
+        //Reduce by multiples of pi/2. When the argument is close to one of them, the reduction cancels digits, so retry with enough extra precision to cover the loss.
+        let mut extra = self.top().max(0) as usize + 5;
+        let (k, r, wp) = loop {
+            let wp2 = wp + extra;
+            let half_pi = Self::pi_approx(wp2)?.halve(wp2)?;
+            let k = self.div_with(&half_pi, wp2)?.round_to_integer()?;
+            let multiple = half_pi.mul_with(&Self::from(k.clone()), wp2)?;
+            let r = self.sub_with(&multiple, wp2)?;
+
+            let lost = if r.is_zero() { wp } else { (self.top() - r.top()).max(0) as usize };
+            if lost + 5 <= extra {
+                break (k, r, wp2);
+            }
+            extra = lost + 10;
+        };
+
+        let r2 = r.mul_with(&r, wp)?;
+        let series = |start: Self, offset: i64| {
+            let mut term = start.clone();
+            let mut sum = start;
+            for n in 1.. {
+                term = -term.mul_with(&r2, wp)?.div_with(&Self::small((2 * n + offset) * (2 * n + offset - 1)), wp)?;
+                if term.is_zero() || term.top() < sum.top() - wp as i64 - 2 {
+                    break;
+                }
+                sum = sum.add_with(&term, wp)?;
+            }
+            Ok::<Self, Error>( sum )
+        };
+        let sin = if r.is_zero() { Self::zero() } else { series(r.clone(), 1)? };
+        let cos = series(Self::one(), 0)?;
+
+        Ok(
+            match (k % BigInt::from(4i64)).ok().and_then(|m| i64::try_from(&m).ok()).unwrap_or(0).rem_euclid(4) {
+                0 => (sin, cos),
+                1 => (cos, -sin),
+                2 => (-sin, -cos),
+                _ => (-cos, sin)
+            }
+        )
+    }
+    fn atan_approx(&self, wp: usize) -> Result<Self, Error> {
+        if self.is_zero() {
+            return Ok( Self::zero() );
+        }
+
+        let wp = wp + 3;
+        let one = Self::one();
+        if self.abs() > one {
+            //atan(x) = sign(x) pi/2 - atan(1/x)
+            let half_pi = Self::pi_approx(wp)?.halve(wp)?;
+            let inner = one.div_with(self, wp)?.atan_approx(wp)?;
+            let half_pi = if self.is_negative() { -half_pi } else { half_pi };
+            return half_pi.sub_with(&inner, wp);
+        }
+
+        //atan(x) = 2 atan(x / (1 + sqrt(1 + x^2))), applied three times brings |x| under 0.1.
+        let mut x = self.clone();
+        for _ in 0..3 {
+            let root = one.add_with(&x.mul_with(&x, wp)?, wp)?.sqrt_with(wp)?;
+            x = x.div_with(&one.add_with(&root, wp)?, wp)?;
+        }
+
+        Self::atan_series(&x, wp)?.mul_with(&Self::small(8), wp)
+    }
+}
+
+#[test]
+fn test_bigdecimal_functions() {
+    let d = |s: &str| s.parse::<BigDecimal>().unwrap();
+    let p = 50;
+
+    assert_eq!(d("1").div_with(&d("3"), p).unwrap(), d("0.33333333333333333333333333333333333333333333333333"));
+    assert_eq!(d("2").div_with(&d("3"), 5).unwrap(), d("0.66667"));
+    assert_eq!(d("2.5").round(1), d("2"));
+    assert_eq!(d("3.5").round(1), d("4"));
+    assert_eq!(d("1e60").add_with(&d("1e-60"), p).unwrap(), d("1e60"));
+    assert_eq!(d("1").sub_with(&d("1e-60"), p).unwrap(), d("1"));
+    assert_eq!(d("1").sub_with(&d("1e-50"), p).unwrap(), d("0.99999999999999999999999999999999999999999999999999"));
+    assert!(d("1").add_with(&d("1e-4300000000"), 5_000_000_000).is_err());
+    assert!(d("1e1000000000000000000").mul_with(&d("1e1000000000000000000"), p).is_err());
+    assert!("1.5e-9223372036854775808".parse::<BigDecimal>().is_err());
+    assert!("10e9223372036854775807".parse::<BigDecimal>().is_err());
+    assert!(d("1").div_with(&BigDecimal::zero(), p).is_err());
+    assert_eq!(d("2.5e3").round_to_integer().unwrap(), BigInt::from(2500i64));
+    assert!(d("1e5000000000").round_to_integer().is_err());
+    assert!(d("1e-5000000000").asin_with(p).is_err());
+    assert!(d("1").div_with(&d("3"), 5_000_000_000).is_err());
+
+    assert_eq!(d("2").sqrt_with(p).unwrap(), d("1.4142135623730950488016887242096980785696718753769"));
+    assert_eq!(d("1").exp_with(p).unwrap(), d("2.718281828459045235360287471352662497757247093700"));
+    assert_eq!(d("-100").exp_with(p).unwrap(), d("3.7200759760208359629596958038631183373588922923768e-44"));
+    assert_eq!(d("10").ln_with(p).unwrap(), d("2.3025850929940456840179914546843642076011014886288"));
+    assert_eq!(d("0.999").ln_with(p).unwrap(), d("-0.0010005003335835335001429822540683449607552052504344"));
+    assert_eq!(BigDecimal::pi_with(p).unwrap(), d("3.1415926535897932384626433832795028841971693993751"));
+    assert_eq!(d("1").sin_with(p).unwrap(), d("0.84147098480789650665250232163029899962256306079837"));
+    assert_eq!(d("355").cos_with(20).unwrap(), d("-0.99999999954565898017"));
+    assert_eq!(d("1").atan_with(30).unwrap(), d("0.785398163397448309615660845820"));
+    assert_eq!(d("0.5").asin_with(30).unwrap(), d("0.523598775598298873077107230547"));
+    assert!(d("-1").ln_with(p).is_err());
+    assert!(correctly_rounded(1, |_| Ok(d("2.5"))).is_err());
+
+    assert_eq!(format!("{}", d("-0.00125")), "-0.00125");
+    assert_eq!(format!("{}", d("1.5e-20")), "1.5e-20");
+    assert_eq!(BigDecimal::try_from(0.1).unwrap(), d("0.1000000000000000055511151231257827021181583404541015625"));
+    assert_eq!(d("0.1").to_f64(), 0.1);
+
+    let units: Vec<BinaryUnit> = d("-12.75").into();
+    assert_eq!(BigDecimal::try_from(units).unwrap(), d("-12.75"));
+    let mut units: Vec<BinaryUnit> = d("10").into();
+    units[0] = binary_unit!(i64::MAX);
+    assert!(BigDecimal::try_from(units).is_err());
+}
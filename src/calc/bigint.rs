@@ -0,0 +1,476 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, LowerHex, UpperHex};
+use std::ops::{Add, Sub, Mul, Div, Rem, Neg};
+use std::str::FromStr;
+
+use crate::{binary_unit, conversion_error, format_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::variable_type::VariableType;
+
+/// An integer of unbounded size, stored as a sign and a little endian list of base `2^32` limbs.
+/// The limbs never carry leading zeroes, and zero is never negative, so equal values always compare equal.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>
+}
+impl Debug for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(BigInt:{})", self)
+    }
+}
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        //Peel off nine decimal digits at a time, least significant first.
+        let mut chunks = vec![];
+        let mut rest = self.limbs.clone();
+        while !rest.is_empty() {
+            chunks.push(div_rem_small(&mut rest, 1_000_000_000));
+        }
+
+        let mut digits = chunks.last().map(|c| c.to_string()).unwrap_or_else(|| "0".to_string());
+        for chunk in chunks.iter().rev().skip(1) {
+            digits.push_str(&format!("{:09}", chunk));
+        }
+
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+impl LowerHex for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(!self.negative, "0x", &self.hex_digits())
+    }
+}
+impl UpperHex for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(!self.negative, "0x", &self.hex_digits().to_uppercase())
+    }
+}
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs)
+        }
+    }
+}
+impl From<BigInt> for Vec<BinaryUnit> {
+    /// Encoded as the sign, the number of limbs, and then each limb.
+    fn from(value: BigInt) -> Self {
+        let mut result = vec![ binary_unit!(value.negative as u8), binary_unit!(value.limbs.len() as u64) ];
+        result.extend(value.limbs.into_iter().map(|l| binary_unit!(l)));
+
+        result
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for BigInt {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.len() < 2 {
+            return Err(conversion_error!("expected at least 2 units, got {}", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let negative: u8 = iter.next().unwrap().try_into()?;
+        let len: u64 = iter.next().unwrap().try_into()?;
+        if iter.len() as u64 != len {
+            return Err(conversion_error!("expected {} limbs, got {}", len, iter.len()));
+        }
+
+        let limbs = iter.map(|u| u.try_into()).collect::<Result<Vec<u32>, Error>>()?;
+        Ok( Self::from_parts(negative != 0, limbs) )
+    }
+}
+impl VariableType for BigInt {
+    fn required_units(&self) -> usize {
+        2 + self.limbs.len()
+    }
+}
+impl From<u64> for BigInt {
+    fn from(value: u64) -> Self {
+        Self::from_parts(false, vec![ value as u32, (value >> 32) as u32 ])
+    }
+}
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let mut result = Self::from(value.unsigned_abs());
+        result.negative = value < 0;
+        result
+    }
+}
+impl TryFrom<&BigInt> for i64 {
+    type Error = Error;
+    fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+        if value.limbs.len() > 2 {
+            return Err(conversion_error!("{} does not fit in 64 bits", value));
+        }
+
+        let magnitude = value.limbs.iter().rev().fold(0u64, |acc, &l| (acc << 32) | l as u64);
+        if value.negative {
+            0i64.checked_sub_unsigned(magnitude).ok_or_else(|| conversion_error!("{} does not fit in 64 bits", value))
+        }
+        else {
+            i64::try_from(magnitude).map_err(|_| conversion_error!("{} does not fit in 64 bits", value))
+        }
+    }
+}
+impl FromStr for BigInt {
+    type Err = Error;
+    /// Parses an optionally signed decimal string, or a hexadecimal one when prefixed by `0x`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let (negative, body) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed))
+        };
+        let (radix, body) = match body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")) {
+            Some(rest) => (16u32, rest),
+            None => (10u32, body)
+        };
+        if body.is_empty() {
+            return Err(format_error!(s, "no digits were given"));
+        }
+
+        let mut limbs = vec![];
+        for c in body.chars().filter(|&c| c != '_') {
+            let digit = c.to_digit(radix).ok_or_else(|| format_error!(s, "'{}' is not a base {} digit", c, radix))?;
+            mul_add_small(&mut limbs, radix, digit);
+        }
+
+        Ok( Self::from_parts(negative, limbs) )
+    }
+}
+impl Neg for BigInt {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::from_parts(!self.negative, self.limbs)
+    }
+}
+impl Add for BigInt {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.signed_add(&rhs, false)
+    }
+}
+impl Sub for BigInt {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.signed_add(&rhs, true)
+    }
+}
+impl Mul for BigInt {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from_parts(self.negative != rhs.negative, mul_mag(&self.limbs, &rhs.limbs))
+    }
+}
+impl Div for BigInt {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).map(|(q, _)| q)
+    }
+}
+impl Rem for BigInt {
+    type Output = Result<Self, Error>;
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).map(|(_, r)| r)
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &l) in long.iter().enumerate() {
+        let sum = l as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry != 0 {
+        result.push(carry as u32);
+    }
+
+    result
+}
+/// `a - b`, where `a` must be at least as large as `b`.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &l) in a.iter().enumerate() {
+        let mut diff = l as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+
+    trim(&mut result);
+    result
+}
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+
+    trim(&mut result);
+    result
+}
+fn mul_add_small(limbs: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for l in limbs.iter_mut() {
+        let t = *l as u64 * factor as u64 + carry;
+        *l = t as u32;
+        carry = t >> 32;
+    }
+    if carry != 0 {
+        limbs.push(carry as u32);
+    }
+}
+/// Divides `limbs` in place by a single limb, returning the remainder.
+fn div_rem_small(limbs: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for l in limbs.iter_mut().rev() {
+        let cur = (rem << 32) | *l as u64;
+        *l = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+
+    trim(limbs);
+    rem as u32
+}
+/// Long division of magnitudes (Knuth's algorithm D). `v` must be non-zero.
+fn div_rem_mag(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(u, v) == Ordering::Less {
+        return (vec![], u.to_vec());
+    }
+    if v.len() == 1 {
+        let mut q = u.to_vec();
+        let r = div_rem_small(&mut q, v[0]);
+        let mut r = vec![r];
+        trim(&mut r);
+        return (q, r);
+    }
+
+    //Normalize so the divisor's top limb has its high bit set, which keeps each quotient estimate within two of the truth.
+    let n = v.len();
+    let m = u.len() - n;
+    let shift = v[n - 1].leading_zeros();
+    let shl = |x: &[u32], extra: bool| -> Vec<u32> {
+        let mut out = Vec::with_capacity(x.len() + 1);
+        let mut carry = 0u32;
+        for &l in x {
+            out.push(if shift == 0 { l } else { (l << shift) | carry });
+            carry = if shift == 0 { 0 } else { l >> (32 - shift) };
+        }
+        if extra {
+            out.push(carry);
+        }
+        out
+    };
+    let vn = shl(v, false);
+    let mut un = shl(u, true);
+
+    const BASE: u64 = 1 << 32;
+    let mut q = vec![0u32; m + 1];
+    for j in (0..=m).rev() {
+        let num = ((un[j + n] as u64) << 32) | un[j + n - 1] as u64;
+        let mut qhat = num / vn[n - 1] as u64;
+        let mut rhat = num % vn[n - 1] as u64;
+        while qhat >= BASE || qhat * vn[n - 2] as u64 > ((rhat << 32) | un[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u64;
+            if rhat >= BASE {
+                break;
+            }
+        }
+
+        //Multiply and subtract, then add back once if the estimate was one too large.
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let p = qhat * vn[i] as u64;
+            let t = un[i + j] as i64 - borrow - (p & 0xFFFF_FFFF) as i64;
+            un[i + j] = t as u32;
+            borrow = (p >> 32) as i64 - (t >> 32);
+        }
+        let t = un[j + n] as i64 - borrow;
+        un[j + n] = t as u32;
+
+        q[j] = qhat as u32;
+        if t < 0 {
+            q[j] = q[j].wrapping_sub(1);
+            let mut carry = 0u64;
+            for i in 0..n {
+                let s = un[i + j] as u64 + vn[i] as u64 + carry;
+                un[i + j] = s as u32;
+                carry = s >> 32;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+    }
+
+    let mut r: Vec<u32> = (0..n).map(|i| {
+        if shift == 0 { un[i] } else { (un[i] >> shift) | (un[i + 1] << (32 - shift)) }
+    }).collect();
+    trim(&mut q);
+    trim(&mut r);
+    (q, r)
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        Self {
+            negative: negative && !limbs.is_empty(),
+            limbs
+        }
+    }
+    fn signed_add(&self, rhs: &Self, subtract: bool) -> Self {
+        let rhs_negative = rhs.negative != subtract;
+        if self.negative == rhs_negative {
+            return Self::from_parts(self.negative, add_mag(&self.limbs, &rhs.limbs));
+        }
+
+        match cmp_mag(&self.limbs, &rhs.limbs) {
+            Ordering::Less => Self::from_parts(rhs_negative, sub_mag(&rhs.limbs, &self.limbs)),
+            _ => Self::from_parts(self.negative, sub_mag(&self.limbs, &rhs.limbs))
+        }
+    }
+    fn hex_digits(&self) -> String {
+        let mut iter = self.limbs.iter().rev();
+        let mut result = match iter.next() {
+            Some(top) => format!("{:x}", top),
+            None => "0".to_string()
+        };
+        for l in iter {
+            result.push_str(&format!("{:08x}", l));
+        }
+
+        result
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+    pub fn one() -> Self {
+        Self::from(1i64)
+    }
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.limbs.clone())
+    }
+    /// The number of bits needed to hold the magnitude.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0
+        }
+    }
+    /// The closest `f64`, which becomes infinite once the value passes `f64::MAX`.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.limbs.iter().rev().fold(0.0, |acc, &l| acc * 4294967296.0 + l as f64);
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    /// Truncating division, returning the quotient and a remainder with the sign of `self`, like the `/` and `%` operators on primitive integers.
+    pub fn div_rem(&self, rhs: &Self) -> Result<(Self, Self), Error> {
+        if rhs.is_zero() {
+            return Err(operator_error!('/', self, rhs));
+        }
+
+        let (q, r) = div_rem_mag(&self.limbs, &rhs.limbs);
+        Ok( (Self::from_parts(self.negative != rhs.negative, q), Self::from_parts(self.negative, r)) )
+    }
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base.clone();
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.clone() * base;
+            }
+        }
+
+        result
+    }
+    /// The greatest common divisor, which is never negative.
+    pub fn gcd(&self, rhs: &Self) -> Self {
+        let (mut a, mut b) = (self.limbs.clone(), rhs.limbs.clone());
+        while !b.is_empty() {
+            let (_, r) = div_rem_mag(&a, &b);
+            (a, b) = (b, r);
+        }
+
+        Self::from_parts(false, a)
+    }
+}
+
+#[test]
+fn test_bigint_arithmetic() {
+    let big = |s: &str| s.parse::<BigInt>().unwrap();
+
+    let factorial = (1..=30i64).fold(BigInt::one(), |acc, k| acc * BigInt::from(k));
+    assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+
+    let two_200 = BigInt::from(2i64).pow(200);
+    assert_eq!(two_200.to_string(), "1606938044258990275541962092341162602522202993782792835301376");
+    assert_eq!(format!("{:x}", two_200), format!("1{}", "0".repeat(50)));
+    assert_eq!(format!("{:#X}", big("-48879")), "-0xBEEF");
+    assert_eq!(big("0xdeadBEEF"), BigInt::from(3735928559i64));
+
+    let a = big("123456789012345678901234567890");
+    let b = big("-987654321098765");
+    assert_eq!((a.clone() + b.clone()).to_string(), "123456789012344691246913469125");
+    assert_eq!((b.clone() - a.clone()).to_string(), "-123456789012346666555555666655");
+    assert_eq!((a.clone() * b.clone()).to_string(), "-121932631137021741879287174187806784787655850");
+
+    let (q, r) = a.div_rem(&b).unwrap();
+    assert_eq!(q.to_string(), "-124999998860937");
+    assert_eq!(r.to_string(), "547854957125085");
+    assert_eq!(q * b.clone() + r, a);
+    assert!((a.clone() / BigInt::zero()).is_err());
+    assert_eq!((BigInt::from(-7i64) % BigInt::from(2i64)).unwrap(), BigInt::from(-1i64));
+
+    assert_eq!(factorial.gcd(&two_200), BigInt::from(2i64).pow(26));
+    assert_eq!(i64::try_from(&BigInt::from(i64::MIN)).unwrap(), i64::MIN);
+    assert!(i64::try_from(&two_200).is_err());
+    assert!(b < BigInt::zero() && BigInt::zero() < a);
+
+    let units: Vec<BinaryUnit> = b.clone().into();
+    assert_eq!(units.len(), b.required_units());
+    assert_eq!(BigInt::try_from(units).unwrap(), b);
+}
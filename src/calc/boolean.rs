@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::{binary_unit, conversion_error, core::{io::BinaryUnit, errors::Error}};
+use super::variable_type::VariableType;
+
+/// A truth value.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Boolean {
+    data: bool
+}
+impl Debug for Boolean {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Boolean:{})", self.data)
+    }
+}
+impl Display for Boolean {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data)
+    }
+}
+impl From<bool> for Boolean {
+    fn from(value: bool) -> Self {
+        Self {
+            data: value
+        }
+    }
+}
+impl From<Boolean> for bool {
+    fn from(value: Boolean) -> Self {
+        value.data
+    }
+}
+impl From<Boolean> for Vec<BinaryUnit> {
+    fn from(value: Boolean) -> Self {
+        vec![ binary_unit!(value.data as u8) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Boolean {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        match value.into_iter().next() {
+            None => Err(conversion_error!("expected at least one unit, but got none")),
+            Some(d) => {
+                let as_byte: u8 = d.try_into()?;
+                Ok(Self::from(as_byte != 0))
+            }
+        }
+    }
+}
+impl VariableType for Boolean {
+    fn required_units(&self) -> usize {
+        1usize
+    }
+}
+impl Not for Boolean {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        Self::from(!self.data)
+    }
+}
+impl BitAnd for Boolean {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::from(self.data && rhs.data)
+    }
+}
+impl BitOr for Boolean {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::from(self.data || rhs.data)
+    }
+}
+impl BitXor for Boolean {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self::from(self.data != rhs.data)
+    }
+}
+impl Boolean {
+    pub fn value(&self) -> bool {
+        self.data
+    }
+}
+
+/// A comparison operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual
+}
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterEqual => ">="
+        }
+    }
+    /// Whether the operator needs an ordering, rather than just equality.
+    pub fn is_ordering(&self) -> bool {
+        !matches!(self, Self::Equal | Self::NotEqual)
+    }
+    /// Applies the operator to the result of comparing two values. Unordered values (such as `NaN`) are only ever not equal.
+    pub fn evaluate(&self, ordering: Option<Ordering>) -> bool {
+        match (self, ordering) {
+            (Self::NotEqual, None) => true,
+            (_, None) => false,
+            (Self::Equal, Some(o)) => o == Ordering::Equal,
+            (Self::NotEqual, Some(o)) => o != Ordering::Equal,
+            (Self::Less, Some(o)) => o == Ordering::Less,
+            (Self::LessEqual, Some(o)) => o != Ordering::Greater,
+            (Self::Greater, Some(o)) => o == Ordering::Greater,
+            (Self::GreaterEqual, Some(o)) => o != Ordering::Less
+        }
+    }
+}
+
+#[test]
+fn test_comparisons_and_logic() {
+    use super::variable_data::{VariableData, Scalar, Complex, Rational, BigInt, BigDecimal, Integer, Quantity};
+    let t = VariableData::Bool(Boolean::from(true));
+    let f = VariableData::Bool(Boolean::from(false));
+    let scalar = |x: f64| VariableData::Scalar(Scalar::from(x));
+
+    assert_eq!(scalar(4.0).compare(&scalar(3.0), Comparison::Greater).unwrap(), t);
+    assert_eq!(scalar(0.1 + 0.2).compare(&scalar(0.3), Comparison::Equal).unwrap(), f);
+    assert_eq!(scalar(0.1 + 0.2).compare_with(&scalar(0.3), Comparison::Equal, 1e-12).unwrap(), t);
+    assert_eq!(scalar(f64::NAN).compare(&scalar(1.0), Comparison::NotEqual).unwrap(), t);
+
+    let third = VariableData::Rational(Rational::new(1, 3).unwrap());
+    assert_eq!(third.compare(&VariableData::Rational(Rational::new(2, 6).unwrap()), Comparison::Equal).unwrap(), t);
+    assert_eq!(third.compare(&scalar(0.3), Comparison::GreaterEqual).unwrap(), t);
+    let big = VariableData::BigInt("100000000000000000000000000001".parse::<BigInt>().unwrap());
+    let dec = VariableData::BigDecimal("1e29".parse::<BigDecimal>().unwrap());
+    assert_eq!(big.compare(&dec, Comparison::Greater).unwrap(), t);
+    let odd = VariableData::Integer(Integer::from(9007199254740993));
+    let even = VariableData::BigInt(BigInt::from(9007199254740992i64));
+    assert_eq!(odd.compare(&even, Comparison::Equal).unwrap(), f);
+    assert_eq!(even.compare(&odd, Comparison::Less).unwrap(), t);
+    assert_eq!(odd.compare(&VariableData::BigDecimal("9007199254740992.5".parse::<BigDecimal>().unwrap()), Comparison::Greater).unwrap(), t);
+    let close = VariableData::Rational(Rational::new(4611686018427387905, 1).unwrap());
+    assert_eq!(close.compare(&VariableData::BigInt(BigInt::from(4611686018427387904i64)), Comparison::Greater).unwrap(), t);
+    assert_eq!(VariableData::BigInt(BigInt::from(4611686018427387904i64)).compare(&close, Comparison::Less).unwrap(), t);
+
+    let metre = VariableData::Quantity(Quantity::parse(Scalar::from(1.0), "m").unwrap());
+    let inches = VariableData::Quantity(Quantity::parse(Scalar::from(40.0), "in").unwrap());
+    assert_eq!(metre.compare(&inches, Comparison::Less).unwrap(), t);
+    let second = VariableData::Quantity(Quantity::parse(Scalar::from(1.0), "s").unwrap());
+    assert!(metre.compare(&second, Comparison::Less).is_err());
+
+    let z = VariableData::Complex(Complex::new(1.0, 2.0));
+    assert!(matches!(z.compare(&scalar(1.0), Comparison::Less), Err(Error::OperatorError(..))));
+    assert_eq!(z.compare(&z, Comparison::Equal).unwrap(), t);
+    assert!(t.compare(&f, Comparison::Greater).is_err());
+    assert_eq!(t.compare(&f, Comparison::NotEqual).unwrap(), t);
+
+    assert_eq!(t.and(&f).unwrap(), f);
+    assert_eq!(t.or(&f).unwrap(), t);
+    assert_eq!(t.xor(&t).unwrap(), f);
+    assert_eq!(f.not().unwrap(), t);
+    assert!(t.and(&scalar(1.0)).is_err());
+    assert!(matches!(scalar(1.0).or(&t), Err(Error::OperatorError(_, a, Some(b))) if a == format!("{:?}", scalar(1.0)) && b == format!("{:?}", t)));
+
+    let units: Vec<BinaryUnit> = Boolean::from(true).into();
+    assert_eq!(Boolean::try_from(units).unwrap(), Boolean::from(true));
+}
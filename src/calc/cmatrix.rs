@@ -0,0 +1,475 @@
+use std::fmt::{Display, Debug};
+use std::ops::{Add, Sub, Div, Mul, Index, IndexMut};
+
+use crate::{argument_error, binary_unit, conversion_error, operation_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, complex::Complex, matrix::Matrix, vector::MVector};
+
+/// Combined `LU` factors, the row permutation, and the sign of that permutation.
+type ComplexLU = (Vec<Vec<Complex>>, Vec<usize>, f64);
+
+/// A dense matrix of complex numbers. This mirrors `Matrix`, and adds the operations that only make sense over the complex numbers.
+#[derive(Clone, Default)]
+pub struct CMatrix {
+    data: Vec<Vec<Complex>>
+}
+impl Debug for CMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(CMatrix:{}x{} {:?})", self.rows(), self.cols(), &self.data)
+    }
+}
+impl Display for CMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.data.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for (j, item) in row.iter().enumerate() {
+                if j != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            write!(f, "]")?;
+        }
+
+        Ok(())
+    }
+}
+impl PartialEq for CMatrix {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+impl From<CMatrix> for Vec<BinaryUnit> {
+    fn from(value: CMatrix) -> Self {
+        let mut result = vec![binary_unit!(value.rows()), binary_unit!(value.cols())];
+        for row in value.data {
+            for item in row {
+                let units: Vec<BinaryUnit> = item.into();
+                result.extend(units);
+            }
+        }
+
+        result
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for CMatrix {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.len() < 2 {
+            return Err(conversion_error!("cannot construct CMatrix from {} element(s)", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let rows: usize = iter.next().unwrap().try_into()?; //We can unwrap because there are at least two elements
+        let cols: usize = iter.next().unwrap().try_into()?;
+
+        let count = rows.checked_mul(cols).and_then(|n| n.checked_mul(2)).ok_or_else(|| conversion_error!("a {}x{} complex matrix is too large", rows, cols))?;
+        let items: Vec<BinaryUnit> = iter.collect();
+        if items.len() != count {
+            return Err(conversion_error!("expected {} units for a {}x{} complex matrix, got {}", count, rows, cols, items.len()));
+        }
+        if rows == 0 || cols == 0 {
+            return Ok(Self::default());
+        }
+
+        let mut flat: Vec<Complex> = Vec::with_capacity(rows * cols);
+        for chunk in items.chunks(2) {
+            flat.push(Complex::try_from(chunk.to_vec())?);
+        }
+
+        Ok(
+            Self {
+                data: flat.chunks(cols).map(|c| c.to_vec()).collect()
+            }
+        )
+    }
+}
+impl From<Matrix> for CMatrix {
+    fn from(value: Matrix) -> Self {
+        Self {
+            data: (0..value.rows()).map(|i| (0..value.cols()).map(|j| Complex::from(value[(i, j)])).collect()).collect()
+        }
+    }
+}
+impl VariableType for CMatrix {
+    fn required_units(&self) -> usize {
+        2 * self.rows() * self.cols() + 2
+    }
+}
+impl Index<(usize, usize)> for CMatrix {
+    type Output = Complex;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.data[index.0][index.1]
+    }
+}
+impl IndexMut<(usize, usize)> for CMatrix {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.data[index.0][index.1]
+    }
+}
+impl Add for CMatrix {
+    type Output = Result<CMatrix, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.is_error() || rhs.is_error() || self.rows() != rhs.rows() || self.cols() != rhs.cols() {
+            return Err(operator_error!('+', self, rhs));
+        }
+
+        let mut result = self;
+        for (row, other) in result.data.iter_mut().zip(rhs.data) {
+            for (item, x) in row.iter_mut().zip(other) {
+                *item += x;
+            }
+        }
+
+        Ok(result)
+    }
+}
+impl Sub for CMatrix {
+    type Output = Result<CMatrix, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        if self.is_error() || rhs.is_error() || self.rows() != rhs.rows() || self.cols() != rhs.cols() {
+            return Err(operator_error!('-', self, rhs));
+        }
+
+        let mut result = self;
+        for (row, other) in result.data.iter_mut().zip(rhs.data) {
+            for (item, x) in row.iter_mut().zip(other) {
+                *item -= x;
+            }
+        }
+
+        Ok(result)
+    }
+}
+impl Mul for CMatrix {
+    type Output = Result<CMatrix, Error>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_error() || rhs.is_error() || self.cols() != rhs.rows() {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let mut result = Self::zeros(self.rows(), rhs.cols());
+        for i in 0..self.rows() {
+            for k in 0..self.cols() {
+                let lhs = self.data[i][k];
+                for j in 0..rhs.cols() {
+                    result.data[i][j] += lhs * rhs.data[k][j];
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+impl Mul<MVector<Complex>> for CMatrix {
+    type Output = Result<MVector<Complex>, Error>;
+    fn mul(self, rhs: MVector<Complex>) -> Self::Output {
+        if self.is_error() || rhs.is_error() || self.cols() != rhs.dim() {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let result: Vec<Complex> = self.data.iter()
+            .map(|row| row.iter().zip(rhs.iter()).fold(Complex::default(), |acc, (a, b)| acc + *a * *b))
+            .collect();
+
+        Ok( MVector::from(result) )
+    }
+}
+impl Mul<Complex> for CMatrix {
+    type Output = Result<CMatrix, Error>;
+    fn mul(self, rhs: Complex) -> Self::Output {
+        if self.is_error() {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let mut result = self;
+        for row in result.data.iter_mut() {
+            for item in row.iter_mut() {
+                *item *= rhs;
+            }
+        }
+
+        Ok(result)
+    }
+}
+impl Mul<f64> for CMatrix {
+    type Output = Result<CMatrix, Error>;
+    fn mul(self, rhs: f64) -> Self::Output {
+        self.mul(Complex::from(rhs))
+    }
+}
+impl Div<Complex> for CMatrix {
+    type Output = Result<CMatrix, Error>;
+    fn div(self, rhs: Complex) -> Self::Output {
+        if self.is_error() || rhs == Complex::default() {
+            return Err(operator_error!('/', self, rhs));
+        }
+
+        let mut result = self;
+        for row in result.data.iter_mut() {
+            for item in row.iter_mut() {
+                *item /= rhs;
+            }
+        }
+
+        Ok(result)
+    }
+}
+impl Div<f64> for CMatrix {
+    type Output = Result<CMatrix, Error>;
+    fn div(self, rhs: f64) -> Self::Output {
+        self.div(Complex::from(rhs))
+    }
+}
+impl CMatrix {
+    /// Builds a matrix out of its rows. Every row must have the same length.
+    pub fn from_rows(rows: Vec<Vec<Complex>>) -> Result<Self, Error> {
+        if rows.is_empty() || rows[0].is_empty() {
+            return match rows.iter().all(|r| r.is_empty()) {
+                true => Ok(Self::default()),
+                false => Err(argument_error!("rows", "the first row is empty, but others are not"))
+            };
+        }
+
+        let cols = rows[0].len();
+        if let Some((i, row)) = rows.iter().enumerate().find(|(_, r)| r.len() != cols) {
+            return Err(argument_error!("rows", "row {} has {} column(s), expected {}", i, row.len(), cols));
+        }
+
+        Ok(
+            Self {
+                data: rows
+            }
+        )
+    }
+    /// Builds a matrix out of its columns. Every column must have the same length.
+    pub fn from_cols(cols: Vec<Vec<Complex>>) -> Result<Self, Error> {
+        Ok( Self::from_rows(cols).map_err(|_| argument_error!("cols", "columns must all have the same length"))?.transpose() )
+    }
+    /// Builds a complex matrix out of its real and imaginary parts, which must have the same dimensions.
+    pub fn from_parts(real: &Matrix, imag: &Matrix) -> Result<Self, Error> {
+        if real.rows() != imag.rows() || real.cols() != imag.cols() {
+            return Err(argument_error!("imag", "dimensions {}x{} do not match {}x{}", imag.rows(), imag.cols(), real.rows(), real.cols()));
+        }
+
+        Ok(
+            Self {
+                data: (0..real.rows()).map(|i| (0..real.cols()).map(|j| Complex::new(real[(i, j)], imag[(i, j)])).collect()).collect()
+            }
+        )
+    }
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        if rows == 0 || cols == 0 {
+            return Self::default();
+        }
+
+        Self {
+            data: vec![vec![Complex::default(); cols]; rows]
+        }
+    }
+    pub fn identity(n: usize) -> Self {
+        let mut result = Self::zeros(n, n);
+        for i in 0..n {
+            result.data[i][i] = Complex::from(1.0);
+        }
+
+        result
+    }
+    /// Builds a square matrix with `diag` along the main diagonal, and zeros elsewhere.
+    pub fn diagonal(diag: &[Complex]) -> Self {
+        let mut result = Self::zeros(diag.len(), diag.len());
+        for (i, item) in diag.iter().enumerate() {
+            result.data[i][i] = *item;
+        }
+
+        result
+    }
+
+    pub fn rows(&self) -> usize {
+        self.data.len()
+    }
+    pub fn cols(&self) -> usize {
+        if self.data.is_empty() {
+            0
+        }
+        else {
+            self.data[0].len()
+        }
+    }
+    pub fn is_error(&self) -> bool {
+        self.data.is_empty()
+    }
+    pub fn is_square(&self) -> bool {
+        self.rows() == self.cols()
+    }
+    pub fn get(&self, row: usize, col: usize) -> Option<Complex> {
+        self.data.get(row).and_then(|r| r.get(col)).copied()
+    }
+
+    /// The real parts of every entry.
+    pub fn real(&self) -> Matrix {
+        Matrix::from_rows(self.data.iter().map(|r| r.iter().map(|c| c.real()).collect()).collect()).unwrap_or_default()
+    }
+    /// The imaginary parts of every entry.
+    pub fn imag(&self) -> Matrix {
+        Matrix::from_rows(self.data.iter().map(|r| r.iter().map(|c| c.imag()).collect()).collect()).unwrap_or_default()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut result = Self::zeros(self.cols(), self.rows());
+        for (i, row) in self.data.iter().enumerate() {
+            for (j, item) in row.iter().enumerate() {
+                result.data[j][i] = *item;
+            }
+        }
+
+        result
+    }
+    pub fn conjugate(&self) -> Self {
+        Self {
+            data: self.data.iter().map(|r| r.iter().map(|c| c.conjugate()).collect()).collect()
+        }
+    }
+    /// The conjugate (Hermitian) transpose, `A^H`.
+    pub fn conjugate_transpose(&self) -> Self {
+        self.transpose().conjugate()
+    }
+    /// Determines if the matrix equals its own conjugate transpose, with entries compared up to `tol`.
+    pub fn is_hermitian(&self, tol: f64) -> bool {
+        if !self.is_square() {
+            return false;
+        }
+
+        for i in 0..self.rows() {
+            for j in i..self.cols() {
+                if !self.data[i][j].approx_eq(&self.data[j][i].conjugate(), tol) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Performs Gaussian elimination with partial pivoting, returning `None` if a pivot vanishes.
+    fn lu(&self, action: &str) -> Result<Option<ComplexLU>, Error> {
+        if self.is_error() {
+            return Err(operation_error!(action, "no data loaded (error state)"));
+        }
+        else if !self.is_square() {
+            return Err(operation_error!(action, "matrix must be square, got {}x{}", self.rows(), self.cols()));
+        }
+
+        let n = self.rows();
+        let largest = self.data.iter().flatten().map(|c| c.modulus()).fold(0.0, f64::max);
+        let tol = f64::EPSILON * n as f64 * largest;
+
+        let mut lu = self.data.clone();
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut sign = 1.0;
+        for k in 0..n {
+            let pivot_row = (k..n).max_by(|x, y| lu[*x][k].modulus().total_cmp(&lu[*y][k].modulus())).unwrap_or(k);
+            if lu[pivot_row][k].modulus() <= tol {
+                return Ok(None);
+            }
+            if pivot_row != k {
+                lu.swap(pivot_row, k);
+                perm.swap(pivot_row, k);
+                sign = -sign;
+            }
+
+            let (upper, lower) = lu.split_at_mut(k + 1);
+            let pivot_row = &upper[k];
+            for row in lower.iter_mut() {
+                let factor = row[k] / pivot_row[k];
+                row[k] = factor;
+                for (x, p) in row.iter_mut().zip(pivot_row.iter()).skip(k + 1) {
+                    *x -= factor * *p;
+                }
+            }
+        }
+
+        Ok( Some((lu, perm, sign)) )
+    }
+    fn lu_solve(lu: &[Vec<Complex>], perm: &[usize], b: &[Complex]) -> Vec<Complex> {
+        let n = lu.len();
+        let mut x: Vec<Complex> = perm.iter().map(|p| b[*p]).collect();
+        for i in 0..n {
+            let sum = (0..i).fold(Complex::default(), |acc, j| acc + lu[i][j] * x[j]);
+            x[i] -= sum;
+        }
+        for i in (0..n).rev() {
+            let sum = ((i + 1)..n).fold(Complex::default(), |acc, j| acc + lu[i][j] * x[j]);
+            x[i] = (x[i] - sum) / lu[i][i];
+        }
+
+        x
+    }
+
+    pub fn determinant(&self) -> Result<Complex, Error> {
+        match self.lu("determinant")? {
+            None => Ok(Complex::default()),
+            Some((lu, _, sign)) => Ok( (0..lu.len()).fold(Complex::from(sign), |acc, i| acc * lu[i][i]) )
+        }
+    }
+    pub fn inverse(&self) -> Result<CMatrix, Error> {
+        let (lu, perm, _) = self.lu("inverse")?.ok_or_else(|| operation_error!("inverse", "matrix is singular"))?;
+        let n = lu.len();
+        let cols: Vec<Vec<Complex>> = (0..n).map(|j| {
+            let mut e = vec![Complex::default(); n];
+            e[j] = Complex::from(1.0);
+            Self::lu_solve(&lu, &perm, &e)
+        }).collect();
+
+        Self::from_cols(cols)
+    }
+    /// Solves `Ax = b` through the LU decomposition of `A`.
+    pub fn solve(&self, b: &MVector<Complex>) -> Result<MVector<Complex>, Error> {
+        if b.dim() != self.rows() {
+            return Err(operation_error!("solve", "right hand side has dim = {}, expected dim = {}", b.dim(), self.rows()));
+        }
+
+        let (lu, perm, _) = self.lu("solve")?.ok_or_else(|| operation_error!("solve", "matrix is singular"))?;
+        let b: Vec<Complex> = b.iter().copied().collect();
+
+        Ok( MVector::from(Self::lu_solve(&lu, &perm, &b)) )
+    }
+}
+
+#[test]
+fn test_cmatrix_functions() {
+    let i = Complex::I;
+    let one = Complex::from(1.0);
+    let a = CMatrix::from_rows(vec![vec![one, i], vec![-i, Complex::from(2.0)]]).unwrap();
+    assert!(a.is_hermitian(0.0));
+    assert_eq!(a.conjugate_transpose(), a);
+
+    let b = CMatrix::from_rows(vec![vec![one, one + i], vec![Complex::default(), i]]).unwrap();
+    assert!(!b.is_hermitian(1e-12));
+    assert_eq!(b.conjugate_transpose(), CMatrix::from_rows(vec![vec![one, Complex::default()], vec![one - i, -i]]).unwrap());
+
+    let product = (a.clone() * b.clone()).unwrap();
+    assert_eq!(product, CMatrix::from_rows(vec![vec![one, Complex::new(0.0, 1.0)], vec![-i, Complex::new(1.0, 1.0)]]).unwrap());
+    assert!((a.clone() + b.clone()).is_ok());
+    assert!((a.clone() + CMatrix::identity(3)).is_err());
+
+    //Impedances of 1 + j ohm in each branch, with 1 ohm shared, driven by 1V.
+    let z = CMatrix::from_rows(vec![vec![Complex::new(2.0, 1.0), -one], vec![-one, Complex::new(2.0, 1.0)]]).unwrap();
+    let v = MVector::from(vec![one, Complex::default()]);
+    let current = z.solve(&v).unwrap();
+    let back = (z.clone() * current).unwrap();
+    assert!(back[0].approx_eq(&one, 1e-12) && back[1].approx_eq(&Complex::default(), 1e-12));
+    assert!(z.determinant().unwrap().approx_eq(&Complex::new(2.0, 4.0), 1e-12));
+
+    let product = (z.clone() * z.inverse().unwrap()).unwrap();
+    assert!((product - CMatrix::identity(2)).unwrap().data.iter().flatten().all(|c| c.modulus() < 1e-12));
+
+    let parts = CMatrix::from_parts(&b.real(), &b.imag()).unwrap();
+    assert_eq!(parts, b);
+
+    let units: Vec<BinaryUnit> = b.clone().into();
+    assert_eq!(CMatrix::try_from(units).unwrap(), b);
+    assert!(CMatrix::try_from(vec![ binary_unit!(usize::MAX / 2 + 1), binary_unit!(1usize) ]).is_err());
+}
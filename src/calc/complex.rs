@@ -1,38 +1,66 @@
 use std::fmt::{Debug, Display};
-use std::ops::{Add, Sub, Mul, Div};
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg};
 
-use crate::{conversion_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use crate::{binary_unit, conversion_error, operation_error, core::{io::BinaryUnit, errors::Error}};
 use super::{variable_type::{VariableType, SimpleNumerical}, scalar::Scalar};
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub struct Complex {
     a: f64,
     b: f64
 }
 impl Debug for Complex {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        write!(f, "(Complex:{}+{}i)", self.a, self.b)
     }
 }
 impl Display for Complex  {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        if self.b.is_sign_negative() {
+            write!(f, "{} - {}i", self.a, -self.b)
+        }
+        else {
+            write!(f, "{} + {}i", self.a, self.b)
+        }
     }
 }
 impl PartialEq for Complex {
     fn eq(&self, other: &Self) -> bool {
-        todo!()
+        self.a == other.a && self.b == other.b
+    }
+}
+impl PartialEq<f64> for Complex {
+    fn eq(&self, other: &f64) -> bool {
+        self.a == *other && self.b == 0.0
     }
 }
-impl Into<Vec<BinaryUnit>> for Complex {
-    fn into(self) -> Vec<BinaryUnit> {
-        todo!()
+impl From<Complex> for Vec<BinaryUnit> {
+    fn from(value: Complex) -> Self {
+        vec![ binary_unit!(value.a), binary_unit!(value.b) ]
     }
 }
 impl TryFrom<Vec<BinaryUnit>> for Complex {
     type Error = Error;
     fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
-        todo!()
+        if value.len() != 2 {
+            return Err(conversion_error!("expected 2 units, got {}", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let a: f64 = iter.next().unwrap().try_into()?;
+        let b: f64 = iter.next().unwrap().try_into()?;
+
+        Ok(Self::new(a, b))
+    }
+}
+impl From<f64> for Complex {
+    fn from(value: f64) -> Self {
+        Self::new(value, 0.0)
+    }
+}
+impl From<Scalar> for Complex {
+    fn from(value: Scalar) -> Self {
+        Self::new(value.into(), 0.0)
     }
 }
 impl Default for Complex {
@@ -48,6 +76,12 @@ impl VariableType for Complex {
         2usize
     }
 }
+impl Neg for Complex {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.a, -self.b)
+    }
+}
 impl Add for Complex {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
@@ -71,6 +105,16 @@ impl Add<f64> for Complex {
         self.add(Complex::new(rhs, 0.0))
     }
 }
+impl AddAssign for Complex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl AddAssign<f64> for Complex {
+    fn add_assign(&mut self, rhs: f64) {
+        self.a += rhs;
+    }
+}
 impl Sub for Complex {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
@@ -94,10 +138,24 @@ impl Sub<f64> for Complex {
         self.sub(Complex::new(rhs, 0.0))
     }
 }
+impl SubAssign for Complex {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+impl SubAssign<f64> for Complex {
+    fn sub_assign(&mut self, rhs: f64) {
+        self.a -= rhs;
+    }
+}
 impl Mul for Complex {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
-        todo!()
+        // (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+        Self::new(
+            self.a * rhs.a - self.b * rhs.b,
+            self.a * rhs.b + self.b * rhs.a
+        )
     }
 }
 impl Mul<Scalar> for Complex {
@@ -117,10 +175,36 @@ impl Mul<f64> for Complex {
         result
     }
 }
+impl MulAssign for Complex {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+impl MulAssign<f64> for Complex {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
 impl Div for Complex {
     type Output = Self;
     fn div(self, rhs: Self) -> Self::Output {
-        todo!()
+        // Smith's algorithm, which avoids overflowing when squaring the parts of the denominator.
+        if rhs.b.abs() <= rhs.a.abs() {
+            let ratio = rhs.b / rhs.a;
+            let denom = rhs.a + rhs.b * ratio;
+            Self::new(
+                (self.a + self.b * ratio) / denom,
+                (self.b - self.a * ratio) / denom
+            )
+        }
+        else {
+            let ratio = rhs.a / rhs.b;
+            let denom = rhs.a * ratio + rhs.b;
+            Self::new(
+                (self.a * ratio + self.b) / denom,
+                (self.b * ratio - self.a) / denom
+            )
+        }
     }
 }
 impl Div<Scalar> for Complex {
@@ -140,18 +224,252 @@ impl Div<f64> for Complex {
         result
     }
 }
+impl DivAssign for Complex {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+impl DivAssign<f64> for Complex {
+    fn div_assign(&mut self, rhs: f64) {
+        *self = *self / rhs;
+    }
+}
 impl SimpleNumerical for Complex {
-    
+    fn abs(&self) -> f64 {
+        self.modulus()
+    }
+    fn conjugate(&self) -> Self {
+        Complex::conjugate(self)
+    }
 }
 impl Complex {
+    pub const I: Complex = Complex { a: 0.0, b: 1.0 };
+
     pub fn new(a: f64, b: f64) -> Self {
         Self {
             a,
             b
         }
     }
+    /// Builds a complex number from its modulus `r` and argument `theta` (in radians).
+    pub fn from_polar(r: f64, theta: f64) -> Self {
+        Self::new(r * theta.cos(), r * theta.sin())
+    }
 
+    pub fn real(&self) -> f64 {
+        self.a
+    }
+    pub fn imag(&self) -> f64 {
+        self.b
+    }
+    pub fn is_real(&self) -> bool {
+        self.b == 0.0
+    }
+    pub fn is_nan(&self) -> bool {
+        self.a.is_nan() || self.b.is_nan()
+    }
+
+    /// Returns `(r, theta)`, where `r` is the modulus and `theta` is the principal argument in `(-pi, pi]`.
     pub fn polar(&self) -> (f64, f64) {
-        todo!()
+        (self.modulus(), self.argument())
+    }
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.a, -self.b)
+    }
+    pub fn modulus(&self) -> f64 {
+        self.a.hypot(self.b)
+    }
+    pub fn modulus_squared(&self) -> f64 {
+        self.a * self.a + self.b * self.b
+    }
+    pub fn argument(&self) -> f64 {
+        self.b.atan2(self.a)
+    }
+    pub fn recip(&self) -> Self {
+        Self::new(1.0, 0.0) / *self
+    }
+
+    pub fn exp(&self) -> Self {
+        Self::from_polar(self.a.exp(), self.b)
     }
-}
\ No newline at end of file
+    /// The principal branch of the natural logarithm, with the imaginary part in `(-pi, pi]`.
+    pub fn ln(&self) -> Result<Self, Error> {
+        if self.a == 0.0 && self.b == 0.0 {
+            return Err(operation_error!("ln", "the logarithm of zero is undefined"));
+        }
+
+        Ok( Self::new(self.modulus().ln(), self.argument()) )
+    }
+    /// Raises `self` to a complex power using the principal branch, `z^w = exp(w ln z)`.
+    pub fn pow(&self, exp: Complex) -> Result<Self, Error> {
+        if self.a == 0.0 && self.b == 0.0 {
+            return if exp.a == 0.0 && exp.b == 0.0 {
+                Ok( Self::new(1.0, 0.0) )
+            }
+            else if exp.a > 0.0 {
+                Ok( Self::default() )
+            }
+            else {
+                Err(operation_error!("pow", "zero cannot be raised to {}", exp))
+            };
+        }
+
+        Ok( (exp * self.ln()?).exp() )
+    }
+    pub fn powf(&self, exp: f64) -> Result<Self, Error> {
+        self.pow(Complex::from(exp))
+    }
+    pub fn powi(&self, exp: i32) -> Self {
+        let mut base = if exp < 0 { self.recip() } else { *self };
+        let mut n = exp.unsigned_abs();
+        let mut result = Self::new(1.0, 0.0);
+        while n > 0 {
+            if n & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            n >>= 1;
+        }
+
+        result
+    }
+    /// The principal square root, with a non-negative real part.
+    pub fn sqrt(&self) -> Self {
+        if self.a == 0.0 && self.b == 0.0 {
+            return Self::default();
+        }
+
+        // Computed without going through polar form, so that perfect squares stay exact.
+        let t = ((self.a.abs() + self.modulus()) / 2.0).sqrt();
+        if self.a >= 0.0 {
+            Self::new(t, self.b / (2.0 * t))
+        }
+        else {
+            Self::new(self.b.abs() / (2.0 * t), t.copysign(self.b))
+        }
+    }
+    /// Returns all `n` of the n-th roots of `self`, starting from the principal root and proceeding counter-clockwise.
+    pub fn roots(&self, n: u32) -> Result<Vec<Self>, Error> {
+        if n == 0 {
+            return Err(operation_error!("roots", "cannot take the zeroth root"));
+        }
+
+        let (r, theta) = self.polar();
+        let modulus = r.powf(1.0 / n as f64);
+        let step = 2.0 * std::f64::consts::PI / n as f64;
+
+        Ok(
+            (0..n).map(|k| Self::from_polar(modulus, theta / n as f64 + step * k as f64)).collect()
+        )
+    }
+
+    pub fn sin(&self) -> Self {
+        Self::new(self.a.sin() * self.b.cosh(), self.a.cos() * self.b.sinh())
+    }
+    pub fn cos(&self) -> Self {
+        Self::new(self.a.cos() * self.b.cosh(), -self.a.sin() * self.b.sinh())
+    }
+    pub fn tan(&self) -> Self {
+        // tan(a + bi) = (sin 2a + i sinh 2b) / (cos 2a + cosh 2b)
+        let denom = (2.0 * self.a).cos() + (2.0 * self.b).cosh();
+        Self::new((2.0 * self.a).sin() / denom, (2.0 * self.b).sinh() / denom)
+    }
+    pub fn sinh(&self) -> Self {
+        Self::new(self.a.sinh() * self.b.cos(), self.a.cosh() * self.b.sin())
+    }
+    pub fn cosh(&self) -> Self {
+        Self::new(self.a.cosh() * self.b.cos(), self.a.sinh() * self.b.sin())
+    }
+    pub fn tanh(&self) -> Self {
+        // tanh(a + bi) = (sinh 2a + i sin 2b) / (cosh 2a + cos 2b)
+        let denom = (2.0 * self.a).cosh() + (2.0 * self.b).cos();
+        Self::new((2.0 * self.a).sinh() / denom, (2.0 * self.b).sin() / denom)
+    }
+
+    /// asin(z) = -i ln(iz + sqrt(1 - z^2))
+    pub fn asin(&self) -> Self {
+        let one = Self::new(1.0, 0.0);
+        let inner = Self::I * *self + (one - *self * *self).sqrt();
+        -Self::I * Self::new(inner.modulus().ln(), inner.argument())
+    }
+    /// acos(z) = pi/2 - asin(z)
+    pub fn acos(&self) -> Self {
+        Self::new(std::f64::consts::FRAC_PI_2, 0.0) - self.asin()
+    }
+    /// atan(z) = (i/2) (ln(1 - iz) - ln(1 + iz)), which is undefined at `z = ±i`.
+    pub fn atan(&self) -> Result<Self, Error> {
+        let one = Self::new(1.0, 0.0);
+        let iz = Self::I * *self;
+        let lhs = (one - iz).ln().map_err(|_| operation_error!("atan", "{} is a branch point", self))?;
+        let rhs = (one + iz).ln().map_err(|_| operation_error!("atan", "{} is a branch point", self))?;
+
+        Ok( Self::new(0.0, 0.5) * (lhs - rhs) )
+    }
+    /// asinh(z) = ln(z + sqrt(z^2 + 1))
+    pub fn asinh(&self) -> Self {
+        // asinh(z) = -i asin(iz), which keeps the branch cuts consistent with `asin`.
+        -Self::I * (Self::I * *self).asin()
+    }
+    /// acosh(z) = ln(z + sqrt(z + 1) sqrt(z - 1))
+    pub fn acosh(&self) -> Self {
+        let inner = *self + (*self + 1.0).sqrt() * (*self - 1.0).sqrt();
+        Self::new(inner.modulus().ln(), inner.argument())
+    }
+    /// atanh(z) = (1/2) (ln(1 + z) - ln(1 - z)), which is undefined at `z = ±1`.
+    pub fn atanh(&self) -> Result<Self, Error> {
+        let one = Self::new(1.0, 0.0);
+        let lhs = (one + *self).ln().map_err(|_| operation_error!("atanh", "{} is a branch point", self))?;
+        let rhs = (one - *self).ln().map_err(|_| operation_error!("atanh", "{} is a branch point", self))?;
+
+        Ok( (lhs - rhs) * 0.5 )
+    }
+
+    /// Determines if `self` and `other` differ by no more than `tol` in modulus.
+    pub fn approx_eq(&self, other: &Self, tol: f64) -> bool {
+        (*self - *other).modulus() <= tol
+    }
+}
+
+#[test]
+fn test_complex_functions() {
+    use std::f64::consts::{PI, FRAC_PI_2};
+    let tol = 1e-12;
+    let z = Complex::new(3.0, 4.0);
+    let w = Complex::new(1.0, -2.0);
+
+    assert_eq!(z * w, Complex::new(11.0, -2.0));
+    assert!(((z * w) / w).approx_eq(&z, tol));
+    assert_eq!(z.modulus(), 5.0);
+    assert_eq!(z.conjugate(), Complex::new(3.0, -4.0));
+    assert_eq!(format!("{}", w), "1 - 2i");
+
+    let (r, theta) = Complex::new(0.0, 2.0).polar();
+    assert!((r - 2.0).abs() < tol && (theta - FRAC_PI_2).abs() < tol);
+
+    assert!(Complex::new(0.0, PI).exp().approx_eq(&Complex::new(-1.0, 0.0), tol));
+    assert!(z.ln().unwrap().exp().approx_eq(&z, tol));
+    assert!(Complex::default().ln().is_err());
+    assert_eq!(Complex::new(-4.0, 0.0).sqrt(), Complex::new(0.0, 2.0));
+    assert!(Complex::I.pow(Complex::I).unwrap().approx_eq(&Complex::from((-FRAC_PI_2).exp()), tol));
+    assert!(z.powi(3).approx_eq(&(z * z * z), 1e-9));
+
+    let roots = Complex::new(1.0, 0.0).roots(4).unwrap();
+    let expected = [Complex::new(1.0, 0.0), Complex::I, Complex::new(-1.0, 0.0), -Complex::I];
+    for (root, exp) in roots.iter().zip(expected.iter()) {
+        assert!(root.approx_eq(exp, tol));
+    }
+    assert!(z.roots(0).is_err());
+
+    assert!(w.sin().asin().approx_eq(&w, tol));
+    assert!(w.cos().acos().approx_eq(&w, tol));
+    assert!(w.tan().atan().unwrap().approx_eq(&w, tol));
+    let v = Complex::new(0.5, -0.7);
+    assert!(v.sinh().asinh().approx_eq(&v, tol));
+    assert!(v.cosh().acosh().approx_eq(&v, tol));
+    assert!(v.tanh().atanh().unwrap().approx_eq(&v, tol));
+    assert!(Complex::I.atan().is_err());
+    assert!((w.sin() * w.sin() + w.cos() * w.cos()).approx_eq(&Complex::new(1.0, 0.0), tol));
+
+    let units: Vec<BinaryUnit> = z.into();
+    assert_eq!(Complex::try_from(units).unwrap(), z);
+}
@@ -0,0 +1,339 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Neg};
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, SecondsFormat, TimeDelta, Utc, Weekday};
+
+use crate::{argument_error, binary_unit, conversion_error, format_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::variable_type::VariableType;
+
+/// An instant in time, held in UTC with nanosecond resolution.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    data: chrono::DateTime<Utc>
+}
+impl Debug for DateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(DateTime:{})", self)
+    }
+}
+impl Display for DateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+impl Default for DateTime {
+    fn default() -> Self {
+        Self::from(chrono::DateTime::UNIX_EPOCH)
+    }
+}
+impl From<chrono::DateTime<Utc>> for DateTime {
+    fn from(value: chrono::DateTime<Utc>) -> Self {
+        Self {
+            data: value
+        }
+    }
+}
+impl From<DateTime> for chrono::DateTime<Utc> {
+    fn from(value: DateTime) -> Self {
+        value.data
+    }
+}
+impl FromStr for DateTime {
+    type Err = Error;
+    /// Parses ISO-8601 dates and times. Times without an offset, and bare dates, are taken to be UTC.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(d) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Ok( Self::from(d.with_timezone(&Utc)) );
+        }
+        if let Ok(d) = NaiveDateTime::from_str(s) {
+            return Ok( Self::from(d.and_utc()) );
+        }
+        if let Ok(d) = NaiveDate::from_str(s) {
+            return Ok( Self::from(d.and_time(chrono::NaiveTime::MIN).and_utc()) );
+        }
+
+        Err(format_error!(s, "not an ISO-8601 date or time"))
+    }
+}
+impl From<DateTime> for Vec<BinaryUnit> {
+    /// Encoded as whole seconds since the Unix epoch, then the nanoseconds past that second.
+    fn from(value: DateTime) -> Self {
+        vec![ binary_unit!(value.data.timestamp()), binary_unit!(value.data.timestamp_subsec_nanos()) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for DateTime {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        let mut iter = value.into_iter();
+        let mut next = || iter.next().ok_or_else(|| conversion_error!("ran out of units while decoding a date"));
+
+        let seconds: i64 = next()?.try_into()?;
+        let nanos: u32 = next()?.try_into()?;
+        chrono::DateTime::from_timestamp(seconds, nanos).map(Self::from).ok_or_else(|| conversion_error!("timestamp {}.{:09} is out of range", seconds, nanos))
+    }
+}
+impl VariableType for DateTime {
+    fn required_units(&self) -> usize {
+        2usize
+    }
+}
+impl Sub for DateTime {
+    type Output = Duration;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Duration::from(self.data - rhs.data)
+    }
+}
+impl Add<Duration> for DateTime {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Duration) -> Self::Output {
+        self.data.checked_add_signed(rhs.data).map(Self::from).ok_or_else(|| operator_error!('+', self, rhs))
+    }
+}
+impl Sub<Duration> for DateTime {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Duration) -> Self::Output {
+        self.data.checked_sub_signed(rhs.data).map(Self::from).ok_or_else(|| operator_error!('-', self, rhs))
+    }
+}
+
+impl DateTime {
+    pub fn now() -> Self {
+        Self::from(Utc::now())
+    }
+    /// Midnight UTC on the given calendar date.
+    pub fn from_date(year: i32, month: u32, day: u32) -> Result<Self, Error> {
+        NaiveDate::from_ymd_opt(year, month, day).map(|d| Self::from(d.and_time(chrono::NaiveTime::MIN).and_utc())).ok_or_else(|| conversion_error!("{}-{}-{} is not a valid date", year, month, day))
+    }
+    pub fn from_timestamp(seconds: i64) -> Result<Self, Error> {
+        chrono::DateTime::from_timestamp(seconds, 0).map(Self::from).ok_or_else(|| conversion_error!("timestamp {} is out of range", seconds))
+    }
+    pub fn from_timestamp_millis(millis: i64) -> Result<Self, Error> {
+        chrono::DateTime::from_timestamp_millis(millis).map(Self::from).ok_or_else(|| conversion_error!("timestamp {}ms is out of range", millis))
+    }
+    /// Whole seconds since the Unix epoch.
+    pub fn timestamp(&self) -> i64 {
+        self.data.timestamp()
+    }
+    pub fn timestamp_millis(&self) -> i64 {
+        self.data.timestamp_millis()
+    }
+
+    pub fn year(&self) -> i32 {
+        self.data.year()
+    }
+    pub fn month(&self) -> u32 {
+        self.data.month()
+    }
+    pub fn day(&self) -> u32 {
+        self.data.day()
+    }
+    pub fn weekday(&self) -> Weekday {
+        self.data.weekday()
+    }
+    pub fn is_weekend(&self) -> bool {
+        matches!(self.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// The number of calendar days from the date of `self` to the date of `end`, ignoring the time of day.
+    pub fn days_until(&self, end: &Self) -> i64 {
+        (end.data.date_naive() - self.data.date_naive()).num_days()
+    }
+    /// The number of Monday-to-Friday dates from the date of `self` (inclusive) up to the date of `end` (exclusive). Negative if `end` comes first.
+    pub fn working_days_until(&self, end: &Self) -> i64 {
+        if end < self {
+            return -end.working_days_until(self);
+        }
+
+        let days = self.days_until(end);
+        let mut count = (days / 7) * 5;
+        let mut weekday = self.weekday();
+        for _ in 0..days % 7 {
+            if !matches!(weekday, Weekday::Sat | Weekday::Sun) {
+                count += 1;
+            }
+            weekday = weekday.succ();
+        }
+
+        count
+    }
+    /// Moves forward (or backward, if negative) by `days` working days, skipping weekends. A start on a weekend first moves to the next working day.
+    pub fn add_working_days(&self, days: i64) -> Result<Self, Error> {
+        let step = Duration::days(days.signum())?;
+        let mut result = *self;
+        while result.is_weekend() {
+            result = (result + Duration::days(1)?)?;
+        }
+
+        //From a working day, every 5 working days is exactly one week, so only the remainder needs stepping.
+        let weeks = (days / 5).checked_mul(7).ok_or_else(|| argument_error!("days", "{} working days is out of range", days))?;
+        result = (result + Duration::days(weeks)?)?;
+        for _ in 0..(days % 5).unsigned_abs() {
+            result = (result + step)?;
+            while result.is_weekend() {
+                result = (result + step)?;
+            }
+        }
+
+        Ok( result )
+    }
+}
+
+/// A signed span of time with nanosecond resolution.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Duration {
+    data: TimeDelta
+}
+impl Debug for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Duration:{})", self)
+    }
+}
+impl Display for Duration {
+    /// ISO-8601 in seconds, such as `PT129600S`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data)
+    }
+}
+impl From<TimeDelta> for Duration {
+    fn from(value: TimeDelta) -> Self {
+        Self {
+            data: value
+        }
+    }
+}
+impl From<Duration> for TimeDelta {
+    fn from(value: Duration) -> Self {
+        value.data
+    }
+}
+impl From<Duration> for Vec<BinaryUnit> {
+    /// Encoded as whole seconds, then the nanoseconds past that second.
+    fn from(value: Duration) -> Self {
+        let seconds = value.data.num_seconds();
+        let nanos = (value.data - TimeDelta::seconds(seconds)).num_nanoseconds().unwrap_or(0) as i32;
+        vec![ binary_unit!(seconds), binary_unit!(nanos) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Duration {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        let mut iter = value.into_iter();
+        let mut next = || iter.next().ok_or_else(|| conversion_error!("ran out of units while decoding a duration"));
+
+        let seconds: i64 = next()?.try_into()?;
+        let nanos: i32 = next()?.try_into()?;
+        TimeDelta::try_seconds(seconds).and_then(|s| s.checked_add(&TimeDelta::nanoseconds(nanos as i64))).map(Self::from).ok_or_else(|| conversion_error!("duration of {}s is out of range", seconds))
+    }
+}
+impl VariableType for Duration {
+    fn required_units(&self) -> usize {
+        2usize
+    }
+}
+impl Add for Duration {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.data.checked_add(&rhs.data).map(Self::from).ok_or_else(|| operator_error!('+', self, rhs))
+    }
+}
+impl Sub for Duration {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.data.checked_sub(&rhs.data).map(Self::from).ok_or_else(|| operator_error!('-', self, rhs))
+    }
+}
+impl Add<DateTime> for Duration {
+    type Output = Result<DateTime, Error>;
+    fn add(self, rhs: DateTime) -> Self::Output {
+        rhs + self
+    }
+}
+impl Neg for Duration {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::from(-self.data)
+    }
+}
+
+impl Duration {
+    pub fn days(days: i64) -> Result<Self, Error> {
+        TimeDelta::try_days(days).map(Self::from).ok_or_else(|| conversion_error!("{} days is out of range", days))
+    }
+    pub fn hours(hours: i64) -> Result<Self, Error> {
+        TimeDelta::try_hours(hours).map(Self::from).ok_or_else(|| conversion_error!("{} hours is out of range", hours))
+    }
+    pub fn minutes(minutes: i64) -> Result<Self, Error> {
+        TimeDelta::try_minutes(minutes).map(Self::from).ok_or_else(|| conversion_error!("{} minutes is out of range", minutes))
+    }
+    pub fn seconds(seconds: f64) -> Result<Self, Error> {
+        let nanos = seconds * 1e9;
+        if !nanos.is_finite() || nanos.abs() >= i64::MAX as f64 {
+            return Err(conversion_error!("{} seconds is out of range", seconds));
+        }
+
+        Ok( Self::from(TimeDelta::nanoseconds(nanos.round() as i64)) )
+    }
+
+    /// The length in seconds, including the fractional part.
+    pub fn as_seconds(&self) -> f64 {
+        self.data.num_seconds() as f64 + self.data.subsec_nanos() as f64 / 1e9
+    }
+    /// The length in days, including the fractional part.
+    pub fn as_days(&self) -> f64 {
+        self.as_seconds() / 86400.0
+    }
+    /// The number of whole days, rounded toward zero.
+    pub fn whole_days(&self) -> i64 {
+        self.data.num_days()
+    }
+}
+
+#[test]
+fn test_dates_and_durations() {
+    let start: DateTime = "2024-02-26".parse().unwrap();
+    let end: DateTime = "2024-03-11T12:30:00+02:00".parse().unwrap();
+    assert_eq!(end.to_string(), "2024-03-11T10:30:00Z");
+    assert_eq!(start, DateTime::from_date(2024, 2, 26).unwrap());
+    assert_eq!("2024-03-11T10:30:00".parse::<DateTime>().unwrap(), end);
+    assert!("26/02/2024".parse::<DateTime>().is_err());
+
+    let span = end - start;
+    assert_eq!(span.whole_days(), 14);
+    assert!((span.as_days() - (14.0 + 10.5 / 24.0)).abs() < 1e-12);
+    assert_eq!((start + span).unwrap(), end);
+    assert_eq!((end - span).unwrap(), start);
+    assert_eq!((Duration::days(1).unwrap() + Duration::hours(12).unwrap()).unwrap().as_seconds(), 129600.0);
+    assert_eq!(Duration::seconds(-1.5).unwrap().as_seconds(), -1.5);
+    assert_eq!(Duration::hours(36).unwrap().to_string(), "PT129600S");
+
+    use super::{variable_data::VariableData, boolean::{Boolean, Comparison}};
+    let (a, b) = (VariableData::DateTime(start), VariableData::DateTime((start + Duration::seconds(0.5).unwrap()).unwrap()));
+    assert_eq!(a.compare(&b, Comparison::Less).unwrap(), VariableData::Bool(Boolean::from(true)));
+    assert_eq!(a.compare_with(&b, Comparison::Equal, 1.0).unwrap(), VariableData::Bool(Boolean::from(true)));
+
+    //Monday 26 Feb to Monday 11 March is two working weeks, and 2024 is a leap year.
+    assert_eq!(start.days_until(&end), 14);
+    assert_eq!(start.working_days_until(&end), 10);
+    assert_eq!(end.working_days_until(&start), -10);
+    let saturday = DateTime::from_date(2024, 3, 2).unwrap();
+    assert_eq!(saturday.working_days_until(&DateTime::from_date(2024, 3, 5).unwrap()), 1);
+    assert_eq!(start.add_working_days(10).unwrap(), DateTime::from_date(2024, 3, 11).unwrap());
+    assert_eq!(saturday.add_working_days(0).unwrap(), DateTime::from_date(2024, 3, 4).unwrap());
+    assert_eq!(start.add_working_days(-1).unwrap(), DateTime::from_date(2024, 2, 23).unwrap());
+    assert_eq!(start.add_working_days(-11).unwrap(), DateTime::from_date(2024, 2, 9).unwrap());
+    assert_eq!(start.add_working_days(1_000_000).unwrap(), (start + Duration::days(1_400_000).unwrap()).unwrap());
+    assert!(start.add_working_days(i64::MIN).is_err());
+    assert!(start.add_working_days(i64::MAX).is_err());
+
+    assert_eq!(DateTime::from_timestamp(1709251200).unwrap().to_string(), "2024-03-01T00:00:00Z");
+    assert_eq!(start.timestamp(), 1708905600);
+    assert_eq!(DateTime::from_timestamp_millis(start.timestamp_millis()).unwrap(), start);
+
+    let units: Vec<BinaryUnit> = end.into();
+    assert_eq!(DateTime::try_from(units).unwrap(), end);
+    let negative = -Duration::seconds(90061.25).unwrap();
+    let units: Vec<BinaryUnit> = negative.into();
+    assert_eq!(Duration::try_from(units).unwrap(), negative);
+}
@@ -0,0 +1,982 @@
+use crate::{operation_error, core::errors::Error};
+use super::{matrix::Matrix, scalar::Scalar, vector::MVector, complex::Complex};
+
+/// Relative threshold, scaled by the size and largest entry of a matrix, under which a pivot is treated as zero.
+fn pivot_tolerance(a: &Matrix) -> f64 {
+    let mut largest: f64 = 0.0;
+    for i in 0..a.rows() {
+        for j in 0..a.cols() {
+            largest = largest.max(a[(i, j)].abs());
+        }
+    }
+
+    f64::EPSILON * (a.rows().max(a.cols()) as f64) * largest
+}
+
+/// The factorization `PA = LU` of a square matrix, computed by Gaussian elimination with partial pivoting.
+/// `L` (unit lower triangular) and `U` (upper triangular) are stored together in one matrix.
+#[derive(Clone, Debug)]
+pub struct LUDecomposition {
+    lu: Matrix,
+    perm: Vec<usize>,
+    sign: f64,
+    singular: bool
+}
+impl LUDecomposition {
+    pub fn new(a: &Matrix) -> Result<Self, Error> {
+        if a.is_error() {
+            return Err(operation_error!("LU", "no data loaded (error state)"));
+        }
+        else if !a.is_square() {
+            return Err(operation_error!("LU", "matrix must be square, got {}x{}", a.rows(), a.cols()));
+        }
+
+        let n = a.rows();
+        let tol = pivot_tolerance(a);
+        let mut lu = a.clone();
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut sign = 1.0;
+        let mut singular = false;
+
+        for k in 0..n {
+            let mut pivot_row = k;
+            for i in (k + 1)..n {
+                if lu[(i, k)].abs() > lu[(pivot_row, k)].abs() {
+                    pivot_row = i;
+                }
+            }
+
+            if lu[(pivot_row, k)].abs() <= tol {
+                //No usable pivot in this column, so the matrix is singular. The factorization is kept only for the determinant, which is zero.
+                singular = true;
+                continue;
+            }
+
+            if pivot_row != k {
+                lu.swap_rows(pivot_row, k);
+                perm.swap(pivot_row, k);
+                sign = -sign;
+            }
+
+            let pivot = lu[(k, k)];
+            for i in (k + 1)..n {
+                let factor = lu[(i, k)] / pivot;
+                lu[(i, k)] = factor;
+                if factor == 0.0 {
+                    continue;
+                }
+                for j in (k + 1)..n {
+                    let delta = factor * lu[(k, j)];
+                    lu[(i, j)] -= delta;
+                }
+            }
+        }
+
+        Ok(
+            Self {
+                lu,
+                perm,
+                sign,
+                singular
+            }
+        )
+    }
+
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+    /// The unit lower triangular factor.
+    pub fn l(&self) -> Matrix {
+        let n = self.lu.rows();
+        let mut result = Matrix::identity(n);
+        for i in 0..n {
+            for j in 0..i {
+                result[(i, j)] = self.lu[(i, j)];
+            }
+        }
+
+        result
+    }
+    /// The upper triangular factor.
+    pub fn u(&self) -> Matrix {
+        let n = self.lu.rows();
+        let mut result = Matrix::zeros(n, n);
+        for i in 0..n {
+            for j in i..n {
+                result[(i, j)] = self.lu[(i, j)];
+            }
+        }
+
+        result
+    }
+    /// The permutation matrix `P`, such that `PA = LU`.
+    pub fn p(&self) -> Matrix {
+        let n = self.lu.rows();
+        let mut result = Matrix::zeros(n, n);
+        for (i, p) in self.perm.iter().enumerate() {
+            result[(i, *p)] = 1.0;
+        }
+
+        result
+    }
+
+    pub fn determinant(&self) -> f64 {
+        if self.singular {
+            return 0.0;
+        }
+
+        (0..self.lu.rows()).fold(self.sign, |acc, i| acc * self.lu[(i, i)])
+    }
+
+    /// Solves `Ax = b` for a single right hand side, given as a slice.
+    fn solve_slice(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.lu.rows();
+        let mut x: Vec<f64> = self.perm.iter().map(|p| b[*p]).collect();
+
+        //Forward substitution with the unit lower triangle.
+        for i in 0..n {
+            for j in 0..i {
+                x[i] -= self.lu[(i, j)] * x[j];
+            }
+        }
+        //Backward substitution with the upper triangle.
+        for i in (0..n).rev() {
+            for j in (i + 1)..n {
+                x[i] -= self.lu[(i, j)] * x[j];
+            }
+            x[i] /= self.lu[(i, i)];
+        }
+
+        x
+    }
+    pub fn solve(&self, b: &MVector<Scalar>) -> Result<MVector<Scalar>, Error> {
+        if b.dim() != self.lu.rows() {
+            return Err(operation_error!("solve", "right hand side has dim = {}, expected dim = {}", b.dim(), self.lu.rows()));
+        }
+        else if self.singular {
+            return Err(operation_error!("solve", "matrix is singular"));
+        }
+
+        let b: Vec<f64> = b.iter().map(|s| f64::from(s.clone())).collect();
+        Ok( MVector::from(self.solve_slice(&b).into_iter().map(Scalar::from).collect::<Vec<_>>()) )
+    }
+    pub fn inverse(&self) -> Result<Matrix, Error> {
+        if self.singular {
+            return Err(operation_error!("inverse", "matrix is singular"));
+        }
+
+        let n = self.lu.rows();
+        let mut cols: Vec<Vec<f64>> = Vec::with_capacity(n);
+        for j in 0..n {
+            let mut e = vec![0.0; n];
+            e[j] = 1.0;
+            cols.push(self.solve_slice(&e));
+        }
+
+        Matrix::from_cols(cols)
+    }
+}
+
+/// The factorization `A = QR` of an `m` by `n` matrix, computed with Householder reflections.
+/// `Q` is stored implicitly as the sequence of reflectors, and is only formed on request.
+#[derive(Clone, Debug)]
+pub struct QRDecomposition {
+    r: Matrix,
+    reflectors: Vec<(Vec<f64>, f64)>, //Householder vector (acting on rows k..m), beta
+    tol: f64
+}
+impl QRDecomposition {
+    pub fn new(a: &Matrix) -> Result<Self, Error> {
+        if a.is_error() {
+            return Err(operation_error!("QR", "no data loaded (error state)"));
+        }
+
+        let (m, n) = (a.rows(), a.cols());
+        let mut r = a.clone();
+        let mut reflectors: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n.min(m));
+
+        for k in 0..n.min(m) {
+            let mut v: Vec<f64> = (k..m).map(|i| r[(i, k)]).collect();
+            let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            //The sign is chosen opposite to the leading entry so that the subtraction below never cancels.
+            let alpha = if v[0] >= 0.0 { -norm } else { norm };
+            v[0] -= alpha;
+
+            let v_norm_sq: f64 = v.iter().map(|x| x * x).sum();
+            let beta = if v_norm_sq == 0.0 { 0.0 } else { 2.0 / v_norm_sq };
+
+            for j in k..n {
+                let dot: f64 = v.iter().enumerate().map(|(i, x)| x * r[(k + i, j)]).sum();
+                let scale = beta * dot;
+                for (i, x) in v.iter().enumerate() {
+                    r[(k + i, j)] -= scale * x;
+                }
+            }
+            //Clear out the rounding noise under the diagonal.
+            for i in (k + 1)..m {
+                r[(i, k)] = 0.0;
+            }
+
+            reflectors.push((v, beta));
+        }
+
+        Ok(
+            Self {
+                r,
+                reflectors,
+                tol: pivot_tolerance(a)
+            }
+        )
+    }
+
+    /// Applies `Q^T` to `b` in place.
+    fn apply_qt(&self, b: &mut [f64]) {
+        for (k, (v, beta)) in self.reflectors.iter().enumerate() {
+            let dot: f64 = v.iter().enumerate().map(|(i, x)| x * b[k + i]).sum();
+            let scale = beta * dot;
+            for (i, x) in v.iter().enumerate() {
+                b[k + i] -= scale * x;
+            }
+        }
+    }
+
+    /// The orthogonal factor, an `m` by `m` matrix.
+    pub fn q(&self) -> Matrix {
+        let m = self.r.rows();
+        let mut rows: Vec<Vec<f64>> = Vec::with_capacity(m);
+        //Q^T e_i is column i of Q^T, which is row i of Q.
+        for i in 0..m {
+            let mut e = vec![0.0; m];
+            e[i] = 1.0;
+            self.apply_qt(&mut e);
+            rows.push(e);
+        }
+
+        Matrix::from_rows(rows).unwrap_or_default()
+    }
+    /// The upper triangular (or trapezoidal) factor, an `m` by `n` matrix.
+    pub fn r(&self) -> Matrix {
+        self.r.clone()
+    }
+    /// Determines if the columns of `A` are linearly independent (up to rounding).
+    pub fn is_full_rank(&self) -> bool {
+        (0..self.r.rows().min(self.r.cols())).all(|i| self.r[(i, i)].abs() > self.tol) && self.r.rows() >= self.r.cols()
+    }
+
+    /// Finds the `x` that minimizes `||Ax - b||`, returning it along with the norm of the residual.
+    pub fn least_squares(&self, b: &MVector<Scalar>) -> Result<(MVector<Scalar>, f64), Error> {
+        let (m, n) = (self.r.rows(), self.r.cols());
+        if b.dim() != m {
+            return Err(operation_error!("least squares", "right hand side has dim = {}, expected dim = {}", b.dim(), m));
+        }
+        else if m < n {
+            return Err(operation_error!("least squares", "system is underdetermined ({} equations, {} unknowns)", m, n));
+        }
+        else if !self.is_full_rank() {
+            return Err(operation_error!("least squares", "matrix is rank deficient"));
+        }
+
+        let mut qtb: Vec<f64> = b.iter().map(|s| f64::from(s.clone())).collect();
+        self.apply_qt(&mut qtb);
+
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            let mut sum = qtb[i];
+            for (j, xj) in x.iter().enumerate().skip(i + 1) {
+                sum -= self.r[(i, j)] * xj;
+            }
+            x[i] = sum / self.r[(i, i)];
+        }
+
+        //The trailing entries of Q^T b are exactly the part of b that the columns of A cannot reach.
+        let residual = qtb[n..].iter().map(|x| x * x).sum::<f64>().sqrt();
+
+        Ok( (MVector::from(x.into_iter().map(Scalar::from).collect::<Vec<_>>()), residual) )
+    }
+}
+
+/// The eigenvalues and eigenvectors of a square matrix. Since real matrices can have complex eigenvalues, both are always complex.
+/// Symmetric matrices are handled by the Jacobi method, and everything else by Hessenberg reduction followed by the shifted QR algorithm.
+#[derive(Clone, Debug)]
+pub struct EigenDecomposition {
+    values: MVector<Complex>,
+    vectors: Vec<MVector<Complex>>
+}
+impl EigenDecomposition {
+    const MAX_JACOBI_SWEEPS: usize = 100;
+    const MAX_QR_ITERATIONS: usize = 60;
+
+    pub fn new(a: &Matrix) -> Result<Self, Error> {
+        if a.is_error() {
+            return Err(operation_error!("eigen", "no data loaded (error state)"));
+        }
+        else if !a.is_square() {
+            return Err(operation_error!("eigen", "matrix must be square, got {}x{}", a.rows(), a.cols()));
+        }
+
+        if a.is_symmetric(pivot_tolerance(a)) {
+            Self::jacobi(a)
+        }
+        else {
+            Self::general(a)
+        }
+    }
+
+    /// The eigenvalues. For symmetric matrices these are real and in ascending order, otherwise they are ordered by real part, then imaginary part.
+    pub fn values(&self) -> &MVector<Complex> {
+        &self.values
+    }
+    /// The eigenvectors, normalized to unit length, in the same order as the eigenvalues.
+    pub fn vectors(&self) -> &[MVector<Complex>] {
+        &self.vectors
+    }
+
+    fn jacobi(a: &Matrix) -> Result<Self, Error> {
+        let n = a.rows();
+        let mut a = a.clone();
+        let mut v = Matrix::identity(n);
+        let tol = pivot_tolerance(&a);
+
+        let mut converged = false;
+        for _ in 0..Self::MAX_JACOBI_SWEEPS {
+            let mut off: f64 = 0.0;
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    off += a[(p, q)] * a[(p, q)];
+                }
+            }
+            if off.sqrt() <= tol {
+                converged = true;
+                break;
+            }
+
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    if a[(p, q)] == 0.0 {
+                        continue;
+                    }
+
+                    //Rotation angle chosen so that the (p, q) entry vanishes, taking the smaller root for stability.
+                    let theta = (a[(q, q)] - a[(p, p)]) / (2.0 * a[(p, q)]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+
+                    for k in 0..n {
+                        let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                        a[(k, p)] = c * akp - s * akq;
+                        a[(k, q)] = s * akp + c * akq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                        a[(p, k)] = c * apk - s * aqk;
+                        a[(q, k)] = s * apk + c * aqk;
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (v[(k, p)], v[(k, q)]);
+                        v[(k, p)] = c * vkp - s * vkq;
+                        v[(k, q)] = s * vkp + c * vkq;
+                    }
+                }
+            }
+        }
+        if !converged {
+            return Err(operation_error!("eigen", "Jacobi iteration failed to converge"));
+        }
+
+        let mut pairs: Vec<(f64, Vec<f64>)> = (0..n).map(|i| (a[(i, i)], v.col(i).unwrap_or_default())).collect();
+        pairs.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        Ok(
+            Self {
+                values: MVector::from(pairs.iter().map(|(x, _)| Complex::from(*x)).collect::<Vec<_>>()),
+                vectors: pairs.into_iter().map(|(_, vec)| MVector::from(vec.into_iter().map(Complex::from).collect::<Vec<_>>())).collect()
+            }
+        )
+    }
+
+    fn general(a: &Matrix) -> Result<Self, Error> {
+        let values = Self::hessenberg_qr(&a.hessenberg())?;
+
+        let mut values: Vec<Complex> = values;
+        values.sort_by(|x, y| x.real().total_cmp(&y.real()).then(x.imag().total_cmp(&y.imag())));
+
+        let vectors = values.iter().map(|lambda| Self::inverse_iteration(a, *lambda)).collect();
+
+        Ok(
+            Self {
+                values: MVector::from(values),
+                vectors
+            }
+        )
+    }
+
+    /// Finds the eigenvalues of an upper Hessenberg matrix using the Francis double shift QR algorithm.
+    /// This follows the classic `hqr` routine, and so works with 1-based indices internally.
+    #[allow(clippy::needless_range_loop)]
+    fn hessenberg_qr(h: &Matrix) -> Result<Vec<Complex>, Error> {
+        let n = h.rows();
+        let mut a = vec![vec![0.0; n + 1]; n + 1];
+        let mut norm: f64 = 0.0;
+        for i in 1..=n {
+            for j in i.saturating_sub(1).max(1)..=n {
+                a[i][j] = h[(i - 1, j - 1)];
+                norm += a[i][j].abs();
+            }
+        }
+
+        let mut wr = vec![0.0; n + 1];
+        let mut wi = vec![0.0; n + 1];
+        let mut nn = n;
+        let mut t = 0.0;
+
+        while nn >= 1 {
+            let mut its = 0usize;
+            loop {
+                //Look for a single small subdiagonal element to split the matrix.
+                let mut l = nn;
+                while l >= 2 {
+                    let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+                    if s == 0.0 {
+                        s = norm;
+                    }
+                    if a[l][l - 1].abs() + s == s {
+                        a[l][l - 1] = 0.0;
+                        break;
+                    }
+                    l -= 1;
+                }
+
+                let mut x = a[nn][nn];
+                if l == nn {
+                    //One root found.
+                    wr[nn] = x + t;
+                    wi[nn] = 0.0;
+                    nn -= 1;
+                }
+                else {
+                    let mut y = a[nn - 1][nn - 1];
+                    let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+                    if l == nn - 1 {
+                        //Two roots found, either a real pair or a complex conjugate pair.
+                        let p = 0.5 * (y - x);
+                        let q = p * p + w;
+                        let mut z = q.abs().sqrt();
+                        x += t;
+                        if q >= 0.0 {
+                            z = p + z.copysign(p);
+                            wr[nn - 1] = x + z;
+                            wr[nn] = if z != 0.0 { x - w / z } else { x + z };
+                            wi[nn - 1] = 0.0;
+                            wi[nn] = 0.0;
+                        }
+                        else {
+                            wr[nn - 1] = x + p;
+                            wr[nn] = x + p;
+                            wi[nn - 1] = -z;
+                            wi[nn] = z;
+                        }
+                        nn = nn.saturating_sub(2);
+                    }
+                    else {
+                        if its == Self::MAX_QR_ITERATIONS {
+                            return Err(operation_error!("eigen", "QR iteration failed to converge"));
+                        }
+                        if its == 10 || its == 20 {
+                            //Exceptional shift, to break out of cycles.
+                            t += x;
+                            for i in 1..=nn {
+                                a[i][i] -= x;
+                            }
+                            let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                            x = 0.75 * s;
+                            y = x;
+                            w = -0.4375 * s * s;
+                        }
+                        its += 1;
+
+                        //Look for two consecutive small subdiagonal elements.
+                        let mut m = nn - 2;
+                        let (mut p, mut q, mut r, mut z);
+                        loop {
+                            z = a[m][m];
+                            r = x - z;
+                            let s = y - z;
+                            p = (r * s - w) / a[m + 1][m] + a[m][m + 1];
+                            q = a[m + 1][m + 1] - z - r - s;
+                            r = a[m + 2][m + 1];
+                            let s = p.abs() + q.abs() + r.abs();
+                            p /= s;
+                            q /= s;
+                            r /= s;
+                            if m == l {
+                                break;
+                            }
+                            let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                            let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                            if u + v == v {
+                                break;
+                            }
+                            m -= 1;
+                        }
+
+                        for i in (m + 2)..=nn {
+                            a[i][i - 2] = 0.0;
+                            if i != m + 2 {
+                                a[i][i - 3] = 0.0;
+                            }
+                        }
+
+                        //Double QR step on rows l..nn and columns m..nn.
+                        for k in m..nn {
+                            if k != m {
+                                p = a[k][k - 1];
+                                q = a[k + 1][k - 1];
+                                r = if k != nn - 1 { a[k + 2][k - 1] } else { 0.0 };
+                                x = p.abs() + q.abs() + r.abs();
+                                if x != 0.0 {
+                                    p /= x;
+                                    q /= x;
+                                    r /= x;
+                                }
+                            }
+
+                            let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                            if s != 0.0 {
+                                if k == m {
+                                    if l != m {
+                                        a[k][k - 1] = -a[k][k - 1];
+                                    }
+                                }
+                                else {
+                                    a[k][k - 1] = -s * x;
+                                }
+                                p += s;
+                                x = p / s;
+                                y = q / s;
+                                z = r / s;
+                                q /= p;
+                                r /= p;
+                                for j in k..=nn {
+                                    p = a[k][j] + q * a[k + 1][j];
+                                    if k != nn - 1 {
+                                        p += r * a[k + 2][j];
+                                        a[k + 2][j] -= p * z;
+                                    }
+                                    a[k + 1][j] -= p * y;
+                                    a[k][j] -= p * x;
+                                }
+                                let mmin = nn.min(k + 3);
+                                for i in l..=mmin {
+                                    p = x * a[i][k] + y * a[i][k + 1];
+                                    if k != nn - 1 {
+                                        p += z * a[i][k + 2];
+                                        a[i][k + 2] -= p * r;
+                                    }
+                                    a[i][k + 1] -= p * q;
+                                    a[i][k] -= p;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if nn < 2 || l + 1 >= nn {
+                    break;
+                }
+            }
+        }
+
+        Ok( (1..=n).map(|i| Complex::new(wr[i], wi[i])).collect() )
+    }
+
+    /// Recovers the eigenvector for `lambda` by inverse iteration on `A - lambda I`, carried out in complex arithmetic.
+    #[allow(clippy::needless_range_loop)]
+    fn inverse_iteration(a: &Matrix, lambda: Complex) -> MVector<Complex> {
+        let n = a.rows();
+        let tiny = pivot_tolerance(a).max(f64::MIN_POSITIVE);
+
+        //Factor A - lambda I once, with partial pivoting. Exactly zero pivots are expected (lambda is an eigenvalue), and are nudged.
+        let mut lu: Vec<Vec<Complex>> = (0..n).map(|i| (0..n).map(|j| {
+            let diag = if i == j { lambda } else { Complex::default() };
+            Complex::from(a[(i, j)]) - diag
+        }).collect()).collect();
+        let mut perm: Vec<usize> = (0..n).collect();
+        for k in 0..n {
+            let pivot_row = (k..n).max_by(|x, y| lu[*x][k].modulus().total_cmp(&lu[*y][k].modulus())).unwrap_or(k);
+            lu.swap(k, pivot_row);
+            perm.swap(k, pivot_row);
+            if lu[k][k].modulus() < tiny {
+                lu[k][k] = Complex::from(tiny);
+            }
+            for i in (k + 1)..n {
+                let factor = lu[i][k] / lu[k][k];
+                lu[i][k] = factor;
+                for j in (k + 1)..n {
+                    let delta = factor * lu[k][j];
+                    lu[i][j] -= delta;
+                }
+            }
+        }
+
+        let mut x: Vec<Complex> = vec![Complex::from(1.0); n];
+        for _ in 0..3 {
+            let mut y: Vec<Complex> = perm.iter().map(|p| x[*p]).collect();
+            for i in 0..n {
+                for j in 0..i {
+                    let delta = lu[i][j] * y[j];
+                    y[i] -= delta;
+                }
+            }
+            for i in (0..n).rev() {
+                for j in (i + 1)..n {
+                    let delta = lu[i][j] * y[j];
+                    y[i] -= delta;
+                }
+                y[i] /= lu[i][i];
+            }
+
+            let norm = y.iter().map(|c| c.modulus_squared()).sum::<f64>().sqrt();
+            x = y.into_iter().map(|c| c / norm).collect();
+        }
+
+        //Rotate so that the largest component is real and positive, which makes the result deterministic.
+        if let Some(largest) = x.iter().copied().max_by(|p, q| p.modulus().total_cmp(&q.modulus())) {
+            let phase = largest.conjugate() / largest.modulus();
+            x = x.into_iter().map(|c| c * phase).collect();
+        }
+
+        MVector::from(x)
+    }
+}
+
+/// The singular value decomposition `A = U Σ V^T` of an `m` by `n` matrix, computed with one-sided Jacobi rotations.
+/// This is the thin form, where `k = min(m, n)`: `U` is `m` by `k`, `V` is `n` by `k`, and the `k` singular values are in descending order.
+#[derive(Clone, Debug)]
+pub struct SingularValueDecomposition {
+    u: Matrix,
+    sigma: Vec<f64>,
+    v: Matrix, //All n right singular vectors, since the trailing ones span the null space.
+    rows: usize,
+    cols: usize
+}
+impl SingularValueDecomposition {
+    const MAX_SWEEPS: usize = 60;
+
+    pub fn new(a: &Matrix) -> Result<Self, Error> {
+        if a.is_error() {
+            return Err(operation_error!("SVD", "no data loaded (error state)"));
+        }
+
+        let (m, n) = (a.rows(), a.cols());
+        //Wide matrices are padded with zero rows, so that the rotations always produce a complete set of right singular vectors.
+        let size = m.max(n);
+        let mut w: Vec<Vec<f64>> = (0..n).map(|j| {
+            let mut col = a.col(j).unwrap_or_default();
+            col.resize(size, 0.0);
+            col
+        }).collect();
+        let mut v: Vec<Vec<f64>> = (0..n).map(|j| {
+            let mut e = vec![0.0; n];
+            e[j] = 1.0;
+            e
+        }).collect();
+
+        let mut converged = false;
+        for _ in 0..Self::MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    let alpha: f64 = w[p].iter().map(|x| x * x).sum();
+                    let beta: f64 = w[q].iter().map(|x| x * x).sum();
+                    let gamma: f64 = w[p].iter().zip(w[q].iter()).map(|(x, y)| x * y).sum();
+                    if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+
+                    //Rotate columns p and q so that they become orthogonal.
+                    let zeta = (beta - alpha) / (2.0 * gamma);
+                    let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                    let c = 1.0 / (1.0 + t * t).sqrt();
+                    let s = c * t;
+                    for cols in [&mut w, &mut v] {
+                        let (left, right) = cols.split_at_mut(q);
+                        for (x, y) in left[p].iter_mut().zip(right[0].iter_mut()) {
+                            let (xp, xq) = (*x, *y);
+                            *x = c * xp - s * xq;
+                            *y = s * xp + c * xq;
+                        }
+                    }
+                }
+            }
+
+            if !rotated {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Err(operation_error!("SVD", "Jacobi rotations failed to converge"));
+        }
+
+        //Each rotated column is sigma_j u_j.
+        let mut order: Vec<(f64, usize)> = w.iter().enumerate().map(|(j, col)| (col.iter().map(|x| x * x).sum::<f64>().sqrt(), j)).collect();
+        order.sort_by(|x, y| y.0.total_cmp(&x.0));
+
+        let k = m.min(n);
+        let tiny = order.first().map(|x| x.0).unwrap_or(0.0) * f64::EPSILON * size as f64;
+        let sigma: Vec<f64> = order.iter().take(k).map(|x| x.0).collect();
+        let mut u_cols: Vec<Vec<f64>> = Vec::with_capacity(k);
+        for (s, j) in order.iter().take(k) {
+            if *s > tiny {
+                u_cols.push(w[*j].iter().take(m).map(|x| x / s).collect());
+            }
+        }
+        //Columns belonging to (numerically) zero singular values are arbitrary, so they are completed to an orthonormal set.
+        let mut e_index = 0;
+        while u_cols.len() < k && e_index < m {
+            let mut candidate = vec![0.0; m];
+            candidate[e_index] = 1.0;
+            e_index += 1;
+            for _ in 0..2 {
+                for col in u_cols.iter() {
+                    let dot: f64 = col.iter().zip(candidate.iter()).map(|(x, y)| x * y).sum();
+                    for (c, x) in candidate.iter_mut().zip(col.iter()) {
+                        *c -= dot * x;
+                    }
+                }
+            }
+            let norm = candidate.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm > 0.5 {
+                u_cols.push(candidate.into_iter().map(|x| x / norm).collect());
+            }
+        }
+
+        let v_cols: Vec<Vec<f64>> = order.iter().map(|(_, j)| v[*j].clone()).collect();
+
+        Ok(
+            Self {
+                u: Matrix::from_cols(u_cols)?,
+                sigma,
+                v: Matrix::from_cols(v_cols)?,
+                rows: m,
+                cols: n
+            }
+        )
+    }
+
+    /// The left singular vectors, as the columns of an `m` by `k` matrix.
+    pub fn u(&self) -> Matrix {
+        self.u.clone()
+    }
+    pub fn singular_values(&self) -> &[f64] {
+        &self.sigma
+    }
+    /// The right singular vectors, as the columns of an `n` by `k` matrix.
+    pub fn v(&self) -> Matrix {
+        let k = self.sigma.len();
+        Matrix::from_cols((0..k).filter_map(|j| self.v.col(j)).collect()).unwrap_or_default()
+    }
+
+    /// The threshold under which singular values are treated as zero, `max(m, n) * eps * sigma_max`.
+    pub fn default_tolerance(&self) -> f64 {
+        self.rows.max(self.cols) as f64 * f64::EPSILON * self.sigma.first().copied().unwrap_or(0.0)
+    }
+    /// The number of singular values above `tol`.
+    pub fn rank_with(&self, tol: f64) -> usize {
+        self.sigma.iter().filter(|s| **s > tol).count()
+    }
+    pub fn rank(&self) -> usize {
+        self.rank_with(self.default_tolerance())
+    }
+    /// The 2-norm condition number, `sigma_max / sigma_min`. This is infinite for singular matrices.
+    pub fn condition_number(&self) -> f64 {
+        match (self.sigma.first(), self.sigma.last()) {
+            (Some(max), Some(min)) if *min > 0.0 => max / min,
+            _ => f64::INFINITY
+        }
+    }
+    /// The Moore-Penrose pseudoinverse, `V Σ^+ U^T`, where singular values at or below the default tolerance are dropped.
+    pub fn pseudoinverse(&self) -> Matrix {
+        let tol = self.default_tolerance();
+        let mut result = Matrix::zeros(self.cols, self.rows);
+        for (k, s) in self.sigma.iter().enumerate() {
+            if *s <= tol {
+                continue;
+            }
+            for i in 0..self.cols {
+                let scaled = self.v[(i, k)] / s;
+                for j in 0..self.rows {
+                    result[(i, j)] += scaled * self.u[(j, k)];
+                }
+            }
+        }
+
+        result
+    }
+    /// An orthonormal basis for the range (column space) of `A`.
+    pub fn range(&self) -> Vec<MVector<Scalar>> {
+        (0..self.rank())
+            .filter_map(|j| self.u.col(j))
+            .map(|col| MVector::from(col.into_iter().map(Scalar::from).collect::<Vec<_>>()))
+            .collect()
+    }
+    /// An orthonormal basis for the null space of `A`.
+    pub fn null_space(&self) -> Vec<MVector<Scalar>> {
+        (self.rank()..self.cols)
+            .filter_map(|j| self.v.col(j))
+            .map(|col| MVector::from(col.into_iter().map(Scalar::from).collect::<Vec<_>>()))
+            .collect()
+    }
+}
+
+#[test]
+fn test_lu_decomposition() {
+    let a = Matrix::from_rows(vec![vec![0.0, 2.0, 1.0], vec![1.0, 1.0, 0.0], vec![2.0, 1.0, 3.0]]).unwrap();
+    let lu = LUDecomposition::new(&a).unwrap();
+    let pa = (lu.p() * a.clone()).unwrap();
+    let l_u = (lu.l() * lu.u()).unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((pa[(i, j)] - l_u[(i, j)]).abs() < 1e-12);
+        }
+    }
+
+    assert!((a.determinant().unwrap() - -7.0).abs() < 1e-12);
+
+    let inv = a.inverse().unwrap();
+    let product = (a.clone() * inv).unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((product[(i, j)] - expected).abs() < 1e-12);
+        }
+    }
+
+    let b = MVector::from(vec![Scalar::from(3.0), Scalar::from(2.0), Scalar::from(6.0)]);
+    let x = a.solve(&b).unwrap();
+    let back = (a.clone() * x).unwrap();
+    for i in 0..3 {
+        assert!((f64::from(back[i].clone()) - f64::from(b[i].clone())).abs() < 1e-12);
+    }
+
+    let singular = Matrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+    assert_eq!(singular.determinant().unwrap(), 0.0);
+    assert!(singular.inverse().is_err());
+    assert!(singular.solve(&MVector::from(vec![Scalar::from(1.0), Scalar::from(1.0)])).is_err());
+    assert!(Matrix::zeros(2, 3).determinant().is_err());
+}
+
+#[test]
+fn test_qr_decomposition() {
+    let a = Matrix::from_rows(vec![vec![12.0, -51.0, 4.0], vec![6.0, 167.0, -68.0], vec![-4.0, 24.0, -41.0]]).unwrap();
+    let qr = a.qr().unwrap();
+    let (q, r) = (qr.q(), qr.r());
+    let rebuilt = (q.clone() * r.clone()).unwrap();
+    let qtq = (q.transpose() * q).unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((rebuilt[(i, j)] - a[(i, j)]).abs() < 1e-10);
+            assert!((qtq[(i, j)] - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+            if i > j {
+                assert_eq!(r[(i, j)], 0.0);
+            }
+        }
+    }
+
+    //Fitting y = c0 + c1 t through (0, 1), (1, 3), (2, 4), (3, 4) gives c0 = 1.5, c1 = 1, with residuals (-0.5, 0.5, 0.5, -0.5).
+    let design = Matrix::from_rows(vec![vec![1.0, 0.0], vec![1.0, 1.0], vec![1.0, 2.0], vec![1.0, 3.0]]).unwrap();
+    let y = MVector::from(vec![Scalar::from(1.0), Scalar::from(3.0), Scalar::from(4.0), Scalar::from(4.0)]);
+    let (c, residual) = design.least_squares(&y).unwrap();
+    assert!((f64::from(c[0].clone()) - 1.5).abs() < 1e-12);
+    assert!((f64::from(c[1].clone()) - 1.0).abs() < 1e-12);
+    assert!((residual - 1.0).abs() < 1e-12);
+
+    let deficient = Matrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]]).unwrap();
+    assert!(deficient.least_squares(&MVector::from(vec![Scalar::from(1.0); 3])).is_err());
+    assert!(design.transpose().least_squares(&MVector::from(vec![Scalar::from(1.0); 2])).is_err());
+}
+
+#[test]
+fn test_eigen_decomposition() {
+    fn check(a: &Matrix, eigen: &EigenDecomposition) {
+        let n = a.rows();
+        for (lambda, v) in eigen.values().iter().zip(eigen.vectors()) {
+            for i in 0..n {
+                let mut av = Complex::default();
+                for j in 0..n {
+                    av += v[j] * a[(i, j)];
+                }
+                assert!(av.approx_eq(&(v[i] * *lambda), 1e-9), "A v != lambda v for lambda = {}", lambda);
+            }
+        }
+    }
+
+    let symmetric = Matrix::from_rows(vec![vec![2.0, 1.0, 0.0], vec![1.0, 2.0, 1.0], vec![0.0, 1.0, 2.0]]).unwrap();
+    let eigen = symmetric.eigen().unwrap();
+    let root2 = 2f64.sqrt();
+    for (value, expected) in eigen.values().iter().zip([2.0 - root2, 2.0, 2.0 + root2]) {
+        assert!(value.approx_eq(&Complex::from(expected), 1e-12));
+    }
+    check(&symmetric, &eigen);
+
+    let rotation = Matrix::from_rows(vec![vec![0.0, -1.0], vec![1.0, 0.0]]).unwrap();
+    let eigen = rotation.eigen().unwrap();
+    assert!(eigen.values()[0].approx_eq(&-Complex::I, 1e-12));
+    assert!(eigen.values()[1].approx_eq(&Complex::I, 1e-12));
+    check(&rotation, &eigen);
+
+    //Companion matrix of (x - 1)(x - 2)(x^2 + 2x + 5), which has roots 1, 2 and -1 ± 2i.
+    let companion = Matrix::from_rows(vec![
+        vec![0.0, 0.0, 0.0, -10.0],
+        vec![1.0, 0.0, 0.0, 11.0],
+        vec![0.0, 1.0, 0.0, -1.0],
+        vec![0.0, 0.0, 1.0, 1.0]
+    ]).unwrap();
+    let eigen = companion.eigen().unwrap();
+    let expected = [Complex::new(-1.0, -2.0), Complex::new(-1.0, 2.0), Complex::from(1.0), Complex::from(2.0)];
+    for (value, expected) in eigen.values().iter().zip(expected) {
+        assert!(value.approx_eq(&expected, 1e-9), "got {}, expected {}", value, expected);
+    }
+    check(&companion, &eigen);
+
+    assert!(Matrix::zeros(2, 3).eigen().is_err());
+}
+
+#[test]
+fn test_singular_value_decomposition() {
+    fn assert_close(a: &Matrix, b: &Matrix) {
+        assert_eq!((a.rows(), a.cols()), (b.rows(), b.cols()));
+        for i in 0..a.rows() {
+            for j in 0..a.cols() {
+                assert!((a[(i, j)] - b[(i, j)]).abs() < 1e-10, "{} != {}", a, b);
+            }
+        }
+    }
+
+    let a = Matrix::from_rows(vec![vec![3.0, 2.0, 2.0], vec![2.0, 3.0, -2.0]]).unwrap();
+    let svd = a.svd().unwrap();
+    assert!((svd.singular_values()[0] - 5.0).abs() < 1e-12);
+    assert!((svd.singular_values()[1] - 3.0).abs() < 1e-12);
+    let rebuilt = ((svd.u() * Matrix::diagonal(svd.singular_values())).unwrap() * svd.v().transpose()).unwrap();
+    assert_close(&rebuilt, &a);
+    assert_eq!(svd.rank(), 2);
+    assert!((svd.condition_number() - 5.0 / 3.0).abs() < 1e-12);
+
+    //The pseudoinverse of a full row rank matrix is a right inverse.
+    assert_close(&(a.clone() * a.pseudoinverse().unwrap()).unwrap(), &Matrix::identity(2));
+    let null = svd.null_space();
+    assert_eq!(null.len(), 1);
+    let image = (a.clone() * null[0].clone()).unwrap();
+    assert!(image.magnitude().unwrap() < 1e-12);
+    assert_eq!(svd.range().len(), 2);
+
+    let deficient = Matrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]]).unwrap();
+    let svd = deficient.svd().unwrap();
+    assert_eq!(deficient.rank().unwrap(), 1);
+    assert_eq!(svd.condition_number(), f64::INFINITY);
+    assert_eq!(svd.null_space().len(), 1);
+    let pinv = deficient.pseudoinverse().unwrap();
+    assert_close(&((deficient.clone() * pinv).unwrap() * deficient.clone()).unwrap(), &deficient);
+}
@@ -0,0 +1,186 @@
+use crate::{operation_error, argument_error, core::errors::Error};
+use super::{variable_type::SimpleNumerical, vector::MVector};
+
+//Gauss-Jordan elimination over any SimpleNumerical element, given as a list of rows.
+//With an exact element type such as Rational, every step is exact. Floating point matrices should prefer the LU path on Matrix, which uses a tolerance.
+
+fn one<T: SimpleNumerical>() -> T {
+    let mut result = T::default();
+    result += 1.0;
+    result
+}
+fn is_zero<T: SimpleNumerical>(value: &T) -> bool {
+    value.abs() == 0.0
+}
+fn check_rows<T: SimpleNumerical>(rows: &[MVector<T>]) -> Result<usize, Error> {
+    let cols = rows.first().map(|r| r.dim()).unwrap_or(0);
+    if rows.iter().any(|r| r.dim() != cols) {
+        return Err(argument_error!("rows", "every row must have the same dimension"));
+    }
+
+    Ok(cols)
+}
+/// Undefined values (such as an overflowed `Rational`) report an absolute value of `NaN`.
+fn check_defined<T: SimpleNumerical>(rows: &[MVector<T>]) -> Result<(), Error> {
+    if rows.iter().any(|r| r.iter().any(|x| x.abs().is_nan())) {
+        return Err(operation_error!("elimination", "an entry overflowed or became undefined"));
+    }
+
+    Ok(())
+}
+
+/// Brings `rows` into reduced row echelon form in place, returning the pivot column of each non-zero row.
+#[allow(clippy::needless_range_loop)]
+pub fn row_reduce<T: SimpleNumerical>(rows: &mut [MVector<T>]) -> Result<Vec<usize>, Error> {
+    let cols = check_rows(rows)?;
+    let mut pivots = vec![];
+
+    let mut r = 0usize;
+    for c in 0..cols {
+        if r == rows.len() {
+            break;
+        }
+
+        let Some(p) = (r..rows.len()).filter(|&i| !is_zero(&rows[i][c])).max_by(|&a, &b| rows[a][c].abs().total_cmp(&rows[b][c].abs())) else {
+            continue;
+        };
+        if p != r {
+            rows.swap(p, r);
+        }
+
+        let pivot = rows[r][c].clone();
+        for j in c..cols {
+            rows[r][j] /= pivot.clone();
+        }
+        for i in 0..rows.len() {
+            if i == r || is_zero(&rows[i][c]) {
+                continue;
+            }
+
+            let factor = rows[i][c].clone();
+            for j in c..cols {
+                let mut term = rows[r][j].clone();
+                term *= factor.clone();
+                rows[i][j] -= term;
+            }
+        }
+
+        pivots.push(c);
+        r += 1;
+    }
+
+    check_defined(rows)?;
+    Ok(pivots)
+}
+
+/// The number of linearly independent rows.
+pub fn rank<T: SimpleNumerical>(rows: &[MVector<T>]) -> Result<usize, Error> {
+    let mut rows = rows.to_vec();
+    Ok( row_reduce(&mut rows)?.len() )
+}
+
+/// The determinant of a square matrix, computed by forward elimination.
+#[allow(clippy::needless_range_loop)]
+pub fn determinant<T: SimpleNumerical>(rows: &[MVector<T>]) -> Result<T, Error> {
+    let n = check_rows(rows)?;
+    if n != rows.len() {
+        return Err(operation_error!("determinant", "matrix must be square"));
+    }
+
+    let mut rows = rows.to_vec();
+    let mut result = one::<T>();
+    for c in 0..n {
+        let Some(p) = (c..n).filter(|&i| !is_zero(&rows[i][c])).max_by(|&a, &b| rows[a][c].abs().total_cmp(&rows[b][c].abs())) else {
+            return Ok(T::default());
+        };
+        if p != c {
+            rows.swap(p, c);
+            result *= -1.0;
+        }
+
+        let pivot = rows[c][c].clone();
+        result *= pivot.clone();
+        for i in (c + 1)..n {
+            let mut factor = rows[i][c].clone();
+            factor /= pivot.clone();
+            for j in c..n {
+                let mut term = rows[c][j].clone();
+                term *= factor.clone();
+                rows[i][j] -= term;
+            }
+        }
+    }
+
+    if result.abs().is_nan() {
+        return Err(operation_error!("determinant", "an entry overflowed or became undefined"));
+    }
+    Ok(result)
+}
+
+/// Solves `A x = b` for a square, non-singular `A` given by its rows.
+pub fn solve<T: SimpleNumerical>(rows: &[MVector<T>], b: &MVector<T>) -> Result<MVector<T>, Error> {
+    let n = check_rows(rows)?;
+    if n != rows.len() || b.dim() != n {
+        return Err(operation_error!("solve", "expected a square matrix and a right hand side of matching dimension"));
+    }
+
+    let mut augmented: Vec<MVector<T>> = rows.iter().zip(b.iter()).map(|(row, x)| {
+        let mut data: Vec<T> = row.iter().cloned().collect();
+        data.push(x.clone());
+        MVector::from(data)
+    }).collect();
+
+    let pivots = row_reduce(&mut augmented)?;
+    if pivots.len() != n || pivots.iter().any(|&c| c >= n) {
+        return Err(operation_error!("solve", "matrix is singular"));
+    }
+
+    Ok( MVector::from(augmented.into_iter().map(|r| r[n].clone()).collect::<Vec<T>>()) )
+}
+
+/// The inverse of a square, non-singular matrix, returned as its rows.
+pub fn inverse<T: SimpleNumerical>(rows: &[MVector<T>]) -> Result<Vec<MVector<T>>, Error> {
+    let n = check_rows(rows)?;
+    if n != rows.len() {
+        return Err(operation_error!("inverse", "matrix must be square"));
+    }
+
+    let mut augmented: Vec<MVector<T>> = rows.iter().enumerate().map(|(i, row)| {
+        let mut data: Vec<T> = row.iter().cloned().collect();
+        data.extend((0..n).map(|j| if i == j { one::<T>() } else { T::default() }));
+        MVector::from(data)
+    }).collect();
+
+    let pivots = row_reduce(&mut augmented)?;
+    if pivots.len() != n || pivots.iter().any(|&c| c >= n) {
+        return Err(operation_error!("inverse", "matrix is singular"));
+    }
+
+    Ok( augmented.into_iter().map(|r| MVector::from(r.iter().skip(n).cloned().collect::<Vec<T>>())).collect() )
+}
+
+#[test]
+fn test_rational_elimination() {
+    use super::rational::Rational;
+    let r = |n, d| Rational::new(n, d).unwrap();
+    let row = |v: &[i64]| MVector::from(v.iter().map(|&x| Rational::from(x)).collect::<Vec<_>>());
+
+    let a = vec![ row(&[2, 1, 1]), row(&[1, 3, 2]), row(&[1, 0, 0]) ];
+    let b = row(&[4, 5, 6]);
+
+    let x = solve(&a, &b).unwrap();
+    assert_eq!(x, MVector::from(vec![ r(6, 1), r(15, 1), r(-23, 1) ]));
+    assert_eq!(determinant(&a).unwrap(), r(-1, 1));
+
+    let hilbert: Vec<MVector<Rational>> = (1..=3).map(|i| MVector::from((1..=3).map(|j| r(1, i + j - 1)).collect::<Vec<_>>())).collect();
+    assert_eq!(determinant(&hilbert).unwrap(), r(1, 2160));
+    let inv = inverse(&hilbert).unwrap();
+    assert_eq!(inv[0], row(&[9, -36, 30]));
+    assert_eq!(inv[1], row(&[-36, 192, -180]));
+    assert_eq!(inv[2], row(&[30, -180, 180]));
+
+    let singular = vec![ row(&[1, 2]), row(&[2, 4]) ];
+    assert_eq!(rank(&singular).unwrap(), 1);
+    assert!(solve(&singular, &row(&[1, 1])).is_err());
+    assert_eq!(determinant(&singular).unwrap(), Rational::default());
+}
@@ -0,0 +1,348 @@
+use std::fmt::{Debug, Display, Binary, Octal, LowerHex, UpperHex};
+use std::ops::{Add, Sub, Mul, Div, Rem, Neg, BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+use crate::{binary_unit, conversion_error, operation_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use crate::io::sesssion::session_settings;
+use super::{variable_type::VariableType, rational::Rational, bigint::BigInt, modular::ModInt, number_theory, radix::{Radix, WordFormat}};
+
+/// A 64-bit signed integer. Arithmetic is checked, so overflow is an error rather than a wrap, and `/` gives the exact `Rational` quotient.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Integer {
+    data: i64
+}
+impl Debug for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Integer:{})", self.data)
+    }
+}
+impl Display for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data)
+    }
+}
+//The formatting traits show the full 64-bit two's complement pattern. Use `to_radix` for a specific word.
+impl Binary for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Binary::fmt(&self.data, f)
+    }
+}
+impl Octal for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Octal::fmt(&self.data, f)
+    }
+}
+impl LowerHex for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.data, f)
+    }
+}
+impl UpperHex for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        UpperHex::fmt(&self.data, f)
+    }
+}
+impl From<i64> for Integer {
+    fn from(value: i64) -> Self {
+        Self {
+            data: value
+        }
+    }
+}
+impl From<Integer> for i64 {
+    fn from(value: Integer) -> Self {
+        value.data
+    }
+}
+impl From<Integer> for f64 {
+    fn from(value: Integer) -> Self {
+        value.data as f64
+    }
+}
+impl From<Integer> for Rational {
+    fn from(value: Integer) -> Self {
+        Rational::from(value.data)
+    }
+}
+impl From<Integer> for BigInt {
+    fn from(value: Integer) -> Self {
+        BigInt::from(value.data)
+    }
+}
+impl TryFrom<Rational> for Integer {
+    type Error = Error;
+    fn try_from(value: Rational) -> Result<Self, Self::Error> {
+        if !value.is_integer() {
+            return Err(conversion_error!("{} is not an integer", value));
+        }
+
+        Ok( Self::from(value.numerator()) )
+    }
+}
+impl From<Integer> for Vec<BinaryUnit> {
+    fn from(value: Integer) -> Self {
+        vec![ binary_unit!(value.data) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Integer {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        match value.into_iter().next() {
+            None => Err(conversion_error!("expected at least one unit, but got none")),
+            Some(d) => {
+                let as_int: i64 = d.try_into()?;
+                Ok(Self::from(as_int))
+            }
+        }
+    }
+}
+impl VariableType for Integer {
+    fn required_units(&self) -> usize {
+        1usize
+    }
+}
+impl Neg for Integer {
+    type Output = Result<Self, Error>;
+    fn neg(self) -> Self::Output {
+        self.data.checked_neg().map(Self::from).ok_or_else(|| operator_error!('-', self))
+    }
+}
+impl Add for Integer {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.data.checked_add(rhs.data).map(Self::from).ok_or_else(|| operator_error!('+', self, rhs))
+    }
+}
+impl Sub for Integer {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.data.checked_sub(rhs.data).map(Self::from).ok_or_else(|| operator_error!('-', self, rhs))
+    }
+}
+impl Mul for Integer {
+    type Output = Result<Self, Error>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.data.checked_mul(rhs.data).map(Self::from).ok_or_else(|| operator_error!('*', self, rhs))
+    }
+}
+impl Div for Integer {
+    type Output = Result<Rational, Error>;
+    /// The exact quotient, so `7 / 2` is `7/2` rather than `3`. Use `div_floor` or `div_exact` for an integer result.
+    fn div(self, rhs: Self) -> Self::Output {
+        Rational::new(self.data, rhs.data).map_err(|_| operator_error!('/', self, rhs))
+    }
+}
+impl Rem for Integer {
+    type Output = Result<Self, Error>;
+    /// The remainder of floored division, which takes the sign of `rhs`, so `-7 % 3` is `2`.
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_floor_rem(rhs).map(|(_, r)| r).map_err(|_| operator_error!('%', self, rhs))
+    }
+}
+//The bitwise operators work within the session's word format.
+impl BitAnd for Integer {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.and_with(&rhs, session_settings.word_format())
+    }
+}
+impl BitOr for Integer {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.or_with(&rhs, session_settings.word_format())
+    }
+}
+impl BitXor for Integer {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        self.xor_with(&rhs, session_settings.word_format())
+    }
+}
+impl Not for Integer {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        self.not_with(session_settings.word_format())
+    }
+}
+impl Shl<u32> for Integer {
+    type Output = Self;
+    fn shl(self, rhs: u32) -> Self::Output {
+        self.shl_with(rhs, session_settings.word_format())
+    }
+}
+impl Shr<u32> for Integer {
+    type Output = Self;
+    fn shr(self, rhs: u32) -> Self::Output {
+        self.shr_with(rhs, session_settings.word_format())
+    }
+}
+
+impl Integer {
+    pub fn value(&self) -> i64 {
+        self.data
+    }
+
+    fn div_floor_rem(&self, rhs: Self) -> Result<(Self, Self), Error> {
+        if rhs.data == 0 {
+            return Err(operation_error!("divide", "division by zero"));
+        }
+
+        let (a, b) = (self.data as i128, rhs.data as i128);
+        let (mut q, mut r) = (a / b, a % b);
+        if r != 0 && (r < 0) != (b < 0) {
+            q -= 1;
+            r += b;
+        }
+
+        let q = i64::try_from(q).map_err(|_| operation_error!("divide", "{} / {} overflows", self, rhs))?;
+        Ok( (Self::from(q), Self::from(r as i64)) )
+    }
+    /// The quotient rounded toward negative infinity.
+    pub fn div_floor(&self, rhs: Self) -> Result<Self, Error> {
+        Ok( self.div_floor_rem(rhs)?.0 )
+    }
+    /// The quotient, which must have no remainder.
+    pub fn div_exact(&self, rhs: Self) -> Result<Self, Error> {
+        let (q, r) = self.div_floor_rem(rhs)?;
+        if r.data != 0 {
+            return Err(operation_error!("exact division", "{} is not divisible by {}", self, rhs));
+        }
+
+        Ok(q)
+    }
+    pub fn divides(&self, rhs: &Self) -> bool {
+        match self.data {
+            0 => rhs.data == 0,
+            d => rhs.data.checked_rem(d).is_none_or(|r| r == 0)
+        }
+    }
+    pub fn abs(&self) -> Result<Self, Error> {
+        self.data.checked_abs().map(Self::from).ok_or_else(|| operation_error!("abs", "|{}| overflows", self))
+    }
+    pub fn pow(&self, exp: u32) -> Result<Self, Error> {
+        self.data.checked_pow(exp).map(Self::from).ok_or_else(|| operation_error!("pow", "{}^{} overflows", self, exp))
+    }
+    /// The value as an element of the integers mod `modulus`.
+    pub fn modulo(&self, modulus: u64) -> Result<ModInt, Error> {
+        ModInt::new(self.data, modulus)
+    }
+
+    pub fn gcd(&self, rhs: &Self) -> u64 {
+        number_theory::gcd(self.data, rhs.data)
+    }
+    pub fn lcm(&self, rhs: &Self) -> Result<u64, Error> {
+        number_theory::lcm(self.data, rhs.data)
+    }
+    /// Whether the value is a (positive) prime.
+    pub fn is_prime(&self) -> bool {
+        self.data > 0 && number_theory::is_prime(self.data as u64)
+    }
+    /// The prime factorization of `|self|`.
+    pub fn factorize(&self) -> Result<Vec<(u64, u32)>, Error> {
+        number_theory::factorize(self.data.unsigned_abs())
+    }
+    /// Euler's totient, which is only defined for positive values.
+    pub fn totient(&self) -> Result<u64, Error> {
+        if self.data <= 0 {
+            return Err(operation_error!("totient", "only defined for positive integers, got {}", self));
+        }
+
+        number_theory::totient(self.data as u64)
+    }
+}
+
+impl Integer {
+    /// Writes the value in `radix`, within the session's word format.
+    pub fn to_radix(&self, radix: Radix) -> String {
+        self.to_radix_with(radix, session_settings.word_format())
+    }
+    pub fn to_radix_with(&self, radix: Radix, word: WordFormat) -> String {
+        word.format(self.data, radix)
+    }
+    /// Reads a decimal, `0b`, `0o` or `0x` literal, within the session's word format.
+    pub fn parse_radix(s: &str) -> Result<Self, Error> {
+        Self::parse_radix_with(s, session_settings.word_format())
+    }
+    pub fn parse_radix_with(s: &str, word: WordFormat) -> Result<Self, Error> {
+        Ok( Self::from(word.parse(s)?) )
+    }
+
+    pub fn and_with(&self, rhs: &Self, word: WordFormat) -> Self {
+        Self::from(word.wrap(self.data & rhs.data))
+    }
+    pub fn or_with(&self, rhs: &Self, word: WordFormat) -> Self {
+        Self::from(word.wrap(self.data | rhs.data))
+    }
+    pub fn xor_with(&self, rhs: &Self, word: WordFormat) -> Self {
+        Self::from(word.wrap(self.data ^ rhs.data))
+    }
+    pub fn not_with(&self, word: WordFormat) -> Self {
+        Self::from(word.wrap(!self.data))
+    }
+    /// Shifts left, dropping the bits that leave the word.
+    pub fn shl_with(&self, n: u32, word: WordFormat) -> Self {
+        if n >= word.width() {
+            return Self::from(0);
+        }
+
+        Self::from(word.wrap((word.bits(self.data) << n) as i64))
+    }
+    /// Shifts right, copying the sign bit in for signed words (arithmetic) and zeros for unsigned words (logical).
+    pub fn shr_with(&self, n: u32, word: WordFormat) -> Self {
+        if word.is_signed() {
+            Self::from(word.wrap(self.data) >> n.min(63))
+        }
+        else {
+            Self::from(word.bits(self.data).checked_shr(n).unwrap_or(0) as i64)
+        }
+    }
+    /// Rotates left, so bits leaving the top of the word come back in at the bottom.
+    pub fn rotate_left_with(&self, n: u32, word: WordFormat) -> Self {
+        let (bits, width) = (word.bits(self.data) as u128, word.width());
+        let n = n % width;
+        Self::from(word.wrap((((bits << n) | (bits >> (width - n))) as u64 & word.mask()) as i64))
+    }
+    pub fn rotate_right_with(&self, n: u32, word: WordFormat) -> Self {
+        self.rotate_left_with(word.width() - n % word.width(), word)
+    }
+    pub fn rotate_left(&self, n: u32) -> Self {
+        self.rotate_left_with(n, session_settings.word_format())
+    }
+    pub fn rotate_right(&self, n: u32) -> Self {
+        self.rotate_right_with(n, session_settings.word_format())
+    }
+}
+
+#[test]
+fn test_integer_arithmetic() {
+    let i = Integer::from;
+    assert_eq!((i(7) + i(5)).unwrap(), i(12));
+    assert_eq!((i(7) * i(-5)).unwrap(), i(-35));
+    assert!((i(i64::MAX) + i(1)).is_err());
+    assert!((-i(i64::MIN)).is_err());
+
+    assert_eq!((i(7) / i(2)).unwrap(), Rational::new(7, 2).unwrap());
+    assert_eq!(Integer::try_from((i(12) / i(4)).unwrap()).unwrap(), i(3));
+    assert!((i(1) / i(0)).is_err());
+    assert_eq!(i(-7).div_floor(i(2)).unwrap(), i(-4));
+    assert_eq!((i(-7) % i(3)).unwrap(), i(2));
+    assert_eq!((i(7) % i(-3)).unwrap(), i(-2));
+    assert_eq!(i(84).div_exact(i(-12)).unwrap(), i(-7));
+    assert!(i(84).div_exact(i(5)).is_err());
+    assert!(i(i64::MIN).div_floor(i(-1)).is_err());
+    assert!(i(6).divides(&i(-42)));
+    assert!(i(-1).divides(&i(i64::MIN)));
+
+    assert_eq!(i(2).pow(62).unwrap(), i(1 << 62));
+    assert!(i(2).pow(63).is_err());
+    assert_eq!(i(-15).modulo(4).unwrap(), ModInt::new(1, 4).unwrap());
+    assert_eq!(i(12).gcd(&i(-18)), 6);
+    assert_eq!(i(12).lcm(&i(-18)).unwrap(), 36);
+    assert!(i(1_000_000_007).is_prime());
+    assert!(!i(-7).is_prime());
+    assert_eq!(i(-84).factorize().unwrap(), vec![(2, 2), (3, 1), (7, 1)]);
+    assert_eq!(i(84).totient().unwrap(), 24);
+    assert!(i(0).totient().is_err());
+
+    let units: Vec<BinaryUnit> = i(-42).into();
+    assert_eq!(Integer::try_from(units).unwrap(), i(-42));
+}
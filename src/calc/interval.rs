@@ -0,0 +1,392 @@
+use std::f64::consts::{PI, FRAC_PI_2};
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg};
+
+use crate::{binary_unit, argument_error, conversion_error, operator_error, operation_error, core::{io::BinaryUnit, errors::Error}};
+use super::variable_type::{VariableType, SimpleNumerical};
+
+/// A closed interval `[lo, hi]` of real numbers.
+/// Every operation rounds its bounds outward, so the result always encloses every value the operation could produce from values inside its operands.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct Interval {
+    lo: f64,
+    hi: f64
+}
+impl Debug for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Interval:{})", self)
+    }
+}
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+impl From<Interval> for Vec<BinaryUnit> {
+    fn from(value: Interval) -> Self {
+        vec![ binary_unit!(value.lo), binary_unit!(value.hi) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Interval {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.len() != 2 {
+            return Err(conversion_error!("expected 2 units, got {}", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let lo: f64 = iter.next().unwrap().try_into()?;
+        let hi: f64 = iter.next().unwrap().try_into()?;
+        Self::new(lo, hi).map_err(|_| conversion_error!("[{}, {}] is not a valid interval", lo, hi))
+    }
+}
+impl From<f64> for Interval {
+    fn from(value: f64) -> Self {
+        Self::point(value)
+    }
+}
+impl VariableType for Interval {
+    fn required_units(&self) -> usize {
+        2usize
+    }
+}
+impl Neg for Interval {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo
+        }
+    }
+}
+impl Add for Interval {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::outward(self.lo + rhs.lo, self.hi + rhs.hi)
+    }
+}
+impl Sub for Interval {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::outward(self.lo - rhs.hi, self.hi - rhs.lo)
+    }
+}
+impl Mul for Interval {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        //In interval arithmetic, zero times an infinite bound is zero.
+        let product = |a: f64, b: f64| if a == 0.0 || b == 0.0 { 0.0 } else { a * b };
+        let products = [product(self.lo, rhs.lo), product(self.lo, rhs.hi), product(self.hi, rhs.lo), product(self.hi, rhs.hi)];
+
+        Self::outward(products.iter().copied().fold(f64::INFINITY, f64::min), products.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+    }
+}
+impl Div for Interval {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.contains(0.0) {
+            return Err(operator_error!('/', self, rhs));
+        }
+
+        let quotients = [self.lo / rhs.lo, self.lo / rhs.hi, self.hi / rhs.lo, self.hi / rhs.hi];
+        Ok( Self::outward(quotients.iter().copied().fold(f64::INFINITY, f64::min), quotients.iter().copied().fold(f64::NEG_INFINITY, f64::max)) )
+    }
+}
+impl AddAssign for Interval {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl AddAssign<f64> for Interval {
+    fn add_assign(&mut self, rhs: f64) {
+        *self = *self + Self::point(rhs);
+    }
+}
+impl SubAssign for Interval {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+impl SubAssign<f64> for Interval {
+    fn sub_assign(&mut self, rhs: f64) {
+        *self = *self - Self::point(rhs);
+    }
+}
+impl MulAssign for Interval {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+impl MulAssign<f64> for Interval {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * Self::point(rhs);
+    }
+}
+impl DivAssign for Interval {
+    /// Dividing by an interval that contains zero leaves the whole real line, which is still a valid enclosure.
+    fn div_assign(&mut self, rhs: Self) {
+        *self = (*self / rhs).unwrap_or_else(|_| Self::entire());
+    }
+}
+impl DivAssign<f64> for Interval {
+    fn div_assign(&mut self, rhs: f64) {
+        *self /= Self::point(rhs);
+    }
+}
+impl SimpleNumerical for Interval {
+    /// The magnitude, the largest absolute value inside the interval.
+    fn abs(&self) -> f64 {
+        self.lo.abs().max(self.hi.abs())
+    }
+}
+
+/// How many units in the last place the results of the standard library's elementary functions are widened by. They are accurate to within one, so two leaves a margin.
+const LIBM_ULPS: usize = 2;
+
+impl Interval {
+    /// `[lo, hi]`, which fails if `lo > hi` or either bound is `NaN`.
+    pub fn new(lo: f64, hi: f64) -> Result<Self, Error> {
+        if lo.is_nan() || hi.is_nan() || lo > hi {
+            return Err(argument_error!("lo, hi", "[{}, {}] is not a valid interval", lo, hi));
+        }
+
+        Ok( Self { lo, hi } )
+    }
+    /// The degenerate interval `[x, x]`.
+    pub fn point(x: f64) -> Self {
+        Self {
+            lo: x,
+            hi: x
+        }
+    }
+    /// `[nominal - tolerance, nominal + tolerance]`, rounded outward.
+    pub fn with_tolerance(nominal: f64, tolerance: f64) -> Self {
+        let tolerance = tolerance.abs();
+        Self::outward(nominal - tolerance, nominal + tolerance)
+    }
+    /// The whole real line.
+    pub fn entire() -> Self {
+        Self {
+            lo: f64::NEG_INFINITY,
+            hi: f64::INFINITY
+        }
+    }
+    /// Builds the interval from bounds computed with round-to-nearest, stepping each out by one unit in the last place.
+    fn outward(lo: f64, hi: f64) -> Self {
+        Self::widened(lo, hi, 1)
+    }
+    fn widened(mut lo: f64, mut hi: f64, ulps: usize) -> Self {
+        for _ in 0..ulps {
+            lo = lo.next_down();
+            hi = hi.next_up();
+        }
+
+        Self { lo, hi }
+    }
+    /// Applies a monotonically increasing library function to both bounds.
+    fn increasing<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+        Self::widened(f(self.lo), f(self.hi), LIBM_ULPS)
+    }
+    /// Whether the interval contains a point `phase + n pi` with `n` even, and one with `n` odd. Points sitting right on a bound are counted.
+    fn contains_multiples(&self, phase: f64) -> (bool, bool) {
+        if self.width() >= 2.0 * PI || !self.width().is_finite() {
+            return (true, true);
+        }
+
+        let slack = |t: f64| 1e-9 + t.abs() * 4.0 * f64::EPSILON;
+        let (t_lo, t_hi) = ((self.lo - phase) / PI, (self.hi - phase) / PI);
+        let (first, last) = ((t_lo - slack(t_lo)).ceil() as i64, (t_hi + slack(t_hi)).floor() as i64);
+
+        match last - first {
+            n if n < 0 => (false, false),
+            0 => (first.rem_euclid(2) == 0, first.rem_euclid(2) == 1),
+            _ => (true, true)
+        }
+    }
+    /// The range of a function with period `2 pi` that reaches `+1` at `phase + 2k pi` and `-1` at `phase + (2k + 1) pi`, such as sine and cosine.
+    fn periodic<F: Fn(f64) -> f64>(&self, f: F, phase: f64) -> Self {
+        let (maximum, minimum) = self.contains_multiples(phase);
+        let (a, b) = (f(self.lo), f(self.hi));
+        let result = Self::widened(a.min(b), a.max(b), LIBM_ULPS);
+
+        Self {
+            lo: if minimum { -1.0 } else { result.lo.max(-1.0) },
+            hi: if maximum { 1.0 } else { result.hi.min(1.0) }
+        }
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+    pub fn midpoint(&self) -> f64 {
+        self.lo / 2.0 + self.hi / 2.0
+    }
+    pub fn width(&self) -> f64 {
+        (self.hi - self.lo).next_up()
+    }
+    pub fn radius(&self) -> f64 {
+        (self.width() / 2.0).next_up()
+    }
+    pub fn is_point(&self) -> bool {
+        self.lo == self.hi
+    }
+    pub fn is_entire(&self) -> bool {
+        self.lo == f64::NEG_INFINITY && self.hi == f64::INFINITY
+    }
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        other.lo <= self.lo && self.hi <= other.hi
+    }
+    /// The overlap of the two intervals, if there is any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let (lo, hi) = (self.lo.max(other.lo), self.hi.min(other.hi));
+        if lo > hi { None } else { Some(Self { lo, hi }) }
+    }
+    /// The smallest interval containing both.
+    pub fn hull(&self, other: &Self) -> Self {
+        Self {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi)
+        }
+    }
+    /// The interval of absolute values, `{ |x| : x in self }`.
+    pub fn absolute(&self) -> Self {
+        if self.lo >= 0.0 {
+            *self
+        }
+        else if self.hi <= 0.0 {
+            -*self
+        }
+        else {
+            Self { lo: 0.0, hi: self.abs() }
+        }
+    }
+
+    pub fn recip(&self) -> Result<Self, Error> {
+        Self::point(1.0) / *self
+    }
+    pub fn powi(&self, n: i32) -> Result<Self, Error> {
+        //Repeated squaring. The squares are taken of the magnitudes, which keeps them tight and keeps even powers from going negative.
+        let exp = n.unsigned_abs();
+        let mut result = if !exp.is_multiple_of(2) { *self } else { Self::point(1.0) };
+        let mut square = self.absolute();
+        let mut rest = exp >> 1;
+        while rest > 0 {
+            square *= square;
+            if rest & 1 == 1 {
+                result *= square;
+            }
+            rest >>= 1;
+        }
+        if exp.is_multiple_of(2) {
+            result.lo = result.lo.max(0.0);
+        }
+
+        if n < 0 { result.recip() } else { Ok(result) }
+    }
+    pub fn sqrt(&self) -> Result<Self, Error> {
+        if self.hi < 0.0 {
+            return Err(operation_error!("sqrt", "{} is entirely negative", self));
+        }
+
+        //Square roots are correctly rounded, so one step outward is enough.
+        Ok( Self::outward(self.lo.max(0.0).sqrt(), self.hi.sqrt()).intersection(&Self { lo: 0.0, hi: f64::INFINITY }).unwrap() )
+    }
+    pub fn exp(&self) -> Self {
+        let result = self.increasing(f64::exp);
+        Self { lo: result.lo.max(0.0), hi: result.hi }
+    }
+    pub fn ln(&self) -> Result<Self, Error> {
+        if self.hi <= 0.0 {
+            return Err(operation_error!("ln", "{} has no positive values", self));
+        }
+
+        Ok( self.intersection(&Self { lo: 0.0, hi: f64::INFINITY }).unwrap().increasing(f64::ln) )
+    }
+    pub fn sin(&self) -> Self {
+        self.periodic(f64::sin, FRAC_PI_2)
+    }
+    pub fn cos(&self) -> Self {
+        self.periodic(f64::cos, 0.0)
+    }
+    pub fn tan(&self) -> Result<Self, Error> {
+        //Tangent increases between its poles at pi/2 + k pi, so it is only bounded if there is no pole inside.
+        let (even, odd) = self.contains_multiples(FRAC_PI_2);
+        if even || odd {
+            return Err(operation_error!("tan", "{} contains a pole", self));
+        }
+
+        Ok( self.increasing(f64::tan) )
+    }
+    pub fn atan(&self) -> Self {
+        self.increasing(f64::atan)
+    }
+    pub fn asin(&self) -> Result<Self, Error> {
+        let domain = self.intersection(&Self { lo: -1.0, hi: 1.0 }).ok_or_else(|| operation_error!("asin", "{} is outside of [-1, 1]", self))?;
+        Ok( domain.increasing(f64::asin) )
+    }
+    pub fn acos(&self) -> Result<Self, Error> {
+        let domain = self.intersection(&Self { lo: -1.0, hi: 1.0 }).ok_or_else(|| operation_error!("acos", "{} is outside of [-1, 1]", self))?;
+        Ok( Self::widened(domain.hi.acos(), domain.lo.acos(), LIBM_ULPS) )
+    }
+    pub fn sinh(&self) -> Self {
+        self.increasing(f64::sinh)
+    }
+    pub fn cosh(&self) -> Self {
+        let absolute = self.absolute();
+        let result = absolute.increasing(f64::cosh);
+        Self { lo: result.lo.max(1.0), hi: result.hi }
+    }
+    pub fn tanh(&self) -> Self {
+        let result = self.increasing(f64::tanh);
+        Self { lo: result.lo.max(-1.0), hi: result.hi.min(1.0) }
+    }
+}
+
+#[test]
+fn test_interval_enclosures() {
+    let i = |lo, hi| Interval::new(lo, hi).unwrap();
+
+    let sum = Interval::point(0.1) + Interval::point(0.2);
+    assert!(sum.contains(0.1 + 0.2) && sum.lo() < 0.30000000000000004 && sum.hi() > 0.3);
+    assert!((i(-1.0, 2.0) * i(3.0, 4.0)).is_subset_of(&i(-4.0 - 1e-12, 8.0 + 1e-12)));
+    assert!((i(1.0, 2.0) / i(-1.0, 1.0)).is_err());
+    assert_eq!(i(-1.0, 2.0).powi(2).unwrap().lo(), 0.0);
+    let cube = i(-2.0, 1.5).powi(3).unwrap();
+    assert!(cube.contains(-8.0) && cube.contains(3.375) && cube.hi() < 8.0);
+    assert!(i(0.9, 1.1).powi(-5).unwrap().contains(1.1f64.powi(-5)));
+    assert!(i(-1.0, 1.0).powi(i32::MAX).unwrap().contains(-1.0));
+    assert!(Interval::point(1.0).powi(i32::MIN).unwrap().contains(1.0));
+    assert!(Interval::new(2.0, 1.0).is_err());
+
+    let c = i(-0.5, 3.5).cos();
+    assert_eq!(c.hi(), 1.0);
+    assert_eq!(c.lo(), -1.0);
+    let s = i(0.1, 0.2).sin();
+    assert!(s.contains(0.1f64.sin()) && s.contains(0.2f64.sin()) && s.hi() < 0.2);
+    assert!(i(1.0, 2.0).tan().is_err());
+    assert!(i(-1.0, 1.0).tan().unwrap().contains(1.0f64.tan()));
+    assert!(i(-1.0, 0.0).ln().is_err());
+    assert!(i(0.0, 4.0).sqrt().unwrap().contains(2.0));
+
+    //A tolerance stack-up: three parts of 10 ± 0.1 and a gap of 1 ± 0.05.
+    let part = Interval::with_tolerance(10.0, 0.1);
+    let stack = part + part + part + Interval::with_tolerance(1.0, 0.05);
+    assert!(stack.contains(30.65) && stack.contains(31.35) && stack.radius() < 0.35 + 1e-12);
+
+    //Bounding the solutions of [[2, 1], [1, 3]] x = b for every b in [1 ± 0.1, 2 ± 0.1].
+    use super::{vector::MVector, elimination};
+    let rows = vec![ MVector::from(vec![ Interval::point(2.0), Interval::point(1.0) ]), MVector::from(vec![ Interval::point(1.0), Interval::point(3.0) ]) ];
+    let b = MVector::from(vec![ Interval::with_tolerance(1.0, 0.1), Interval::with_tolerance(2.0, 0.1) ]);
+    let x = elimination::solve(&rows, &b).unwrap();
+    assert!(x[0].contains(0.2) && x[1].contains(0.6));
+    assert!(x[0].contains((3.0 * 1.1 - 1.9) / 5.0) && x[1].contains((2.0 * 2.1 - 0.9) / 5.0));
+
+    let units: Vec<BinaryUnit> = stack.into();
+    assert_eq!(Interval::try_from(units).unwrap(), stack);
+}
@@ -0,0 +1,135 @@
+use crate::{operation_error, operator_error, range_error, core::errors::Error};
+use super::variable_data::VariableData;
+
+//Operations on lists. The higher-order functions take any Fn over VariableData, so built-ins such as VariableData::not can be passed directly.
+impl VariableData {
+    fn as_list(&self, action: &str) -> Result<&Vec<VariableData>, Error> {
+        match self {
+            Self::List(l) => Ok(l),
+            _ => Err(operation_error!(action, "expected a list, got {:?}", self))
+        }
+    }
+
+    /// The number of elements in a list.
+    pub fn list_len(&self) -> Result<usize, Error> {
+        Ok( self.as_list("length")?.len() )
+    }
+    /// The element at `index`. Negative indices count back from the end, so `-1` is the last element.
+    pub fn get(&self, index: i64) -> Result<&VariableData, Error> {
+        let list = self.as_list("index")?;
+        let len = list.len() as i64;
+        let position = if index < 0 { index + len } else { index };
+        if position < 0 || position >= len {
+            return Err(range_error!("index", index, -len, len - 1));
+        }
+
+        Ok( &list[position as usize] )
+    }
+    /// Adds `value` to the end of a list.
+    pub fn append(&mut self, value: VariableData) -> Result<(), Error> {
+        match self {
+            Self::List(l) => {
+                l.push(value);
+                Ok(())
+            },
+            _ => Err(operation_error!("append", "expected a list, got {:?}", self))
+        }
+    }
+    /// The elements of `self` followed by the elements of `rhs`.
+    pub fn concat(&self, rhs: &VariableData) -> Result<VariableData, Error> {
+        match (self, rhs) {
+            (Self::List(a), Self::List(b)) => Ok( Self::List(a.iter().chain(b.iter()).cloned().collect()) ),
+            _ => Err(operator_error!("concat", self, rhs))
+        }
+    }
+
+    /// Applies `f` to every element, stopping at the first error.
+    pub fn map<F>(&self, f: F) -> Result<VariableData, Error> where F: Fn(&VariableData) -> Result<VariableData, Error> {
+        Ok( Self::List(self.as_list("map")?.iter().map(f).collect::<Result<Vec<VariableData>, Error>>()?) )
+    }
+    /// Keeps the elements for which `predicate` gives `true`. The predicate must produce a `Bool`.
+    pub fn filter<F>(&self, predicate: F) -> Result<VariableData, Error> where F: Fn(&VariableData) -> Result<VariableData, Error> {
+        let mut result = vec![];
+        for item in self.as_list("filter")? {
+            match predicate(item)? {
+                Self::Bool(b) if b.value() => result.push(item.clone()),
+                Self::Bool(_) => {},
+                other => return Err(operation_error!("filter", "the predicate must give a Bool, but gave {:?}", other))
+            }
+        }
+
+        Ok( Self::List(result) )
+    }
+    /// Combines the elements from left to right, starting from `init`.
+    pub fn fold<F>(&self, init: VariableData, f: F) -> Result<VariableData, Error> where F: Fn(&VariableData, &VariableData) -> Result<VariableData, Error> {
+        self.as_list("fold")?.iter().try_fold(init, |acc, item| f(&acc, item))
+    }
+    /// Combines the elements from left to right, starting from the first. Fails on an empty list.
+    pub fn reduce<F>(&self, f: F) -> Result<VariableData, Error> where F: Fn(&VariableData, &VariableData) -> Result<VariableData, Error> {
+        let list = self.as_list("reduce")?;
+        match list.split_first() {
+            None => Err(operation_error!("reduce", "the list is empty")),
+            Some((first, rest)) => rest.iter().try_fold(first.clone(), |acc, item| f(&acc, item))
+        }
+    }
+}
+
+#[test]
+fn test_list_operations() {
+    use crate::{binary_unit, core::io::BinaryUnit};
+    use super::variable_data::{Scalar, MVector, Matrix, Text, Boolean, Comparison};
+    let scalar = |x: f64| VariableData::Scalar(Scalar::from(x));
+    let add = |a: &VariableData, b: &VariableData| match (a, b) {
+        (VariableData::Scalar(x), VariableData::Scalar(y)) => Ok( VariableData::Scalar(Scalar::from(f64::from(x.clone()) + f64::from(y.clone()))) ),
+        _ => Err(operator_error!('+', a, b))
+    };
+
+    let mut mixed = VariableData::List(vec![
+        scalar(1.5),
+        VariableData::Vector(MVector::from(vec![Scalar::from(1.0), Scalar::from(2.0)])),
+        VariableData::Matrix(Matrix::identity(2))
+    ]);
+    mixed.append(VariableData::Text(Text::from("note"))).unwrap();
+    assert_eq!(mixed.list_len().unwrap(), 4);
+    assert_eq!(mixed.get(0).unwrap(), &scalar(1.5));
+    assert_eq!(mixed.get(-1).unwrap(), &VariableData::Text(Text::from("note")));
+    assert!(mixed.get(4).is_err());
+    assert!(mixed.get(-5).is_err());
+    assert!(scalar(1.0).append(scalar(2.0)).is_err());
+
+    let numbers = VariableData::List((1..=5).map(|x| scalar(x as f64)).collect());
+    let both = mixed.concat(&numbers).unwrap();
+    assert_eq!(both.list_len().unwrap(), 9);
+    assert_eq!(both.get(4).unwrap(), &scalar(1.0));
+    assert!(mixed.concat(&scalar(1.0)).is_err());
+
+    let doubled = numbers.map(|x| add(x, x)).unwrap();
+    assert_eq!(doubled, VariableData::List((1..=5).map(|x| scalar(2.0 * x as f64)).collect()));
+    let large = numbers.filter(|x| x.compare(&scalar(3.0), Comparison::Greater)).unwrap();
+    assert_eq!(large.to_string(), "[4, 5]");
+    assert!(numbers.filter(|x| Ok(x.clone())).is_err());
+    assert_eq!(numbers.reduce(add).unwrap(), scalar(15.0));
+    assert_eq!(numbers.fold(scalar(100.0), add).unwrap(), scalar(115.0));
+    assert!(VariableData::List(vec![]).reduce(add).is_err());
+
+    let flags = VariableData::List(vec![VariableData::Bool(Boolean::from(true)), VariableData::Bool(Boolean::from(false))]);
+    assert_eq!(flags.map(VariableData::not).unwrap().reduce(VariableData::or).unwrap(), VariableData::Bool(Boolean::from(true)));
+
+    let nested = VariableData::List(vec![both, flags, VariableData::List(vec![])]);
+    let units: Vec<BinaryUnit> = nested.clone().into();
+    assert_eq!(VariableData::try_from(units.clone()).unwrap(), nested);
+    let mut truncated = units;
+    truncated.pop();
+    assert!(VariableData::try_from(truncated).is_err());
+
+    //Each level is a tag, a length and a count of 1, down to an empty list.
+    let nested_lists = |levels: usize| -> Vec<BinaryUnit> {
+        (0..levels).flat_map(|k| {
+            let inside = levels - 1 - k;
+            vec![ binary_unit!(255u8), binary_unit!(3 * inside + 1), binary_unit!(usize::from(inside > 0)) ]
+        }).collect()
+    };
+    assert!(VariableData::try_from(nested_lists(64)).is_ok());
+    assert!(VariableData::try_from(nested_lists(65)).is_err());
+    assert!(VariableData::try_from(nested_lists(10_000)).is_err());
+}
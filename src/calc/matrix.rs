@@ -1,21 +1,35 @@
 use std::fmt::{Display, Debug};
-use std::ops::{Add, Sub, Div, Mul};
+use std::ops::{Add, Sub, Div, Mul, Index, IndexMut};
 
-use crate::{binary_unit, conversion_error, operator_error, core::{io::BinaryUnit, errors::Error}};
-use super::{variable_type::VariableType, scalar::Scalar, vector::MVector};
+use crate::{argument_error, binary_unit, conversion_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, scalar::Scalar, complex::Complex, vector::MVector, decomposition::{LUDecomposition, QRDecomposition, EigenDecomposition, SingularValueDecomposition}};
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct Matrix {
     data: Vec<Vec<f64>>
 }
 impl Debug for Matrix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        write!(f, "(Matrix:{}x{} {:?})", self.rows(), self.cols(), &self.data)
     }
 }
 impl Display for Matrix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        for (i, row) in self.data.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for (j, item) in row.iter().enumerate() {
+                if j != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            write!(f, "]")?;
+        }
+
+        Ok(())
     }
 }
 impl PartialEq for Matrix {
@@ -23,22 +37,41 @@ impl PartialEq for Matrix {
         self.data == other.data
     }
 }
-impl Into<Vec<BinaryUnit>> for Matrix {
-    fn into(self) -> Vec<BinaryUnit> {
-        todo!()
+impl From<Matrix> for Vec<BinaryUnit> {
+    fn from(value: Matrix) -> Self {
+        let mut result = vec![binary_unit!(value.rows()), binary_unit!(value.cols())];
+        for row in value.data {
+            for item in row {
+                result.push(binary_unit!(item));
+            }
+        }
+
+        result
     }
 }
 impl TryFrom<Vec<BinaryUnit>> for Matrix {
     type Error = Error;
     fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
-        todo!()
-    }
-}
-impl Default for Matrix {
-    fn default() -> Self {
-        Self {
-            data: vec![]
+        if value.len() < 2 {
+            return Err(conversion_error!("cannot construct Matrix from {} element(s)", value.len()));
         }
+
+        let mut iter = value.into_iter();
+        let rows: usize = iter.next().unwrap().try_into()?; //We can unwrap because there are at least two elements
+        let cols: usize = iter.next().unwrap().try_into()?;
+
+        let count = rows.checked_mul(cols).ok_or_else(|| conversion_error!("a {}x{} matrix is too large", rows, cols))?;
+        let items: Vec<BinaryUnit> = iter.collect();
+        if items.len() != count {
+            return Err(conversion_error!("expected {} units for a {}x{} matrix, got {}", count, rows, cols, items.len()));
+        }
+
+        let mut flat: Vec<f64> = Vec::with_capacity(items.len());
+        for item in items {
+            flat.push(item.try_into()?);
+        }
+
+        Ok( Self::from_flat(rows, cols, flat) )
     }
 }
 impl VariableType for Matrix {
@@ -46,64 +79,394 @@ impl VariableType for Matrix {
         self.rows() * self.cols() + 2
     }
 }
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.data[index.0][index.1]
+    }
+}
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.data[index.0][index.1]
+    }
+}
 impl Add for Matrix {
     type Output = Result<Matrix, Error>;
     fn add(self, rhs: Self) -> Self::Output {
-        todo!()
+        if self.is_error() || rhs.is_error() || self.rows() != rhs.rows() || self.cols() != rhs.cols() {
+            return Err(operator_error!('+', self, rhs));
+        }
+
+        let mut result = self;
+        for (row, other) in result.data.iter_mut().zip(rhs.data) {
+            for (item, x) in row.iter_mut().zip(other) {
+                *item += x;
+            }
+        }
+
+        Ok(result)
     }
 }
 impl Sub for Matrix {
     type Output = Result<Matrix, Error>;
     fn sub(self, rhs: Self) -> Self::Output {
-        todo!()
+        if self.is_error() || rhs.is_error() || self.rows() != rhs.rows() || self.cols() != rhs.cols() {
+            return Err(operator_error!('-', self, rhs));
+        }
+
+        let mut result = self;
+        for (row, other) in result.data.iter_mut().zip(rhs.data) {
+            for (item, x) in row.iter_mut().zip(other) {
+                *item -= x;
+            }
+        }
+
+        Ok(result)
     }
 }
 impl Mul for Matrix {
     type Output = Result<Matrix, Error>;
     fn mul(self, rhs: Self) -> Self::Output {
-        todo!()
+        if self.is_error() || rhs.is_error() || self.cols() != rhs.rows() {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let mut result = Self::zeros(self.rows(), rhs.cols());
+        for i in 0..self.rows() {
+            for k in 0..self.cols() {
+                let lhs = self.data[i][k];
+                if lhs == 0.0 {
+                    continue;
+                }
+                for j in 0..rhs.cols() {
+                    result.data[i][j] += lhs * rhs.data[k][j];
+                }
+            }
+        }
+
+        Ok(result)
     }
 }
 impl Mul<MVector<Scalar>> for Matrix {
     type Output = Result<MVector<Scalar>, Error>;
     fn mul(self, rhs: MVector<Scalar>) -> Self::Output {
-        todo!()
+        if self.is_error() || rhs.is_error() || self.cols() != rhs.dim() {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let x: Vec<f64> = rhs.iter().map(|s| f64::from(s.clone())).collect();
+        let result: Vec<Scalar> = self.data.iter()
+            .map(|row| Scalar::from(row.iter().zip(x.iter()).map(|(a, b)| a * b).sum::<f64>()))
+            .collect();
+
+        Ok( MVector::from(result) )
     }
 }
 impl Mul<Scalar> for Matrix {
     type Output = Result<Matrix, Error>;
     fn mul(self, rhs: Scalar) -> Self::Output {
-        todo!()
+        let data: f64 = rhs.into();
+        self.mul(data)
     }
 }
 impl Mul<f64> for Matrix {
     type Output = Result<Matrix, Error>;
     fn mul(self, rhs: f64) -> Self::Output {
-        todo!()
+        if self.is_error() {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let mut result = self;
+        for row in result.data.iter_mut() {
+            for item in row.iter_mut() {
+                *item *= rhs;
+            }
+        }
+
+        Ok(result)
     }
 }
 impl Div<Scalar> for Matrix {
     type Output = Result<Matrix, Error>;
     fn div(self, rhs: Scalar) -> Self::Output {
-        todo!()
+        let data: f64 = rhs.into();
+        self.div(data)
     }
 }
 impl Div<f64> for Matrix {
     type Output = Result<Matrix, Error>;
     fn div(self, rhs: f64) -> Self::Output {
-        todo!()   
+        if self.is_error() || rhs == 0.0 {
+            return Err(operator_error!('/', self, rhs));
+        }
+
+        let mut result = self;
+        for row in result.data.iter_mut() {
+            for item in row.iter_mut() {
+                *item /= rhs;
+            }
+        }
+
+        Ok(result)
     }
 }
 impl Matrix {
+    /// Builds a matrix out of its rows. Every row must have the same length.
+    pub fn from_rows(rows: Vec<Vec<f64>>) -> Result<Self, Error> {
+        if rows.is_empty() || rows[0].is_empty() {
+            return match rows.iter().all(|r| r.is_empty()) {
+                true => Ok(Self::default()),
+                false => Err(argument_error!("rows", "the first row is empty, but others are not"))
+            };
+        }
+
+        let cols = rows[0].len();
+        if let Some((i, row)) = rows.iter().enumerate().find(|(_, r)| r.len() != cols) {
+            return Err(argument_error!("rows", "row {} has {} column(s), expected {}", i, row.len(), cols));
+        }
+
+        Ok(
+            Self {
+                data: rows
+            }
+        )
+    }
+    /// Builds a matrix out of its columns. Every column must have the same length.
+    pub fn from_cols(cols: Vec<Vec<f64>>) -> Result<Self, Error> {
+        if cols.is_empty() || cols[0].is_empty() {
+            return match cols.iter().all(|c| c.is_empty()) {
+                true => Ok(Self::default()),
+                false => Err(argument_error!("cols", "the first column is empty, but others are not"))
+            };
+        }
+
+        let rows = cols[0].len();
+        if let Some((i, col)) = cols.iter().enumerate().find(|(_, c)| c.len() != rows) {
+            return Err(argument_error!("cols", "column {} has {} row(s), expected {}", i, col.len(), rows));
+        }
+
+        Ok( Self::from_rows(cols)?.transpose() )
+    }
+    /// Builds a `rows` by `cols` matrix out of `data`, which is stored in row-major order.
+    /// The caller is expected to ensure that `data.len() == rows * cols`.
+    pub(crate) fn from_flat(rows: usize, cols: usize, data: Vec<f64>) -> Self {
+        if rows == 0 || cols == 0 {
+            return Self::default();
+        }
+
+        Self {
+            data: data.chunks(cols).map(|c| c.to_vec()).collect()
+        }
+    }
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self::filled(rows, cols, 0.0)
+    }
+    pub fn ones(rows: usize, cols: usize) -> Self {
+        Self::filled(rows, cols, 1.0)
+    }
+    pub fn filled(rows: usize, cols: usize, value: f64) -> Self {
+        if rows == 0 || cols == 0 {
+            return Self::default();
+        }
+
+        Self {
+            data: vec![vec![value; cols]; rows]
+        }
+    }
+    pub fn identity(n: usize) -> Self {
+        let mut result = Self::zeros(n, n);
+        for i in 0..n {
+            result.data[i][i] = 1.0;
+        }
+
+        result
+    }
+    /// Builds a square matrix with `diag` along the main diagonal, and zeros elsewhere.
+    pub fn diagonal(diag: &[f64]) -> Self {
+        let mut result = Self::zeros(diag.len(), diag.len());
+        for (i, item) in diag.iter().enumerate() {
+            result.data[i][i] = *item;
+        }
+
+        result
+    }
+
     pub fn rows(&self) -> usize {
         self.data.len()
     }
     pub fn cols(&self) -> usize {
         if self.data.is_empty() {
             0
-        } 
+        }
         else {
             self.data[0].len()
         }
     }
+    pub fn is_error(&self) -> bool {
+        self.data.is_empty()
+    }
+    pub fn is_square(&self) -> bool {
+        self.rows() == self.cols()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<f64> {
+        self.data.get(row).and_then(|r| r.get(col)).copied()
+    }
+    pub fn row(&self, row: usize) -> Option<&[f64]> {
+        self.data.get(row).map(|r| r.as_slice())
+    }
+    pub fn col(&self, col: usize) -> Option<Vec<f64>> {
+        if col >= self.cols() {
+            return None;
+        }
+
+        Some( self.data.iter().map(|r| r[col]).collect() )
+    }
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        self.data.swap(a, b);
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut result = Self::zeros(self.cols(), self.rows());
+        for (i, row) in self.data.iter().enumerate() {
+            for (j, item) in row.iter().enumerate() {
+                result.data[j][i] = *item;
+            }
+        }
+
+        result
+    }
+
+    pub fn lu(&self) -> Result<LUDecomposition, Error> {
+        LUDecomposition::new(self)
+    }
+    pub fn determinant(&self) -> Result<f64, Error> {
+        Ok( self.lu()?.determinant() )
+    }
+    pub fn inverse(&self) -> Result<Matrix, Error> {
+        self.lu()?.inverse()
+    }
+    /// Solves `Ax = b` through the LU decomposition of `A`.
+    pub fn solve(&self, b: &MVector<Scalar>) -> Result<MVector<Scalar>, Error> {
+        self.lu()?.solve(b)
+    }
+
+    pub fn qr(&self) -> Result<QRDecomposition, Error> {
+        QRDecomposition::new(self)
+    }
+    /// Solves the (possibly overdetermined) system `Ax = b` in the least squares sense, returning `x` and the norm of the residual `Ax - b`.
+    pub fn least_squares(&self, b: &MVector<Scalar>) -> Result<(MVector<Scalar>, f64), Error> {
+        self.qr()?.least_squares(b)
+    }
+
+    /// Determines if the matrix is square and equal to its transpose, with entries compared up to `tol`.
+    pub fn is_symmetric(&self, tol: f64) -> bool {
+        if !self.is_square() {
+            return false;
+        }
+
+        for i in 0..self.rows() {
+            for j in (i + 1)..self.cols() {
+                if (self.data[i][j] - self.data[j][i]).abs() > tol {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+    /// Reduces a square matrix to upper Hessenberg form (zero below the first subdiagonal) with Householder similarity transforms.
+    /// The result has the same eigenvalues as `self`. Non-square matrices are returned unchanged.
+    pub fn hessenberg(&self) -> Matrix {
+        let mut h = self.clone();
+        if !self.is_square() {
+            return h;
+        }
+
+        let n = self.rows();
+        for k in 0..n.saturating_sub(2) {
+            let mut v: Vec<f64> = ((k + 1)..n).map(|i| h.data[i][k]).collect();
+            let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm == 0.0 {
+                continue;
+            }
+            v[0] += norm.copysign(v[0]);
+            let beta = 2.0 / v.iter().map(|x| x * x).sum::<f64>();
+
+            //H = (I - beta v v^T) H, acting on rows k + 1..n.
+            for j in 0..n {
+                let dot: f64 = v.iter().enumerate().map(|(i, x)| x * h.data[k + 1 + i][j]).sum();
+                for (i, x) in v.iter().enumerate() {
+                    h.data[k + 1 + i][j] -= beta * dot * x;
+                }
+            }
+            //H = H (I - beta v v^T), acting on columns k + 1..n.
+            for row in h.data.iter_mut() {
+                let dot: f64 = v.iter().enumerate().map(|(i, x)| x * row[k + 1 + i]).sum();
+                for (i, x) in v.iter().enumerate() {
+                    row[k + 1 + i] -= beta * dot * x;
+                }
+            }
+            for i in (k + 2)..n {
+                h.data[i][k] = 0.0;
+            }
+        }
+
+        h
+    }
+    pub fn eigen(&self) -> Result<EigenDecomposition, Error> {
+        EigenDecomposition::new(self)
+    }
+    pub fn eigenvalues(&self) -> Result<MVector<Complex>, Error> {
+        Ok( self.eigen()?.values().clone() )
+    }
+
+    pub fn svd(&self) -> Result<SingularValueDecomposition, Error> {
+        SingularValueDecomposition::new(self)
+    }
+    /// The numerical rank, counting singular values above `max(m, n) * eps * sigma_max`.
+    pub fn rank(&self) -> Result<usize, Error> {
+        Ok( self.svd()?.rank() )
+    }
+    pub fn pseudoinverse(&self) -> Result<Matrix, Error> {
+        Ok( self.svd()?.pseudoinverse() )
+    }
+    /// The 2-norm condition number. Large values mean that `solve` results should not be trusted.
+    pub fn condition_number(&self) -> Result<f64, Error> {
+        Ok( self.svd()?.condition_number() )
+    }
+    pub fn null_space(&self) -> Result<Vec<MVector<Scalar>>, Error> {
+        Ok( self.svd()?.null_space() )
+    }
+    pub fn range(&self) -> Result<Vec<MVector<Scalar>>, Error> {
+        Ok( self.svd()?.range() )
+    }
+}
+
+#[test]
+fn test_matrix_arithmetic() {
+    let a = Matrix::from_rows(vec![vec![1.0, 2.0], vec![3.0, 4.0]]).unwrap();
+    let b = Matrix::from_cols(vec![vec![5.0, 7.0], vec![6.0, 8.0]]).unwrap();
+
+    assert_eq!((a.clone() + b.clone()).unwrap(), Matrix::from_rows(vec![vec![6.0, 8.0], vec![10.0, 12.0]]).unwrap());
+    assert_eq!((b.clone() - a.clone()).unwrap(), Matrix::filled(2, 2, 4.0));
+    assert_eq!((a.clone() * b.clone()).unwrap(), Matrix::from_rows(vec![vec![19.0, 22.0], vec![43.0, 50.0]]).unwrap());
+    assert_eq!((a.clone() * Matrix::identity(2)).unwrap(), a);
+    assert_eq!((a.clone() * 2.0).unwrap(), Matrix::from_rows(vec![vec![2.0, 4.0], vec![6.0, 8.0]]).unwrap());
+    assert!((a.clone() / 0.0).is_err());
+    assert_eq!(Matrix::diagonal(&[1.0, 1.0]), Matrix::identity(2));
+
+    let v = MVector::from(vec![Scalar::from(1.0), Scalar::from(1.0)]);
+    assert_eq!((a.clone() * v).unwrap(), MVector::from(vec![Scalar::from(3.0), Scalar::from(7.0)]));
+
+    let wide = Matrix::from_rows(vec![vec![1.0, 2.0, 3.0]]).unwrap();
+    assert_eq!(wide.transpose(), Matrix::from_cols(vec![vec![1.0, 2.0, 3.0]]).unwrap());
+    assert!((a.clone() + wide.clone()).is_err());
+    assert!((wide.clone() * a.clone()).is_err());
+    assert!((a.clone() * wide.transpose()).is_err());
+    assert!(Matrix::from_rows(vec![vec![1.0, 2.0], vec![3.0]]).is_err());
+
+    let units: Vec<BinaryUnit> = wide.clone().into();
+    assert_eq!(Matrix::try_from(units).unwrap(), wide);
+    assert!(Matrix::try_from(vec![ binary_unit!(usize::MAX), binary_unit!(2usize) ]).is_err());
 }
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{binary_unit, conversion_error, operator_error, operation_error, core::{io::BinaryUnit, errors::Error}};
+use super::variable_type::{VariableType, SimpleNumerical};
+
+/// Hands out the identifiers of independent sources of uncertainty.
+static NEXT_SOURCE: AtomicU64 = AtomicU64::new(1);
+
+/// Gives the sources read from storage fresh identifiers, since the stored ones belong to the session that wrote them.
+/// Share one map across every value read in a single load, so values that were correlated when saved stay correlated with each other, but not with anything already in memory.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    fresh: HashMap<u64, u64>
+}
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn remap(&mut self, stored: u64) -> u64 {
+        *self.fresh.entry(stored).or_insert_with(|| NEXT_SOURCE.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A value with a standard uncertainty, `value ± σ`.
+/// Uncertainty is propagated to first order. Each value remembers how much of its uncertainty comes from each independent measurement, so correlated terms cancel: `x - x` is exactly `0 ± 0`.
+#[derive(Clone, PartialEq, Default)]
+pub struct Measurement {
+    value: f64,
+    /// `(source, ∂value/∂source · σ_source)`, sorted by source.
+    components: Vec<(u64, f64)>
+}
+impl Debug for Measurement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Measurement:{} ± {} from {} source(s))", self.value, self.uncertainty(), self.components.len())
+    }
+}
+impl Display for Measurement {
+    /// Rounds the uncertainty to one significant figure (two when it starts with a 1) and the value to the same place, unless a precision is given.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sigma = self.uncertainty();
+        if let Some(decimals) = f.precision() {
+            return write!(f, "{:.*} ± {:.*}", decimals, self.value, decimals, sigma);
+        }
+        if sigma == 0.0 || !sigma.is_finite() {
+            return write!(f, "{} ± {}", self.value, sigma);
+        }
+
+        let exponent = sigma.log10().floor() as i32;
+        let figures = if sigma / 10f64.powi(exponent) < 2.0 { 2 } else { 1 };
+        let place = exponent - figures + 1;
+        let unit = 10f64.powi(place);
+        let decimals = (-place).max(0) as usize;
+
+        write!(f, "{:.*} ± {:.*}", decimals, (self.value / unit).round() * unit, decimals, (sigma / unit).round() * unit)
+    }
+}
+impl From<Measurement> for Vec<BinaryUnit> {
+    /// Encoded as the value, the number of sources, and each source with its component.
+    fn from(value: Measurement) -> Self {
+        let mut result = vec![ binary_unit!(value.value), binary_unit!(value.components.len()) ];
+        for (source, component) in value.components {
+            result.push(binary_unit!(source));
+            result.push(binary_unit!(component));
+        }
+
+        result
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Measurement {
+    type Error = Error;
+    /// Reads a single measurement with its own `SourceMap`. Use `Measurement::decode` to keep several values correlated.
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        Self::decode(value, &mut SourceMap::new())
+    }
+}
+impl From<f64> for Measurement {
+    fn from(value: f64) -> Self {
+        Self::exact(value)
+    }
+}
+impl VariableType for Measurement {
+    fn required_units(&self) -> usize {
+        2 + 2 * self.components.len()
+    }
+}
+impl Neg for Measurement {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        self.apply(-self.value, -1.0)
+    }
+}
+impl Add for Measurement {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::combine(self.value + rhs.value, 1.0, &self, 1.0, &rhs)
+    }
+}
+impl Sub for Measurement {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::combine(self.value - rhs.value, 1.0, &self, -1.0, &rhs)
+    }
+}
+impl Mul for Measurement {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::combine(self.value * rhs.value, rhs.value, &self, self.value, &rhs)
+    }
+}
+impl Div for Measurement {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.value == 0.0 {
+            return Err(operator_error!('/', self, rhs));
+        }
+
+        Ok( Self::combine(self.value / rhs.value, 1.0 / rhs.value, &self, -self.value / (rhs.value * rhs.value), &rhs) )
+    }
+}
+impl AddAssign for Measurement {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = Self::combine(self.value + rhs.value, 1.0, self, 1.0, &rhs);
+    }
+}
+impl AddAssign<f64> for Measurement {
+    fn add_assign(&mut self, rhs: f64) {
+        self.value += rhs;
+    }
+}
+impl SubAssign for Measurement {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = Self::combine(self.value - rhs.value, 1.0, self, -1.0, &rhs);
+    }
+}
+impl SubAssign<f64> for Measurement {
+    fn sub_assign(&mut self, rhs: f64) {
+        self.value -= rhs;
+    }
+}
+impl MulAssign for Measurement {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = Self::combine(self.value * rhs.value, rhs.value, self, self.value, &rhs);
+    }
+}
+impl MulAssign<f64> for Measurement {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = self.apply(self.value * rhs, rhs);
+    }
+}
+impl DivAssign for Measurement {
+    /// Follows `f64` division, so dividing by an exact zero gives infinite or `NaN` results.
+    fn div_assign(&mut self, rhs: Self) {
+        *self = Self::combine(self.value / rhs.value, 1.0 / rhs.value, self, -self.value / (rhs.value * rhs.value), &rhs);
+    }
+}
+impl DivAssign<f64> for Measurement {
+    fn div_assign(&mut self, rhs: f64) {
+        *self = self.apply(self.value / rhs, 1.0 / rhs);
+    }
+}
+impl SimpleNumerical for Measurement {
+    fn abs(&self) -> f64 {
+        self.value.abs()
+    }
+}
+
+impl Measurement {
+    /// A new, independent measurement of `value` with standard uncertainty `sigma`.
+    pub fn new(value: f64, sigma: f64) -> Self {
+        if sigma == 0.0 {
+            return Self::exact(value);
+        }
+
+        Self {
+            value,
+            components: vec![ (NEXT_SOURCE.fetch_add(1, Ordering::Relaxed), sigma.abs()) ]
+        }
+    }
+    /// Reads a measurement, giving its sources fresh identifiers through `sources`.
+    pub fn decode(value: Vec<BinaryUnit>, sources: &mut SourceMap) -> Result<Self, Error> {
+        if value.len() < 2 {
+            return Err(conversion_error!("expected at least 2 units, got {}", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let val: f64 = iter.next().unwrap().try_into()?;
+        let count: usize = iter.next().unwrap().try_into()?;
+        if count.checked_mul(2) != Some(iter.len()) {
+            return Err(conversion_error!("expected 2 units for each of {} source(s), got {}", count, iter.len()));
+        }
+
+        let mut components: Vec<(u64, f64)> = Vec::with_capacity(count);
+        for _ in 0..count {
+            let source: u64 = iter.next().unwrap().try_into()?;
+            let component: f64 = iter.next().unwrap().try_into()?;
+            components.push((sources.remap(source), component));
+        }
+        components.sort_by_key(|(s, _)| *s);
+        if components.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(conversion_error!("a source of uncertainty is listed more than once"));
+        }
+
+        Ok( Self { value: val, components } )
+    }
+    /// A value with no uncertainty.
+    pub fn exact(value: f64) -> Self {
+        Self {
+            value,
+            components: vec![]
+        }
+    }
+    /// The linear combination `a·x + b·y` of the uncertainty components, attached to `value`.
+    fn combine(value: f64, a: f64, x: &Self, b: f64, y: &Self) -> Self {
+        let mut components = Vec::with_capacity(x.components.len() + y.components.len());
+        let (mut i, mut j) = (0, 0);
+        while i < x.components.len() || j < y.components.len() {
+            let next = match (x.components.get(i), y.components.get(j)) {
+                (Some(&(s, c)), Some(&(t, d))) if s == t => { i += 1; j += 1; (s, a * c + b * d) },
+                (Some(&(s, c)), Some(&(t, _))) if s < t => { i += 1; (s, a * c) },
+                (Some(&(s, c)), None) => { i += 1; (s, a * c) },
+                (_, Some(&(t, d))) => { j += 1; (t, b * d) },
+                (None, None) => unreachable!()
+            };
+            if next.1 != 0.0 {
+                components.push(next);
+            }
+        }
+
+        Self { value, components }
+    }
+    /// The result of a function with value `value` and derivative `derivative` at `self`.
+    fn apply(&self, value: f64, derivative: f64) -> Self {
+        Self::combine(value, derivative, self, 0.0, &Self::default())
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+    /// The standard uncertainty, σ.
+    pub fn uncertainty(&self) -> f64 {
+        self.components.iter().map(|(_, c)| c * c).sum::<f64>().sqrt()
+    }
+    /// σ / |value|.
+    pub fn relative_uncertainty(&self) -> f64 {
+        self.uncertainty() / self.value.abs()
+    }
+    pub fn covariance(&self, other: &Self) -> f64 {
+        self.components.iter().filter_map(|(s, c)| {
+            other.components.iter().find(|(t, _)| t == s).map(|(_, d)| c * d)
+        }).sum()
+    }
+    /// The correlation coefficient, or zero when either value is exact.
+    pub fn correlation(&self, other: &Self) -> f64 {
+        let denom = self.uncertainty() * other.uncertainty();
+        if denom == 0.0 { 0.0 } else { self.covariance(other) / denom }
+    }
+
+    pub fn powi(&self, n: i32) -> Self {
+        self.apply(self.value.powi(n), n as f64 * self.value.powf(n as f64 - 1.0))
+    }
+    pub fn powf(&self, n: f64) -> Result<Self, Error> {
+        if self.value < 0.0 && n.fract() != 0.0 {
+            return Err(operation_error!("powf", "cannot raise the negative value {} to the power {}", self.value, n));
+        }
+
+        Ok( self.apply(self.value.powf(n), n * self.value.powf(n - 1.0)) )
+    }
+    pub fn sqrt(&self) -> Result<Self, Error> {
+        if self.value < 0.0 {
+            return Err(operation_error!("sqrt", "{} is negative", self.value));
+        }
+
+        let root = self.value.sqrt();
+        Ok( self.apply(root, 0.5 / root) )
+    }
+    pub fn exp(&self) -> Self {
+        let e = self.value.exp();
+        self.apply(e, e)
+    }
+    pub fn ln(&self) -> Result<Self, Error> {
+        if self.value <= 0.0 {
+            return Err(operation_error!("ln", "{} is not positive", self.value));
+        }
+
+        Ok( self.apply(self.value.ln(), 1.0 / self.value) )
+    }
+    pub fn sin(&self) -> Self {
+        self.apply(self.value.sin(), self.value.cos())
+    }
+    pub fn cos(&self) -> Self {
+        self.apply(self.value.cos(), -self.value.sin())
+    }
+    pub fn tan(&self) -> Self {
+        let cos = self.value.cos();
+        self.apply(self.value.tan(), 1.0 / (cos * cos))
+    }
+    pub fn atan(&self) -> Self {
+        self.apply(self.value.atan(), 1.0 / (1.0 + self.value * self.value))
+    }
+}
+
+#[test]
+fn test_measurement_propagation() {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+
+    let x = Measurement::new(12.3, 0.4);
+    let y = Measurement::new(5.0, 0.3);
+    assert_eq!(format!("{}", x), "12.3 ± 0.4");
+    assert_eq!(format!("{}", Measurement::new(1234.0, 56.0)), "1230 ± 60");
+    assert_eq!(format!("{}", Measurement::new(9.8765, 0.0123)), "9.877 ± 0.012");
+
+    assert!(close((x.clone() - x.clone()).uncertainty(), 0.0));
+    assert!(close((x.clone() + x.clone()).uncertainty(), 0.8));
+    assert!(close((x.clone() + y.clone()).uncertainty(), 0.5));
+
+    let product = x.clone() * y.clone();
+    assert!(close(product.relative_uncertainty(), ((0.4f64 / 12.3).powi(2) + (0.3f64 / 5.0).powi(2)).sqrt()));
+    assert!(close((product / y.clone()).unwrap().uncertainty(), 0.4));
+    assert!((x.clone() / Measurement::exact(0.0)).is_err());
+
+    let sum = x.clone() + y.clone();
+    assert!(close(sum.correlation(&x), 0.8));
+    assert!(close(x.correlation(&y), 0.0));
+    assert!(close(y.powi(2).uncertainty(), 3.0));
+    assert_eq!(Measurement::new(1.0, 0.1).powi(i32::MIN).value(), 1.0);
+    assert!(close(Measurement::new(0.0, 0.1).sin().uncertainty(), 0.1));
+
+    //Decoded sources are new to this session, but stay shared between values read with the same map.
+    let units: Vec<BinaryUnit> = sum.clone().into();
+    let decoded = Measurement::try_from(units.clone()).unwrap();
+    assert_eq!((decoded.value(), decoded.uncertainty()), (sum.value(), sum.uncertainty()));
+    assert!(close(decoded.correlation(&sum), 0.0));
+    assert!(close((decoded.clone() - sum.clone()).uncertainty(), 2.0f64.sqrt() * 0.5));
+
+    let mut sources = SourceMap::new();
+    let loaded_sum = Measurement::decode(units, &mut sources).unwrap();
+    let loaded_x = Measurement::decode(x.clone().into(), &mut sources).unwrap();
+    assert!(close((loaded_sum - loaded_x).uncertainty(), 0.3));
+
+    use super::variable_data::VariableData;
+    let saved: Vec<BinaryUnit> = VariableData::List(vec![ VariableData::Measurement(sum), VariableData::Measurement(x) ]).into();
+    match VariableData::try_from(saved).unwrap() {
+        VariableData::List(l) => match (&l[0], &l[1]) {
+            (VariableData::Measurement(a), VariableData::Measurement(b)) => assert!(close((a.clone() - b.clone()).uncertainty(), 0.3)),
+            _ => panic!("expected two measurements")
+        },
+        _ => panic!("expected a list")
+    }
+
+    let repeated = vec![ binary_unit!(1.0), binary_unit!(2usize), binary_unit!(7u64), binary_unit!(0.1), binary_unit!(7u64), binary_unit!(0.2) ];
+    assert!(Measurement::try_from(repeated).is_err());
+    assert!(Measurement::try_from(vec![ binary_unit!(1.0), binary_unit!(usize::MAX) ]).is_err());
+}
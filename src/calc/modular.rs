@@ -0,0 +1,177 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+use crate::{argument_error, binary_unit, conversion_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, number_theory::{mul_mod, pow_mod, mod_inverse}};
+
+/// An element of the integers modulo `n`, always stored as its representative in `[0, n)`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModInt {
+    value: u64,
+    modulus: u64
+}
+impl Debug for ModInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(ModInt:{} mod {})", self.value, self.modulus)
+    }
+}
+impl Display for ModInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+impl From<ModInt> for Vec<BinaryUnit> {
+    fn from(value: ModInt) -> Self {
+        vec![ binary_unit!(value.value), binary_unit!(value.modulus) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for ModInt {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.len() != 2 {
+            return Err(conversion_error!("expected 2 units, got {}", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let value: u64 = iter.next().unwrap().try_into()?;
+        let modulus: u64 = iter.next().unwrap().try_into()?;
+
+        Self::from_unsigned(value, modulus)
+    }
+}
+impl VariableType for ModInt {
+    fn required_units(&self) -> usize {
+        2usize
+    }
+}
+impl Neg for ModInt {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            value: (self.modulus - self.value) % self.modulus,
+            modulus: self.modulus
+        }
+    }
+}
+impl Add for ModInt {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check_modulus(&rhs, '+')?;
+        Ok( Self::reduced((self.value as u128 + rhs.value as u128) % self.modulus as u128, self.modulus) )
+    }
+}
+impl Sub for ModInt {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.check_modulus(&rhs, '-')?;
+        self + (-rhs)
+    }
+}
+impl Mul for ModInt {
+    type Output = Result<Self, Error>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_modulus(&rhs, '*')?;
+        Ok( Self::reduced(mul_mod(self.value, rhs.value, self.modulus) as u128, self.modulus) )
+    }
+}
+impl Div for ModInt {
+    type Output = Result<Self, Error>;
+    /// Multiplies by the inverse of `rhs`, which must be coprime to the modulus.
+    fn div(self, rhs: Self) -> Self::Output {
+        self.check_modulus(&rhs, '/')?;
+        self.mul(rhs.inverse()?)
+    }
+}
+
+impl ModInt {
+    fn reduced(value: u128, modulus: u64) -> Self {
+        Self {
+            value: value as u64,
+            modulus
+        }
+    }
+    fn check_modulus(&self, rhs: &Self, op: char) -> Result<(), Error> {
+        if self.modulus != rhs.modulus {
+            return Err(operator_error!(op, self, rhs));
+        }
+
+        Ok(())
+    }
+
+    /// `value mod modulus`. Negative values wrap around, so `-1 mod 7` is `6`.
+    pub fn new(value: i64, modulus: u64) -> Result<Self, Error> {
+        if modulus == 0 {
+            return Err(argument_error!("modulus", "must be positive"));
+        }
+
+        Ok( Self::reduced((value as i128).rem_euclid(modulus as i128) as u128, modulus) )
+    }
+    pub fn from_unsigned(value: u64, modulus: u64) -> Result<Self, Error> {
+        if modulus == 0 {
+            return Err(argument_error!("modulus", "must be positive"));
+        }
+
+        Ok( Self::reduced((value % modulus) as u128, modulus) )
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// The `x` with `self * x = 1`. Fails unless the value is coprime to the modulus.
+    pub fn inverse(&self) -> Result<Self, Error> {
+        //A value above i64::MAX is passed as the equivalent negative representative, value - modulus.
+        let inverse = if self.value <= i64::MAX as u64 {
+            mod_inverse(self.value as i64, self.modulus)?
+        }
+        else {
+            mod_inverse(-((self.modulus - self.value) as i64), self.modulus)?
+        };
+
+        Ok( Self::reduced(inverse as u128, self.modulus) )
+    }
+    /// `self^exp`. Negative exponents raise the inverse.
+    pub fn pow(&self, exp: i64) -> Result<Self, Error> {
+        let base = if exp < 0 { self.inverse()? } else { *self };
+        Ok( Self::reduced(pow_mod(base.value, exp.unsigned_abs(), self.modulus) as u128, self.modulus) )
+    }
+}
+
+#[test]
+fn test_modular_arithmetic() {
+    let m = |a: i64, n: u64| ModInt::new(a, n).unwrap();
+    assert_eq!(m(-1, 7), m(6, 7));
+    assert_eq!(m(-1, 7).to_string(), "6 (mod 7)");
+    assert!(ModInt::new(3, 0).is_err());
+
+    assert_eq!((m(5, 7) + m(4, 7)).unwrap(), m(2, 7));
+    assert_eq!((m(2, 7) - m(5, 7)).unwrap(), m(4, 7));
+    assert_eq!((m(3, 7) * m(5, 7)).unwrap(), m(1, 7));
+    assert_eq!((m(1, 7) / m(3, 7)).unwrap(), m(5, 7));
+    assert!((m(1, 7) + m(1, 8)).is_err());
+    assert!((m(1, 8) / m(2, 8)).is_err());
+    assert_eq!(-m(0, 5), m(0, 5));
+
+    assert_eq!(m(3, 11).inverse().unwrap(), m(4, 11));
+    assert!(m(4, 12).inverse().is_err());
+    assert_eq!(m(4, 497).pow(13).unwrap(), m(445, 497));
+    assert_eq!(m(3, 11).pow(-2).unwrap(), m(4, 11).pow(2).unwrap());
+
+    //Fermat's little theorem for a modulus above i64::MAX.
+    let p = 18446744073709551557u64;
+    let big = ModInt::from_unsigned(p - 2, p).unwrap();
+    assert_eq!(big.pow(((p - 1) / 2) as i64).unwrap().pow(2).unwrap(), ModInt::from_unsigned(1, p).unwrap());
+    assert_eq!((big * big.inverse().unwrap()).unwrap(), ModInt::from_unsigned(1, p).unwrap());
+
+    //A toy RSA round trip: n = 61 * 53, e = 17, d = 17^-1 mod lcm(60, 52).
+    let d = m(17, 780).inverse().unwrap().value() as i64;
+    let cipher = m(65, 3233).pow(17).unwrap();
+    assert_eq!(cipher, m(2790, 3233));
+    assert_eq!(cipher.pow(d).unwrap(), m(65, 3233));
+
+    let units: Vec<BinaryUnit> = big.into();
+    assert_eq!(ModInt::try_from(units).unwrap(), big);
+}
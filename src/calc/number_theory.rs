@@ -0,0 +1,269 @@
+use crate::{argument_error, operation_error, core::errors::Error};
+use super::modular::ModInt;
+
+/// The greatest common divisor of `|a|` and `|b|`, with `gcd(0, 0) = 0`.
+pub fn gcd(a: i64, b: i64) -> u64 {
+    gcd_u64(a.unsigned_abs(), b.unsigned_abs())
+}
+fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+/// The least common multiple of `|a|` and `|b|`, with `lcm(a, 0) = 0`. Fails if the result does not fit in 64 bits.
+pub fn lcm(a: i64, b: i64) -> Result<u64, Error> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+
+    (a.unsigned_abs() / gcd(a, b)).checked_mul(b.unsigned_abs()).ok_or_else(|| operation_error!("lcm", "lcm({}, {}) does not fit in 64 bits", a, b))
+}
+
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    let (mut old_t, mut t) = (0i128, 1i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    }
+    else {
+        (old_r, old_s, old_t)
+    }
+}
+/// Returns `(g, x, y)` with `g = gcd(a, b)` and `a x + b y = g`.
+pub fn extended_gcd(a: i64, b: i64) -> (u64, i64, i64) {
+    let (g, x, y) = extended_gcd_wide(a as i128, b as i128);
+    (g as u64, x as i64, y as i64)
+}
+
+/// `a * b mod m`, without overflow.
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+/// `base^exp mod m`, by repeated squaring.
+pub fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+
+    result
+}
+/// The `x` in `[0, m)` with `a x = 1 (mod m)`. Fails unless `a` and `m` are coprime.
+pub fn mod_inverse(a: i64, m: u64) -> Result<u64, Error> {
+    if m == 0 {
+        return Err(argument_error!("m", "the modulus must be positive"));
+    }
+
+    let m_wide = m as i128;
+    let (g, x, _) = extended_gcd_wide((a as i128).rem_euclid(m_wide), m_wide);
+    if g != 1 {
+        return Err(operation_error!("inverse", "{} has no inverse mod {}, since they share the factor {}", a, m, g));
+    }
+
+    Ok( x.rem_euclid(m_wide) as u64 )
+}
+
+/// Whether `n` is prime, using Miller-Rabin with the first twelve primes as witnesses, which is exact for every 64-bit `n`.
+pub fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for p in WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+//Brent's variant of Pollard's rho, which finds a non-trivial factor of a composite `n`.
+fn pollard_rho(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+    if n.is_multiple_of(2) {
+        return 2;
+    }
+
+    for c in 1..n {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut ys) = (0u64, 2u64, 2u64);
+        let (mut g, mut r, mut q) = (1u64, 1u64, 1u64);
+
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd_u64(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+
+        //The batch overshot, so step back through it one value at a time.
+        if g == n {
+            loop {
+                ys = f(ys);
+                g = gcd_u64(x.abs_diff(ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+
+    n
+}
+
+/// The prime factors of `n` with their multiplicities, in increasing order. `1` has no factors, and `0` fails.
+pub fn factorize(n: u64) -> Result<Vec<(u64, u32)>, Error> {
+    if n == 0 {
+        return Err(argument_error!("n", "0 has no prime factorization"));
+    }
+
+    let mut primes = vec![];
+    let mut n = n;
+    for p in [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+
+    let mut pending = vec![n];
+    while let Some(m) = pending.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            primes.push(m);
+            continue;
+        }
+        let d = pollard_rho(m);
+        pending.push(d);
+        pending.push(m / d);
+    }
+
+    primes.sort_unstable();
+    let mut result: Vec<(u64, u32)> = vec![];
+    for p in primes {
+        match result.last_mut() {
+            Some((q, k)) if *q == p => *k += 1,
+            _ => result.push((p, 1))
+        }
+    }
+
+    Ok(result)
+}
+/// Euler's totient, the count of integers in `[1, n]` coprime to `n`.
+pub fn totient(n: u64) -> Result<u64, Error> {
+    Ok( factorize(n)?.into_iter().fold(n, |acc, (p, _)| acc / p * (p - 1)) )
+}
+
+/// Solves the system `x = a_i (mod m_i)` by the Chinese remainder theorem, giving `x` modulo the lcm of the moduli.
+/// The moduli need not be coprime, but then the congruences must agree wherever the moduli overlap.
+pub fn crt(congruences: &[ModInt]) -> Result<ModInt, Error> {
+    let (mut x, mut m) = (0u128, 1u128);
+    for c in congruences {
+        let (a, n) = (c.value() as u128, c.modulus() as u128);
+        let (g, p, _) = extended_gcd_wide(m as i128, n as i128);
+        let g = g as u128;
+        let difference = (a as i128 - x as i128).rem_euclid(n as i128) as u128;
+        if !difference.is_multiple_of(g) {
+            return Err(operation_error!("crt", "x = {} (mod {}) contradicts the earlier congruences", a, n));
+        }
+
+        //x + m t satisfies the new congruence when t = (a - x)/g * p (mod n/g), where m p = g (mod n).
+        let step = n / g;
+        let t = ((difference / g) % step) * (p.rem_euclid(step as i128) as u128) % step;
+        let combined = m * step;
+        if combined > u64::MAX as u128 {
+            return Err(operation_error!("crt", "the combined modulus does not fit in 64 bits"));
+        }
+        x = (x + m * t) % combined;
+        m = combined;
+    }
+
+    ModInt::from_unsigned(x as u64, m as u64)
+}
+
+#[test]
+fn test_number_theory() {
+    assert_eq!(gcd(-48, 18), 6);
+    assert_eq!(gcd(0, 0), 0);
+    assert_eq!(gcd(i64::MIN, 0), 1 << 63);
+    assert_eq!(lcm(4, -6).unwrap(), 12);
+    assert!(lcm(i64::MAX, i64::MAX - 1).is_err());
+    let (g, x, y) = extended_gcd(240, 46);
+    assert_eq!((g, 240 * x + 46 * y), (2, 2));
+
+    assert_eq!(pow_mod(4, 13, 497), 445);
+    assert_eq!(pow_mod(5, 0, 1), 0);
+    assert_eq!(mod_inverse(-3, 7).unwrap(), 2);
+    assert!(mod_inverse(6, 9).is_err());
+
+    let primes: Vec<u64> = (0..50).filter(|n| is_prime(*n)).collect();
+    assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]);
+    assert!(is_prime(18446744073709551557));
+    assert!(!is_prime(3215031751));
+    assert!(!is_prime(3825123056546413051));
+
+    assert_eq!(factorize(1).unwrap(), vec![]);
+    assert_eq!(factorize(360).unwrap(), vec![(2, 3), (3, 2), (5, 1)]);
+    assert_eq!(factorize(600851475143).unwrap(), vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+    assert_eq!(factorize(18446744073709551557).unwrap(), vec![(18446744073709551557, 1)]);
+    assert_eq!(factorize(4294967291 * 4294967279).unwrap(), vec![(4294967279, 1), (4294967291, 1)]);
+    assert_eq!(factorize(u64::MAX).unwrap(), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6700417, 1)]);
+    assert!(factorize(0).is_err());
+    assert_eq!(totient(36).unwrap(), 12);
+    assert_eq!(totient(1).unwrap(), 1);
+    assert_eq!(totient(4294967291 * 4294967279).unwrap(), 4294967290 * 4294967278);
+
+    let m = |a: i64, n: u64| ModInt::new(a, n).unwrap();
+    assert_eq!(crt(&[m(2, 3), m(3, 5), m(2, 7)]).unwrap(), m(23, 105));
+    assert_eq!(crt(&[m(3, 4), m(5, 6)]).unwrap(), m(11, 12));
+    assert!(crt(&[m(1, 4), m(2, 6)]).is_err());
+    assert_eq!(crt(&[]).unwrap(), m(0, 1));
+}
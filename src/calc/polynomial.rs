@@ -0,0 +1,343 @@
+use std::f64::consts::PI;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Mul, Neg};
+
+use crate::{operation_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::{VariableType, SimpleNumerical}, vector::MVector, complex::Complex};
+
+/// A polynomial in one variable, stored by ascending power: `coefficients[k]` multiplies `x^k`.
+/// The leading coefficient is never zero, and the zero polynomial has no coefficients at all.
+#[derive(Clone, PartialEq)]
+pub struct Polynomial<T: SimpleNumerical> {
+    coefficients: Vec<T>
+}
+impl<T: SimpleNumerical> Debug for Polynomial<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Polynomial:{:?})", &self.coefficients)
+    }
+}
+impl<T: SimpleNumerical> Display for Polynomial<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (i, (k, c)) in self.coefficients.iter().enumerate().rev().filter(|(_, c)| c.abs() != 0.0).enumerate() {
+            let mut text = c.to_string();
+            //Parenthesize compound coefficients such as complex numbers, and pull a leading sign out into the operator.
+            let negative = text.starts_with('-') && !text[1..].contains(' ');
+            if negative {
+                text.remove(0);
+            }
+            else if text.contains(' ') {
+                text = format!("({})", text);
+            }
+
+            match (i, negative) {
+                (0, true) => write!(f, "-")?,
+                (0, false) => (),
+                (_, true) => write!(f, " - ")?,
+                (_, false) => write!(f, " + ")?
+            }
+            if k == 0 || text != "1" {
+                write!(f, "{}", text)?;
+            }
+            match k {
+                0 => (),
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{}", k)?
+            }
+        }
+
+        Ok(())
+    }
+}
+impl<T: SimpleNumerical> From<Polynomial<T>> for Vec<BinaryUnit> {
+    /// Encoded the same way as an `MVector` of the coefficients.
+    fn from(value: Polynomial<T>) -> Self {
+        MVector::from(value.coefficients).into()
+    }
+}
+impl<T: SimpleNumerical> TryFrom<Vec<BinaryUnit>> for Polynomial<T> {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        let coefficients = MVector::<T>::try_from(value)?;
+        Ok( Self::new(coefficients.iter().cloned().collect()) )
+    }
+}
+impl<T: SimpleNumerical> From<Vec<T>> for Polynomial<T> {
+    fn from(value: Vec<T>) -> Self {
+        Self::new(value)
+    }
+}
+impl<T: SimpleNumerical> Default for Polynomial<T> {
+    fn default() -> Self {
+        Self {
+            coefficients: vec![]
+        }
+    }
+}
+impl<T: SimpleNumerical> VariableType for Polynomial<T> {
+    fn required_units(&self) -> usize {
+        1 + self.coefficients.iter().map(|c| c.required_units()).sum::<usize>()
+    }
+}
+impl<T: SimpleNumerical> Neg for Polynomial<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(self.coefficients.into_iter().map(|mut c| { c *= -1.0; c }).collect())
+    }
+}
+impl<T: SimpleNumerical> Add for Polynomial<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        let (mut long, short) = if self.coefficients.len() >= rhs.coefficients.len() { (self.coefficients, rhs.coefficients) } else { (rhs.coefficients, self.coefficients) };
+        for (a, b) in long.iter_mut().zip(short) {
+            *a += b;
+        }
+
+        Self::new(long)
+    }
+}
+impl<T: SimpleNumerical> Sub for Polynomial<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+impl<T: SimpleNumerical> Mul for Polynomial<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::default();
+        }
+
+        let mut result = vec![T::default(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                let mut term = a.clone();
+                term *= b.clone();
+                result[i + j] += term;
+            }
+        }
+
+        Self::new(result)
+    }
+}
+impl<T: SimpleNumerical> Mul<T> for Polynomial<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.coefficients.into_iter().map(|mut c| { c *= rhs.clone(); c }).collect())
+    }
+}
+
+/// Remainders whose coefficients are all this small, relative to the inputs, count as zero when computing a gcd.
+const GCD_TOLERANCE: f64 = 1e-10;
+
+impl<T: SimpleNumerical> Polynomial<T> {
+    /// Builds the polynomial `sum coefficients[k] x^k`, dropping any zero leading coefficients.
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        while coefficients.last().is_some_and(|c| c.abs() == 0.0) {
+            coefficients.pop();
+        }
+
+        Self {
+            coefficients
+        }
+    }
+    /// The polynomial `x`.
+    pub fn x() -> Self {
+        let mut one = T::default();
+        one += 1.0;
+        Self::new(vec![T::default(), one])
+    }
+
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+    pub fn leading(&self) -> Option<&T> {
+        self.coefficients.last()
+    }
+    /// Divides through by the leading coefficient.
+    pub fn monic(&self) -> Result<Self, Error> {
+        let lead = self.leading().ok_or_else(|| operation_error!("monic", "the zero polynomial has no leading coefficient"))?.clone();
+        Ok( Self::new(self.coefficients.iter().cloned().map(|mut c| { c /= lead.clone(); c }).collect()) )
+    }
+
+    /// Evaluates at `x` by Horner's rule.
+    pub fn eval(&self, x: &T) -> T {
+        self.coefficients.iter().rev().fold(T::default(), |mut acc, c| {
+            acc *= x.clone();
+            acc += c.clone();
+            acc
+        })
+    }
+    pub fn derivative(&self) -> Self {
+        Self::new(self.coefficients.iter().enumerate().skip(1).map(|(k, c)| { let mut c = c.clone(); c *= k as f64; c }).collect())
+    }
+    /// The antiderivative with a constant term of zero.
+    pub fn antiderivative(&self) -> Self {
+        let mut result = vec![T::default()];
+        result.extend(self.coefficients.iter().enumerate().map(|(k, c)| { let mut c = c.clone(); c /= (k + 1) as f64; c }));
+
+        Self::new(result)
+    }
+
+    /// Long division, returning `(quotient, remainder)` with `self = quotient * rhs + remainder` and the remainder of lower degree than `rhs`.
+    pub fn div_rem(&self, rhs: &Self) -> Result<(Self, Self), Error> {
+        let (Some(d), Some(lead)) = (rhs.degree(), rhs.leading()) else {
+            return Err(operator_error!('/', self, rhs));
+        };
+        let n = match self.degree() {
+            Some(n) if n >= d => n,
+            _ => return Ok( (Self::default(), self.clone()) )
+        };
+
+        let mut rem = self.coefficients.clone();
+        let mut quot = vec![T::default(); n - d + 1];
+        for k in (0..=n - d).rev() {
+            let mut c = rem[k + d].clone();
+            c /= lead.clone();
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                let mut term = b.clone();
+                term *= c.clone();
+                rem[k + j] -= term;
+            }
+            //The leading term cancels exactly, even if rounding says otherwise.
+            rem[k + d] = T::default();
+            quot[k] = c;
+        }
+        rem.truncate(d);
+
+        Ok( (Self::new(quot), Self::new(rem)) )
+    }
+    /// The monic greatest common divisor. With floating point coefficients, remainders that are negligible next to the inputs are treated as zero.
+    pub fn gcd(&self, rhs: &Self) -> Result<Self, Error> {
+        let scale = self.coefficients.iter().chain(rhs.coefficients.iter()).map(|c| c.abs()).fold(0.0, f64::max);
+        if scale == 0.0 {
+            return Err(operation_error!("gcd", "gcd(0, 0) is undefined"));
+        }
+
+        let (mut a, mut b) = (self.clone(), rhs.clone());
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b)?;
+            let r = if r.coefficients.iter().all(|c| c.abs() <= GCD_TOLERANCE * scale) { Self::default() } else { r };
+            (a, b) = (b, r);
+        }
+
+        a.monic()
+    }
+}
+impl<T: SimpleNumerical + Into<Complex>> Polynomial<T> {
+    /// Evaluates at a complex point.
+    pub fn eval_complex(&self, z: Complex) -> Complex {
+        self.coefficients.iter().rev().fold(Complex::default(), |acc, c| acc * z + Into::<Complex>::into(c.clone()))
+    }
+    /// Every root, repeated by multiplicity, found by the Durand–Kerner iteration and then polished with Newton's method.
+    /// Repeated roots converge more slowly and are only accurate to about half of the working precision.
+    pub fn roots(&self) -> Result<MVector<Complex>, Error> {
+        if self.is_zero() {
+            return Err(operation_error!("roots", "every value is a root of the zero polynomial"));
+        }
+
+        //Roots at zero are exact, so factor them out first.
+        let zeros = self.coefficients.iter().take_while(|c| c.abs() == 0.0).count();
+        let monic: Vec<Complex> = {
+            let coefficients: Vec<Complex> = self.coefficients[zeros..].iter().cloned().map(|c| c.into()).collect();
+            let lead = *coefficients.last().unwrap();
+            coefficients.into_iter().map(|c| c / lead).collect()
+        };
+        let n = monic.len() - 1;
+        let p = |z: Complex| monic.iter().rev().fold(Complex::default(), |acc, c| acc * z + *c);
+
+        //Start on a circle that contains every root (Cauchy's bound), slightly rotated so no guess is real.
+        let radius = 1.0 + monic[..n].iter().map(|c| c.modulus()).fold(0.0, f64::max);
+        let mut roots: Vec<Complex> = (0..n).map(|k| Complex::from_polar(radius, 2.0 * PI * k as f64 / n as f64 + 0.4)).collect();
+        for _ in 0..1000 {
+            let mut change: f64 = 0.0;
+            for k in 0..n {
+                let denom = (0..n).filter(|&j| j != k).fold(Complex::from(1.0), |acc, j| acc * (roots[k] - roots[j]));
+                if denom.modulus() == 0.0 {
+                    roots[k] += Complex::from_polar(1e-8 * radius, k as f64);
+                    change = f64::INFINITY;
+                    continue;
+                }
+
+                let step = p(roots[k]) / denom;
+                roots[k] -= step;
+                change = change.max(step.modulus() / roots[k].modulus().max(1.0));
+            }
+            if change < 1e-15 {
+                break;
+            }
+        }
+
+        let derivative: Vec<Complex> = monic.iter().enumerate().skip(1).map(|(k, c)| *c * k as f64).collect();
+        let dp = |z: Complex| derivative.iter().rev().fold(Complex::default(), |acc, c| acc * z + *c);
+        for root in roots.iter_mut() {
+            for _ in 0..3 {
+                let slope = dp(*root);
+                if slope.modulus() == 0.0 {
+                    break;
+                }
+                let candidate = *root - p(*root) / slope;
+                if p(candidate).modulus() >= p(*root).modulus() {
+                    break;
+                }
+                *root = candidate;
+            }
+        }
+
+        let mut result = vec![Complex::default(); zeros];
+        result.extend(roots);
+        Ok( MVector::from(result) )
+    }
+}
+
+#[test]
+fn test_polynomial_operations() {
+    use super::scalar::Scalar;
+    let poly = |c: &[f64]| Polynomial::new(c.iter().map(|&x| Scalar::from(x)).collect());
+
+    //(x - 1)(x + 2) = x^2 + x - 2
+    let p = poly(&[-2.0, 1.0, 1.0]);
+    assert_eq!(p.clone() * poly(&[3.0, 1.0]), poly(&[-6.0, 1.0, 4.0, 1.0]));
+    assert_eq!(p.clone() + poly(&[2.0, 0.0, -1.0]), poly(&[0.0, 1.0]));
+    assert_eq!(format!("{}", poly(&[-2.0, 1.0, -3.0])), "-3x^2 + x - 2");
+    assert_eq!(p.eval(&Scalar::from(3.0)), Scalar::from(10.0));
+    assert_eq!(p.derivative(), poly(&[1.0, 2.0]));
+    assert_eq!(poly(&[1.0, 2.0]).antiderivative(), poly(&[0.0, 1.0, 1.0]));
+
+    let (q, r) = poly(&[1.0, 0.0, 0.0, 1.0]).div_rem(&poly(&[1.0, 1.0])).unwrap();
+    assert_eq!((q, r), (poly(&[1.0, -1.0, 1.0]), poly(&[])));
+    let (q, r) = poly(&[5.0, 0.0, 1.0]).div_rem(&poly(&[-1.0, 1.0])).unwrap();
+    assert_eq!((q, r), (poly(&[1.0, 1.0]), poly(&[6.0])));
+    assert!(p.div_rem(&Polynomial::default()).is_err());
+
+    //gcd((x - 1)(x + 2), (x - 1)(x - 3)) = x - 1
+    assert_eq!(p.gcd(&poly(&[3.0, -4.0, 1.0])).unwrap(), poly(&[-1.0, 1.0]));
+
+    //x^4 - 1 has roots ±1, ±i; x^3 - 2x^2 has a double root at zero.
+    let roots = poly(&[-1.0, 0.0, 0.0, 0.0, 1.0]).roots().unwrap();
+    for expected in [Complex::from(1.0), Complex::from(-1.0), Complex::I, -Complex::I] {
+        assert!(roots.iter().any(|r| r.approx_eq(&expected, 1e-12)));
+    }
+    let roots = poly(&[0.0, 0.0, -2.0, 1.0]).roots().unwrap();
+    assert_eq!(roots.dim(), 3);
+    assert!(roots[0] == 0.0 && roots[1] == 0.0 && roots[2].approx_eq(&Complex::from(2.0), 1e-12));
+
+    let cp = Polynomial::new(vec![Complex::new(0.0, -1.0), Complex::from(0.0), Complex::from(1.0)]);
+    assert_eq!(format!("{}", cp), "(1 + 0i)x^2 + (0 - 1i)");
+    let roots = cp.roots().unwrap();
+    assert!(roots.iter().all(|r| cp.eval_complex(*r).modulus() < 1e-12));
+
+    let units: Vec<BinaryUnit> = p.clone().into();
+    assert_eq!(Polynomial::<Scalar>::try_from(units).unwrap(), p);
+}
@@ -0,0 +1,349 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Mul, Neg};
+
+use crate::{argument_error, binary_unit, conversion_error, operation_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, scalar::Scalar, vector::MVector, matrix::Matrix};
+
+/// How far a matrix may stray from orthonormal and still be read as a rotation.
+const ROTATION_TOLERANCE: f64 = 1e-6;
+
+/// A quaternion `w + xi + yj + zk`. Unit quaternions represent rotations in three dimensions.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct Quaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64
+}
+impl Debug for Quaternion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Quaternion:{}+{}i+{}j+{}k)", self.w, self.x, self.y, self.z)
+    }
+}
+impl Display for Quaternion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.w)?;
+        for (value, unit) in [(self.x, 'i'), (self.y, 'j'), (self.z, 'k')] {
+            if value.is_sign_negative() {
+                write!(f, " - {}{}", -value, unit)?;
+            }
+            else {
+                write!(f, " + {}{}", value, unit)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+impl From<Quaternion> for Vec<BinaryUnit> {
+    fn from(value: Quaternion) -> Self {
+        vec![ binary_unit!(value.w), binary_unit!(value.x), binary_unit!(value.y), binary_unit!(value.z) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Quaternion {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.len() != 4 {
+            return Err(conversion_error!("expected 4 units, got {}", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let w: f64 = iter.next().unwrap().try_into()?;
+        let x: f64 = iter.next().unwrap().try_into()?;
+        let y: f64 = iter.next().unwrap().try_into()?;
+        let z: f64 = iter.next().unwrap().try_into()?;
+
+        Ok(Self::new(w, x, y, z))
+    }
+}
+impl VariableType for Quaternion {
+    fn required_units(&self) -> usize {
+        4usize
+    }
+}
+impl Neg for Quaternion {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
+impl Add for Quaternion {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.w + rhs.w, self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+impl Sub for Quaternion {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+impl Mul for Quaternion {
+    type Output = Self;
+    /// The Hamilton product. As rotations, `a * b` applies `b` first and then `a`.
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w
+        )
+    }
+}
+impl Mul<f64> for Quaternion {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self::new(self.w * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self {
+            w,
+            x,
+            y,
+            z
+        }
+    }
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    pub fn w(&self) -> f64 {
+        self.w
+    }
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.w, -self.x, -self.y, -self.z)
+    }
+    pub fn dot(&self, rhs: &Self) -> f64 {
+        self.w * rhs.w + self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+    pub fn norm_squared(&self) -> f64 {
+        self.dot(self)
+    }
+    pub fn norm(&self) -> f64 {
+        self.norm_squared().sqrt()
+    }
+    pub fn normalize(&self) -> Result<Self, Error> {
+        let norm = self.norm();
+        if norm == 0.0 || !norm.is_finite() {
+            return Err(operation_error!("normalize", "the quaternion {} has no direction", self));
+        }
+
+        Ok( *self * (1.0 / norm) )
+    }
+    pub fn inverse(&self) -> Result<Self, Error> {
+        let norm_squared = self.norm_squared();
+        if norm_squared == 0.0 || !norm_squared.is_finite() {
+            return Err(operation_error!("inverse", "the quaternion {} is not invertible", self));
+        }
+
+        Ok( self.conjugate() * (1.0 / norm_squared) )
+    }
+
+    /// Spherical linear interpolation between the rotations `self` (at `t = 0`) and `rhs` (at `t = 1`), taking the shorter path.
+    pub fn slerp(&self, rhs: &Self, t: f64) -> Result<Self, Error> {
+        let a = self.normalize()?;
+        let mut b = rhs.normalize()?;
+        let mut cos_theta = a.dot(&b);
+        if cos_theta < 0.0 {
+            b = -b;
+            cos_theta = -cos_theta;
+        }
+
+        //Nearly parallel, so sin(theta) is too small to divide by.
+        if cos_theta > 0.9995 {
+            return (a + (b - a) * t).normalize();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        Ok( a * (((1.0 - t) * theta).sin() / sin_theta) + b * ((t * theta).sin() / sin_theta) )
+    }
+
+    fn components(v: &MVector<Scalar>, action: &str) -> Result<[f64; 3], Error> {
+        if v.dim() != 3 {
+            return Err(operation_error!(action, "expected a vector of dim = 3, got dim = {}", v.dim()));
+        }
+
+        Ok( [f64::from(v[0].clone()), f64::from(v[1].clone()), f64::from(v[2].clone())] )
+    }
+
+    /// The rotation by `angle` radians about `axis`, counterclockwise when looking down the axis.
+    pub fn from_axis_angle(axis: &MVector<Scalar>, angle: f64) -> Result<Self, Error> {
+        let [x, y, z] = Self::components(axis, "from axis-angle")?;
+        let length = (x * x + y * y + z * z).sqrt();
+        if length == 0.0 || !length.is_finite() {
+            return Err(argument_error!("axis", "must be a non-zero vector, got {}", axis));
+        }
+
+        let s = (angle / 2.0).sin() / length;
+        Ok( Self::new((angle / 2.0).cos(), x * s, y * s, z * s) )
+    }
+    /// The unit axis and the angle in `[0, 2pi]` of this rotation. The identity gives the x axis and an angle of 0.
+    pub fn to_axis_angle(&self) -> Result<(MVector<Scalar>, f64), Error> {
+        let q = self.normalize()?;
+        let length = (q.x * q.x + q.y * q.y + q.z * q.z).sqrt();
+        if length == 0.0 {
+            return Ok( (MVector::from(vec![Scalar::from(1.0), Scalar::from(0.0), Scalar::from(0.0)]), 0.0) );
+        }
+
+        let axis = vec![Scalar::from(q.x / length), Scalar::from(q.y / length), Scalar::from(q.z / length)];
+        Ok( (MVector::from(axis), 2.0 * length.atan2(q.w)) )
+    }
+
+    /// Builds a rotation from roll (about x), pitch (about y) and yaw (about z), in radians. The rotations apply as yaw, then pitch, then roll about the rotated axes (intrinsic Z-Y'-X'').
+    pub fn from_euler(roll: f64, pitch: f64, yaw: f64) -> Self {
+        let (sr, cr) = (roll / 2.0).sin_cos();
+        let (sp, cp) = (pitch / 2.0).sin_cos();
+        let (sy, cy) = (yaw / 2.0).sin_cos();
+
+        Self::new(
+            cr * cp * cy + sr * sp * sy,
+            sr * cp * cy - cr * sp * sy,
+            cr * sp * cy + sr * cp * sy,
+            cr * cp * sy - sr * sp * cy
+        )
+    }
+    /// The `(roll, pitch, yaw)` angles matching `from_euler`. Pitch lies in `[-pi/2, pi/2]`; at those limits, roll is reported as 0.
+    pub fn to_euler(&self) -> Result<(f64, f64, f64), Error> {
+        let q = self.normalize()?;
+        let sin_pitch = (2.0 * (q.w * q.y - q.z * q.x)).clamp(-1.0, 1.0);
+        let pitch = sin_pitch.asin();
+
+        //Gimbal lock, where roll and yaw turn about the same axis.
+        if sin_pitch.abs() > 1.0 - 1e-12 {
+            let yaw = -2.0 * sin_pitch.signum() * q.x.atan2(q.w);
+            return Ok( (0.0, pitch, yaw) );
+        }
+
+        let roll = (2.0 * (q.w * q.x + q.y * q.z)).atan2(1.0 - 2.0 * (q.x * q.x + q.y * q.y));
+        let yaw = (2.0 * (q.w * q.z + q.x * q.y)).atan2(1.0 - 2.0 * (q.y * q.y + q.z * q.z));
+        Ok( (roll, pitch, yaw) )
+    }
+
+    /// The 3x3 rotation matrix, so that `to_matrix() * v` equals `rotate(v)`.
+    pub fn to_matrix(&self) -> Result<Matrix, Error> {
+        let Self { w, x, y, z } = self.normalize()?;
+        Matrix::from_rows(vec![
+            vec![1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            vec![2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            vec![2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)]
+        ])
+    }
+    /// Reads a 3x3 rotation matrix, which must be orthonormal with a determinant of 1.
+    pub fn from_matrix(m: &Matrix) -> Result<Self, Error> {
+        if m.rows() != 3 || m.cols() != 3 {
+            return Err(argument_error!("m", "must be 3x3, got {}x{}", m.rows(), m.cols()));
+        }
+        let product = (m.clone() * m.transpose())?;
+        let orthonormal = (0..3).all(|i| (0..3).all(|j| (product[(i, j)] - if i == j { 1.0 } else { 0.0 }).abs() <= ROTATION_TOLERANCE));
+        if !orthonormal || (m.determinant()? - 1.0).abs() > ROTATION_TOLERANCE {
+            return Err(operation_error!("from matrix", "the matrix is not a rotation"));
+        }
+
+        //Shepperd's method, dividing by the largest of the four candidates for stability.
+        let a = |i: usize, j: usize| m[(i, j)];
+        let trace = a(0, 0) + a(1, 1) + a(2, 2);
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self::new(0.25 * s, (a(2, 1) - a(1, 2)) / s, (a(0, 2) - a(2, 0)) / s, (a(1, 0) - a(0, 1)) / s)
+        }
+        else if a(0, 0) > a(1, 1) && a(0, 0) > a(2, 2) {
+            let s = (1.0 + a(0, 0) - a(1, 1) - a(2, 2)).sqrt() * 2.0;
+            Self::new((a(2, 1) - a(1, 2)) / s, 0.25 * s, (a(0, 1) + a(1, 0)) / s, (a(0, 2) + a(2, 0)) / s)
+        }
+        else if a(1, 1) > a(2, 2) {
+            let s = (1.0 + a(1, 1) - a(0, 0) - a(2, 2)).sqrt() * 2.0;
+            Self::new((a(0, 2) - a(2, 0)) / s, (a(0, 1) + a(1, 0)) / s, 0.25 * s, (a(1, 2) + a(2, 1)) / s)
+        }
+        else {
+            let s = (1.0 + a(2, 2) - a(0, 0) - a(1, 1)).sqrt() * 2.0;
+            Self::new((a(1, 0) - a(0, 1)) / s, (a(0, 2) + a(2, 0)) / s, (a(1, 2) + a(2, 1)) / s, 0.25 * s)
+        };
+
+        q.normalize()
+    }
+
+    /// Rotates a vector of dim = 3. The quaternion is normalized first, so any non-zero quaternion may be used.
+    pub fn rotate(&self, v: &MVector<Scalar>) -> Result<MVector<Scalar>, Error> {
+        let [vx, vy, vz] = Self::components(v, "rotate")?;
+        let q = self.normalize()?;
+
+        //v + 2w(u x v) + 2u x (u x v), with u the vector part.
+        let t = [2.0 * (q.y * vz - q.z * vy), 2.0 * (q.z * vx - q.x * vz), 2.0 * (q.x * vy - q.y * vx)];
+        let result = vec![
+            vx + q.w * t[0] + (q.y * t[2] - q.z * t[1]),
+            vy + q.w * t[1] + (q.z * t[0] - q.x * t[2]),
+            vz + q.w * t[2] + (q.x * t[1] - q.y * t[0])
+        ];
+
+        Ok( MVector::from(result.into_iter().map(Scalar::from).collect::<Vec<Scalar>>()) )
+    }
+}
+
+#[test]
+fn test_quaternion_rotations() {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+    let vector = |x: f64, y: f64, z: f64| MVector::from(vec![Scalar::from(x), Scalar::from(y), Scalar::from(z)]);
+    let close = |a: &MVector<Scalar>, b: &MVector<Scalar>| a.distance(b).unwrap() < 1e-12;
+    let close_q = |a: Quaternion, b: Quaternion| (a - b).norm() < 1e-12;
+
+    //The Hamilton product: ij = k, ji = -k, and i^2 = -1.
+    let (i, j, k) = (Quaternion::new(0.0, 1.0, 0.0, 0.0), Quaternion::new(0.0, 0.0, 1.0, 0.0), Quaternion::new(0.0, 0.0, 0.0, 1.0));
+    assert_eq!(i * j, k);
+    assert_eq!(j * i, -k);
+    assert_eq!(i * i, Quaternion::new(-1.0, 0.0, 0.0, 0.0));
+    let q = Quaternion::new(1.0, 2.0, -3.0, 4.0);
+    assert_eq!(q.to_string(), "1 + 2i - 3j + 4k");
+    assert!(close_q(q * q.inverse().unwrap(), Quaternion::identity()));
+    assert!((q.normalize().unwrap().norm() - 1.0).abs() < 1e-15);
+    assert!(Quaternion::default().inverse().is_err());
+
+    let z_quarter = Quaternion::from_axis_angle(&vector(0.0, 0.0, 2.0), FRAC_PI_2).unwrap();
+    assert!(close(&z_quarter.rotate(&vector(1.0, 0.0, 0.0)).unwrap(), &vector(0.0, 1.0, 0.0)));
+    assert!(z_quarter.rotate(&MVector::from(vec![Scalar::from(1.0)])).is_err());
+    let (axis, angle) = z_quarter.to_axis_angle().unwrap();
+    assert!(close(&axis, &vector(0.0, 0.0, 1.0)));
+    assert!((angle - FRAC_PI_2).abs() < 1e-12);
+
+    //Composition applies the right-hand rotation first.
+    let x_quarter = Quaternion::from_axis_angle(&vector(1.0, 0.0, 0.0), FRAC_PI_2).unwrap();
+    assert!(close(&(x_quarter * z_quarter).rotate(&vector(1.0, 0.0, 0.0)).unwrap(), &vector(0.0, 0.0, 1.0)));
+
+    let oriented = Quaternion::from_euler(0.3, -0.7, 2.1);
+    let (roll, pitch, yaw) = oriented.to_euler().unwrap();
+    assert!((roll - 0.3).abs() < 1e-12 && (pitch + 0.7).abs() < 1e-12 && (yaw - 2.1).abs() < 1e-12);
+    assert!(close_q(Quaternion::from_euler(0.0, 0.0, FRAC_PI_2), z_quarter));
+    let (roll, pitch, yaw) = Quaternion::from_euler(0.0, FRAC_PI_2, 0.4).to_euler().unwrap();
+    assert!(roll == 0.0 && (pitch - FRAC_PI_2).abs() < 1e-6 && (yaw - 0.4).abs() < 1e-6);
+
+    let matrix = oriented.to_matrix().unwrap();
+    let v = vector(0.5, -1.0, 2.0);
+    assert!(close(&(matrix.clone() * v.clone()).unwrap(), &oriented.rotate(&v).unwrap()));
+    assert!(close_q(Quaternion::from_matrix(&matrix).unwrap(), oriented));
+    let half_turn = Quaternion::from_axis_angle(&vector(0.0, 1.0, 1.0), PI).unwrap();
+    let recovered = Quaternion::from_matrix(&half_turn.to_matrix().unwrap()).unwrap();
+    assert!(close_q(recovered, half_turn) || close_q(recovered, -half_turn));
+    assert!(Quaternion::from_matrix(&Matrix::diagonal(&[1.0, 1.0, -1.0])).is_err());
+    assert!(Quaternion::from_matrix(&Matrix::identity(2)).is_err());
+
+    let halfway = Quaternion::identity().slerp(&z_quarter, 0.5).unwrap();
+    assert!(close_q(halfway, Quaternion::from_axis_angle(&vector(0.0, 0.0, 1.0), FRAC_PI_4).unwrap()));
+    assert!(close_q(Quaternion::identity().slerp(&-z_quarter, 1.0).unwrap(), z_quarter));
+
+    let units: Vec<BinaryUnit> = q.into();
+    assert_eq!(Quaternion::try_from(units).unwrap(), q);
+}
@@ -0,0 +1,210 @@
+use std::fmt::{Debug, Display};
+
+use crate::{argument_error, format_error, range_error, core::errors::Error};
+
+/// A base for reading and writing integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Radix {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hexadecimal
+}
+impl Radix {
+    pub fn base(&self) -> u32 {
+        match self {
+            Self::Binary => 2,
+            Self::Octal => 8,
+            Self::Decimal => 10,
+            Self::Hexadecimal => 16
+        }
+    }
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::Binary => "0b",
+            Self::Octal => "0o",
+            Self::Decimal => "",
+            Self::Hexadecimal => "0x"
+        }
+    }
+}
+
+/// A machine word of `width` bits (1 to 64), holding either two's complement signed values or unsigned values.
+/// Values are stored in an `i64`; an unsigned 64-bit word keeps its bit pattern, so its values above `i64::MAX` appear negative.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordFormat {
+    width: u32,
+    signed: bool
+}
+impl Debug for WordFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(WordFormat:{})", self)
+    }
+}
+impl Display for WordFormat {
+    /// Written like a Rust integer type, such as `i32` or `u8`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", if self.signed { 'i' } else { 'u' }, self.width)
+    }
+}
+impl Default for WordFormat {
+    fn default() -> Self {
+        Self {
+            width: 64,
+            signed: true
+        }
+    }
+}
+
+impl WordFormat {
+    pub fn new(width: u32, signed: bool) -> Result<Self, Error> {
+        if width == 0 || width > 64 {
+            return Err(argument_error!("width", "must be between 1 and 64 bits, got {}", width));
+        }
+
+        Ok( Self { width, signed } )
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// The bits that belong to the word.
+    pub fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+    /// The smallest value the word holds.
+    pub fn min(&self) -> i128 {
+        if self.signed { -(1i128 << (self.width - 1)) } else { 0 }
+    }
+    /// The largest value the word holds.
+    pub fn max(&self) -> i128 {
+        if self.signed { (1i128 << (self.width - 1)) - 1 } else { self.mask() as i128 }
+    }
+    /// The low `width` bits of `value`.
+    pub fn bits(&self, value: i64) -> u64 {
+        value as u64 & self.mask()
+    }
+    /// Truncates `value` to the word, sign extending it for signed words.
+    pub fn wrap(&self, value: i64) -> i64 {
+        let bits = self.bits(value);
+        if self.signed && bits >> (self.width - 1) & 1 == 1 {
+            (bits | !self.mask()) as i64
+        }
+        else {
+            bits as i64
+        }
+    }
+
+    /// Writes `value` in `radix`. Binary, octal and hexadecimal show the word's bit pattern with a prefix, padded to the full width; decimal shows its value.
+    pub fn format(&self, value: i64, radix: Radix) -> String {
+        let bits = self.bits(value);
+        let digits = |bits_per_digit: u32| self.width.div_ceil(bits_per_digit) as usize;
+        match radix {
+            Radix::Binary => format!("0b{:0width$b}", bits, width = digits(1)),
+            Radix::Octal => format!("0o{:0width$o}", bits, width = digits(3)),
+            Radix::Hexadecimal => format!("0x{:0width$X}", bits, width = digits(4)),
+            Radix::Decimal if self.signed => self.wrap(value).to_string(),
+            Radix::Decimal => bits.to_string()
+        }
+    }
+    /// Reads an integer written in decimal, or in binary, octal or hexadecimal with a `0b`, `0o` or `0x` prefix. Underscores are ignored.
+    /// A prefixed literal without a sign is a bit pattern, so `0xFF` is `-1` in an `i8`; anything else must lie in the word's range.
+    pub fn parse(&self, s: &str) -> Result<i64, Error> {
+        let cleaned: String = s.trim().chars().filter(|c| *c != '_').collect();
+        let (negative, unsigned) = match cleaned.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, cleaned.strip_prefix('+').unwrap_or(&cleaned))
+        };
+
+        let lower = unsigned.to_ascii_lowercase();
+        let (radix, digits) = [Radix::Binary, Radix::Octal, Radix::Hexadecimal].into_iter()
+            .find_map(|r| lower.strip_prefix(r.prefix()).map(|d| (r, d)))
+            .unwrap_or((Radix::Decimal, lower.as_str()));
+        if digits.is_empty() || digits.starts_with(['+', '-']) {
+            return Err(format_error!(s, "expected digits"));
+        }
+        let magnitude = u128::from_str_radix(digits, radix.base()).map_err(|_| format_error!(s, "not a valid base {} integer", radix.base()))?;
+
+        if radix != Radix::Decimal && !negative {
+            if magnitude > self.mask() as u128 {
+                return Err(range_error!("value", s, 0, self.mask()));
+            }
+            return Ok( self.wrap(magnitude as u64 as i64) );
+        }
+
+        let value = match i128::try_from(magnitude) {
+            Ok(m) if negative => -m,
+            Ok(m) => m,
+            Err(_) => i128::MAX
+        };
+        if value < self.min() || value > self.max() {
+            return Err(range_error!("value", s, self.min(), self.max()));
+        }
+
+        Ok( value as u64 as i64 )
+    }
+}
+
+#[test]
+fn test_radix_and_bitwise() {
+    use super::variable_data::Integer;
+    let word = |width: u32, signed: bool| WordFormat::new(width, signed).unwrap();
+    let (i8w, u8w, u16w, u64w) = (word(8, true), word(8, false), word(16, false), word(64, false));
+    assert!(WordFormat::new(0, true).is_err());
+    assert!(WordFormat::new(65, false).is_err());
+    assert_eq!(i8w.to_string(), "i8");
+    assert_eq!((i8w.min(), i8w.max(), u8w.max()), (-128, 127, 255));
+
+    assert_eq!(i8w.format(-1, Radix::Hexadecimal), "0xFF");
+    assert_eq!(i8w.format(-1, Radix::Decimal), "-1");
+    assert_eq!(u8w.format(-1, Radix::Decimal), "255");
+    assert_eq!(u16w.format(0xBEEF, Radix::Binary), "0b1011111011101111");
+    assert_eq!(word(12, false).format(5, Radix::Octal), "0o0005");
+    assert_eq!(word(32, true).format(48879, Radix::Hexadecimal), "0x0000BEEF");
+    assert_eq!(u64w.format(-1, Radix::Decimal), "18446744073709551615");
+
+    assert_eq!(i8w.parse("0xFF").unwrap(), -1);
+    assert_eq!(u8w.parse("0xff").unwrap(), 255);
+    assert_eq!(u16w.parse("0b1010_0101").unwrap(), 0xA5);
+    assert_eq!(i8w.parse("-0o20").unwrap(), -16);
+    assert_eq!(i8w.parse("-128").unwrap(), -128);
+    assert!(i8w.parse("128").is_err());
+    assert!(i8w.parse("0x1FF").is_err());
+    assert!(u8w.parse("-1").is_err());
+    assert!(u8w.parse("0x").is_err());
+    assert!(u8w.parse("0xG1").is_err());
+    assert!(u8w.parse("--1").is_err());
+    assert_eq!(u64w.format(u64w.parse("18446744073709551615").unwrap(), Radix::Hexadecimal), "0xFFFFFFFFFFFFFFFF");
+
+    let i = Integer::from;
+    assert_eq!(i(0b1100).and_with(&i(0b1010), u8w), i(0b1000));
+    assert_eq!(i(0b1100).or_with(&i(0b1010), u8w), i(0b1110));
+    assert_eq!(i(0b1100).xor_with(&i(0b1010), u8w), i(0b0110));
+    assert_eq!(i(0).not_with(u8w), i(255));
+    assert_eq!(i(0).not_with(i8w), i(-1));
+    assert_eq!(i(0x81).shl_with(1, u8w), i(0x02));
+    assert_eq!(i(-128).shr_with(3, i8w), i(-16));
+    assert_eq!(i(0x80).shr_with(3, u8w), i(0x10));
+    assert_eq!(i(1).shl_with(8, u8w), i(0));
+    assert_eq!(i(-1).shr_with(100, i8w), i(-1));
+    assert_eq!(i(0x81).rotate_left_with(1, u8w), i(0x03));
+    assert_eq!(i(0x81).rotate_right_with(1, u8w), i(0xC0));
+    assert_eq!(i(0x1234).rotate_left_with(20, u16w), i(0x2341));
+    assert_eq!(i(1).rotate_right_with(1, u64w), i(i64::MIN));
+
+    //The session's default word is i64.
+    assert_eq!(!i(0), i(-1));
+    assert_eq!(i(6) & i(3), i(2));
+    assert_eq!(i(6) | i(3), i(7));
+    assert_eq!(i(6) ^ i(3), i(5));
+    assert_eq!(i(1) << 63, i(i64::MIN));
+    assert_eq!(i(i64::MIN) >> 63, i(-1));
+    assert_eq!(i(i64::MIN).rotate_left(1), i(1));
+    assert_eq!(i(255).to_radix(Radix::Hexadecimal), "0x00000000000000FF");
+    assert_eq!(Integer::parse_radix("-0x10").unwrap(), i(-16));
+    assert_eq!(format!("{:#x} {:o} {:b}", i(255), i(8), i(5)), "0xff 10 101");
+}
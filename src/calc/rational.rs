@@ -0,0 +1,436 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg};
+use std::str::FromStr;
+
+use crate::{binary_unit, conversion_error, format_error, operation_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::{VariableType, SimpleNumerical}, scalar::Scalar};
+
+/// An exact fraction `num / den`, always stored in lowest terms with a positive denominator.
+/// A denominator of zero marks an undefined value (the result of dividing by zero or overflowing in an assignment operator), much like `NaN`.
+#[derive(Clone, Copy)]
+pub struct Rational {
+    num: i64,
+    den: i64
+}
+impl Debug for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Rational:{})", self)
+    }
+}
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_nan() {
+            write!(f, "NaN")
+        }
+        else if self.den == 1 {
+            write!(f, "{}", self.num)
+        }
+        else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+impl PartialEq for Rational {
+    /// Like `f64`, `NaN` is unequal to everything, itself included.
+    fn eq(&self, other: &Self) -> bool {
+        !self.is_nan() && !other.is_nan() && self.num == other.num && self.den == other.den
+    }
+}
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+
+        //Both denominators are positive, so cross multiplying keeps the order.
+        Some( (self.num as i128 * other.den as i128).cmp(&(other.num as i128 * self.den as i128)) )
+    }
+}
+impl From<Rational> for Vec<BinaryUnit> {
+    fn from(value: Rational) -> Self {
+        vec![ binary_unit!(value.num), binary_unit!(value.den) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Rational {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.len() != 2 {
+            return Err(conversion_error!("expected 2 units, got {}", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let num: i64 = iter.next().unwrap().try_into()?;
+        let den: i64 = iter.next().unwrap().try_into()?;
+        if den == 0 {
+            return Ok(Self::nan());
+        }
+
+        Self::reduce(num as i128, den as i128).ok_or_else(|| conversion_error!("{}/{} cannot be stored in lowest terms", num, den))
+    }
+}
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self {
+            num: value,
+            den: 1
+        }
+    }
+}
+impl From<f64> for Rational {
+    /// Converts exactly when `value` is a small enough integer or binary fraction, and otherwise takes the closest continued fraction approximation whose denominator stays under `10^12`.
+    fn from(value: f64) -> Self {
+        Self::approximate(value, 1_000_000_000_000)
+    }
+}
+impl From<Scalar> for Rational {
+    fn from(value: Scalar) -> Self {
+        Self::from(f64::from(value))
+    }
+}
+impl From<Rational> for f64 {
+    fn from(value: Rational) -> Self {
+        value.to_f64()
+    }
+}
+impl FromStr for Rational {
+    type Err = Error;
+    /// Parses `"a/b"`, `"a"`, or a decimal such as `"0.125"`, which is read exactly.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let parse_int = |part: &str| part.trim().parse::<i64>().map_err(|_| format_error!(s, "'{}' is not an integer", part));
+
+        if let Some((num, den)) = s.split_once('/') {
+            let (num, den) = (parse_int(num)?, parse_int(den)?);
+            return Self::new(num, den).map_err(|_| format_error!(s, "denominator cannot be zero"));
+        }
+        else if let Some((whole, frac)) = s.split_once('.') {
+            let negative = whole.trim().starts_with('-');
+            let digits = frac.trim();
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return Err(format_error!(s, "'{}' is not a decimal fraction", frac));
+            }
+
+            let scale = 10i64.checked_pow(digits.len() as u32).ok_or_else(|| format_error!(s, "too many decimal places"))?;
+            let whole = if whole.trim() == "-" || whole.trim().is_empty() { 0 } else { parse_int(whole)? };
+            let frac = parse_int(digits)?;
+            let magnitude = (whole.unsigned_abs() as i128) * scale as i128 + frac as i128;
+            let num = if negative { -magnitude } else { magnitude };
+
+            return Self::reduce(num, scale as i128).ok_or_else(|| format_error!(s, "value is out of range"));
+        }
+
+        Ok( Self::from(parse_int(s)?) )
+    }
+}
+impl Default for Rational {
+    fn default() -> Self {
+        Self {
+            num: 0,
+            den: 1
+        }
+    }
+}
+impl VariableType for Rational {
+    fn required_units(&self) -> usize {
+        2usize
+    }
+}
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        match self.num.checked_neg() {
+            Some(num) => Self { num, den: self.den },
+            None => Self::nan()
+        }
+    }
+}
+impl Add for Rational {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs).ok_or_else(|| operator_error!('+', self, rhs))
+    }
+}
+impl Sub for Rational {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs).ok_or_else(|| operator_error!('-', self, rhs))
+    }
+}
+impl Mul for Rational {
+    type Output = Result<Self, Error>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(&rhs).ok_or_else(|| operator_error!('*', self, rhs))
+    }
+}
+impl Div for Rational {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(&rhs).ok_or_else(|| operator_error!('/', self, rhs))
+    }
+}
+impl AddAssign for Rational {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.checked_add(&rhs).unwrap_or_else(Self::nan);
+    }
+}
+impl AddAssign<f64> for Rational {
+    fn add_assign(&mut self, rhs: f64) {
+        *self += Self::from(rhs);
+    }
+}
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.checked_sub(&rhs).unwrap_or_else(Self::nan);
+    }
+}
+impl SubAssign<f64> for Rational {
+    fn sub_assign(&mut self, rhs: f64) {
+        *self -= Self::from(rhs);
+    }
+}
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.checked_mul(&rhs).unwrap_or_else(Self::nan);
+    }
+}
+impl MulAssign<f64> for Rational {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self *= Self::from(rhs);
+    }
+}
+impl DivAssign for Rational {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = self.checked_div(&rhs).unwrap_or_else(Self::nan);
+    }
+}
+impl DivAssign<f64> for Rational {
+    fn div_assign(&mut self, rhs: f64) {
+        *self /= Self::from(rhs);
+    }
+}
+impl SimpleNumerical for Rational {
+    fn abs(&self) -> f64 {
+        self.to_f64().abs()
+    }
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    a = a.abs();
+    b = b.abs();
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+impl Rational {
+    /// Builds `num / den` in lowest terms. Fails if `den` is zero.
+    pub fn new(num: i64, den: i64) -> Result<Self, Error> {
+        if den == 0 {
+            return Err(operator_error!('/', num, den));
+        }
+
+        //Reducing a pair of i64 values can only overflow for i64::MIN / -1.
+        Self::reduce(num as i128, den as i128).ok_or_else(|| operator_error!('/', num, den))
+    }
+    /// The undefined value, `0/0`.
+    pub fn nan() -> Self {
+        Self {
+            num: 0,
+            den: 0
+        }
+    }
+    /// Brings a fraction with a non-zero denominator into lowest terms, returning `None` if the result does not fit.
+    fn reduce(num: i128, den: i128) -> Option<Self> {
+        let divisor = gcd(num, den);
+        let (mut num, mut den) = (num / divisor, den / divisor);
+        if den < 0 {
+            num = -num;
+            den = -den;
+        }
+
+        Some(
+            Self {
+                num: i64::try_from(num).ok()?,
+                den: i64::try_from(den).ok()?
+            }
+        )
+    }
+    /// The best rational approximation of `value` with a denominator no larger than `max_den`.
+    pub fn approximate(value: f64, max_den: i64) -> Self {
+        if !value.is_finite() || value.abs() >= i64::MAX as f64 {
+            return Self::nan();
+        }
+        else if value.fract() == 0.0 {
+            return Self::from(value as i64);
+        }
+
+        //Binary fractions with a small enough denominator are represented exactly.
+        let mut scaled = value;
+        let mut den: i64 = 1;
+        while scaled.fract() != 0.0 && den <= max_den / 2 && scaled.abs() < (i64::MAX / 2) as f64 {
+            scaled *= 2.0;
+            den *= 2;
+        }
+        if scaled.fract() == 0.0 {
+            return Self::reduce(scaled as i128, den as i128).unwrap_or_else(Self::nan);
+        }
+
+        //Otherwise, walk the continued fraction expansion until the denominator would grow too large.
+        let (mut h0, mut h1): (i128, i128) = (0, 1);
+        let (mut k0, mut k1): (i128, i128) = (1, 0);
+        let mut y = value;
+        for _ in 0..64 {
+            let a = y.floor();
+            let (h2, k2) = (a as i128 * h1 + h0, a as i128 * k1 + k0);
+            if k2 > max_den as i128 || h2.abs() > i64::MAX as i128 {
+                break;
+            }
+            (h0, h1, k0, k1) = (h1, h2, k1, k2);
+
+            let frac = y - a;
+            if frac == 0.0 || ((h1 as f64 / k1 as f64) - value).abs() <= f64::EPSILON * value.abs() {
+                break;
+            }
+            y = 1.0 / frac;
+        }
+
+        Self::reduce(h1, k1).unwrap_or_else(Self::nan)
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.num
+    }
+    pub fn denominator(&self) -> i64 {
+        self.den
+    }
+    pub fn is_nan(&self) -> bool {
+        self.den == 0
+    }
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+    pub fn to_f64(&self) -> f64 {
+        if self.is_nan() {
+            f64::NAN
+        }
+        else {
+            self.num as f64 / self.den as f64
+        }
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        if self.is_nan() || rhs.is_nan() {
+            return None;
+        }
+
+        let num = self.num as i128 * rhs.den as i128 + rhs.num as i128 * self.den as i128;
+        Self::reduce(num, self.den as i128 * rhs.den as i128)
+    }
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        if self.is_nan() || rhs.is_nan() {
+            return None;
+        }
+
+        let num = self.num as i128 * rhs.den as i128 - rhs.num as i128 * self.den as i128;
+        Self::reduce(num, self.den as i128 * rhs.den as i128)
+    }
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        if self.is_nan() || rhs.is_nan() {
+            return None;
+        }
+
+        Self::reduce(self.num as i128 * rhs.num as i128, self.den as i128 * rhs.den as i128)
+    }
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if self.is_nan() || rhs.is_nan() || rhs.num == 0 {
+            return None;
+        }
+
+        Self::reduce(self.num as i128 * rhs.den as i128, self.den as i128 * rhs.num as i128)
+    }
+
+    pub fn recip(&self) -> Result<Self, Error> {
+        Self::from(1).div(*self)
+    }
+    /// Raises `self` to an integer power by repeated squaring, failing on overflow or when raising zero to a negative power.
+    pub fn pow(&self, exp: i32) -> Result<Self, Error> {
+        let mut base = if exp < 0 { self.recip()? } else { *self };
+        let mut exp_left = exp.unsigned_abs();
+        let mut result = Self::from(1);
+        while exp_left > 0 {
+            if exp_left & 1 == 1 {
+                result = result.checked_mul(&base).ok_or_else(|| operator_error!('^', self, exp))?;
+            }
+            exp_left >>= 1;
+            if exp_left > 0 {
+                base = base.checked_mul(&base).ok_or_else(|| operator_error!('^', self, exp))?;
+            }
+        }
+
+        Ok(result)
+    }
+    /// The largest integer no greater than `self`. Fails for `NaN`.
+    pub fn floor(&self) -> Result<i64, Error> {
+        if self.is_nan() {
+            return Err(operation_error!("floor", "NaN has no floor"));
+        }
+
+        Ok( self.num.div_euclid(self.den) )
+    }
+    /// The smallest integer no less than `self`. Fails for `NaN`.
+    pub fn ceil(&self) -> Result<i64, Error> {
+        if self.is_nan() {
+            return Err(operation_error!("ceil", "NaN has no ceiling"));
+        }
+
+        //The denominator is at least 2 whenever there is a remainder, so rounding up cannot overflow.
+        let floor = self.num.div_euclid(self.den);
+        Ok( if self.num.rem_euclid(self.den) == 0 { floor } else { floor + 1 } )
+    }
+}
+
+#[test]
+fn test_rational_arithmetic() {
+    let r = |n, d| Rational::new(n, d).unwrap();
+
+    assert_eq!(r(2, -4), r(-1, 2));
+    assert_eq!((r(1, 3) + r(1, 6)).unwrap(), r(1, 2));
+    assert_eq!((r(1, 3) - r(1, 2)).unwrap(), r(-1, 6));
+    assert_eq!((r(2, 3) * r(9, 4)).unwrap(), r(3, 2));
+    assert_eq!((r(2, 3) / r(4, 9)).unwrap(), r(3, 2));
+    assert!((r(1, 3) / Rational::default()).is_err());
+    assert!(Rational::new(1, 0).is_err());
+    assert!((Rational::from(i64::MAX) + Rational::from(1)).is_err());
+    assert_eq!(r(2, 3).pow(-2).unwrap(), r(9, 4));
+    assert_eq!(r(-1, 2).pow(-61).unwrap(), Rational::from(-1 << 61));
+    assert!(Rational::from(2).pow(63).is_err());
+    assert_eq!(Rational::from(-1).pow(i32::MIN).unwrap(), Rational::from(1));
+    assert_eq!(Rational::default().pow(i32::MAX).unwrap(), Rational::default());
+    assert!(r(1, 3) < r(1, 2));
+    assert_eq!((r(-7, 2).floor().unwrap(), r(-7, 2).ceil().unwrap()), (-4, -3));
+    assert_eq!(Rational::from(i64::MIN).ceil().unwrap(), i64::MIN);
+    assert!(Rational::nan().floor().is_err());
+    assert!(Rational::nan().ceil().is_err());
+
+    assert_eq!(format!("{}", r(1, 3)), "1/3");
+    assert_eq!(format!("{}", r(4, 2)), "2");
+    assert_eq!("-3/9".parse::<Rational>().unwrap(), r(-1, 3));
+    assert_eq!("-0.125".parse::<Rational>().unwrap(), r(-1, 8));
+    assert!("1/0".parse::<Rational>().is_err());
+
+    assert_eq!(Rational::from(0.375), r(3, 8));
+    assert_eq!(Rational::from(1.0 / 3.0), r(1, 3));
+    assert_eq!(Rational::approximate(std::f64::consts::PI, 1000), r(355, 113));
+
+    let mut x = r(1, 3);
+    x /= Rational::default();
+    assert!(x.is_nan());
+    assert_ne!(x, Rational::nan());
+    assert_ne!(x, x);
+
+    let units: Vec<BinaryUnit> = r(-5, 7).into();
+    assert_eq!(Rational::try_from(units).unwrap(), r(-5, 7));
+}
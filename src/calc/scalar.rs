@@ -23,14 +23,14 @@ impl PartialEq<f64> for Scalar {
         self.data == *other   
     }
 }
-impl Into<f64> for Scalar {
-    fn into(self) -> f64 {
-        self.data
+impl From<Scalar> for f64 {
+    fn from(value: Scalar) -> Self {
+        value.data
     }
 }
-impl Into<Vec<BinaryUnit>> for Scalar {
-    fn into(self) -> Vec<BinaryUnit> {
-        vec![ binary_unit!(self.data) ]
+impl From<Scalar> for Vec<BinaryUnit> {
+    fn from(value: Scalar) -> Self {
+        vec![ binary_unit!(value.data) ]
     }
 }
 impl From<f64> for Scalar {
@@ -44,7 +44,7 @@ impl TryFrom<Vec<BinaryUnit>> for Scalar {
     type Error = Error;
     fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
         match value.into_iter().next() {
-            None => return Err(conversion_error!("expected at least one unit, but got none")),
+            None => Err(conversion_error!("expected at least one unit, but got none")),
             Some(d) => {
                 let as_float: f64 = d.try_into()?;
                 Ok(Self::from(as_float))
@@ -116,6 +116,16 @@ impl Mul<f64> for Scalar {
         result
     }
 }
+impl MulAssign for Scalar {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.data *= rhs.data;
+    }
+}
+impl MulAssign<f64> for Scalar {
+    fn mul_assign(&mut self, rhs: f64) {
+        self.data *= rhs;
+    }
+}
 impl Mul<Complex> for Scalar {
     type Output = Complex;
     fn mul(self, rhs: Complex) -> Self::Output {
@@ -145,11 +155,23 @@ impl Div<f64> for Scalar {
 impl Div<Complex> for Scalar {
     type Output = Complex;
     fn div(self, rhs: Complex) -> Self::Output {
-        rhs.div(self.data)
+        Complex::new(self.data, 0.0).div(rhs)
+    }
+}
+impl DivAssign for Scalar {
+    fn div_assign(&mut self, rhs: Self) {
+        self.data /= rhs.data;
+    }
+}
+impl DivAssign<f64> for Scalar {
+    fn div_assign(&mut self, rhs: f64) {
+        self.data /= rhs;
     }
 }
 impl SimpleNumerical for Scalar {
-
+    fn abs(&self) -> f64 {
+        self.data.abs()
+    }
 }
 impl Scalar {
 
@@ -0,0 +1,525 @@
+use std::fmt::{Display, Debug};
+use std::ops::Mul;
+
+use crate::{argument_error, binary_unit, conversion_error, operation_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, scalar::Scalar, matrix::Matrix, vector::MVector};
+
+/// A sparse matrix stored in compressed sparse row (CSR) form. Only the non-zero entries are kept, so very large systems (such as finite difference grids) fit in memory.
+#[derive(Clone, PartialEq)]
+pub struct SparseMatrix {
+    rows: usize,
+    cols: usize,
+    row_ptr: Vec<usize>, //Row i occupies col_idx[row_ptr[i]..row_ptr[i + 1]], len = rows + 1
+    col_idx: Vec<usize>,
+    values: Vec<f64>
+}
+impl Default for SparseMatrix {
+    fn default() -> Self {
+        Self {
+            rows: 0,
+            cols: 0,
+            row_ptr: vec![0],
+            col_idx: vec![],
+            values: vec![]
+        }
+    }
+}
+impl Debug for SparseMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(SparseMatrix:{}x{} nnz={})", self.rows, self.cols, self.nnz())
+    }
+}
+impl Display for SparseMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{} sparse matrix with {} non-zero entries", self.rows, self.cols, self.nnz())?;
+        for (row, col, value) in self.iter() {
+            write!(f, "\n({}, {}) = {}", row, col, value)?;
+        }
+
+        Ok(())
+    }
+}
+impl From<SparseMatrix> for Vec<BinaryUnit> {
+    fn from(value: SparseMatrix) -> Self {
+        let mut result = vec![binary_unit!(value.rows), binary_unit!(value.cols), binary_unit!(value.nnz())];
+        for ptr in value.row_ptr {
+            result.push(binary_unit!(ptr));
+        }
+        for (col, item) in value.col_idx.into_iter().zip(value.values) {
+            result.push(binary_unit!(col));
+            result.push(binary_unit!(item));
+        }
+
+        result
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for SparseMatrix {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        if value.len() < 3 {
+            return Err(conversion_error!("cannot construct SparseMatrix from {} element(s)", value.len()));
+        }
+
+        let mut iter = value.into_iter();
+        let rows: usize = iter.next().unwrap().try_into()?; //We can unwrap because there are at least three elements
+        let cols: usize = iter.next().unwrap().try_into()?;
+        let nnz: usize = iter.next().unwrap().try_into()?;
+
+        let items: Vec<BinaryUnit> = iter.collect();
+        let expected = nnz.checked_mul(2).and_then(|n| n.checked_add(rows)).and_then(|n| n.checked_add(1));
+        if expected != Some(items.len()) {
+            return Err(conversion_error!("expected rows + 1 + 2 * nnz units for a sparse matrix with {} rows and {} entries, got {}", rows, nnz, items.len()));
+        }
+
+        let mut iter = items.into_iter();
+        let mut row_ptr: Vec<usize> = Vec::with_capacity(rows + 1);
+        for unit in iter.by_ref().take(rows + 1) {
+            row_ptr.push(unit.try_into()?);
+        }
+        let mut col_idx: Vec<usize> = Vec::with_capacity(nnz);
+        let mut values: Vec<f64> = Vec::with_capacity(nnz);
+        while let (Some(col), Some(item)) = (iter.next(), iter.next()) {
+            col_idx.push(col.try_into()?);
+            values.push(item.try_into()?);
+        }
+
+        //Lookups binary search each row, so the columns within a row must be strictly increasing.
+        let valid = row_ptr.first() == Some(&0) && row_ptr.last() == Some(&nnz) && row_ptr.windows(2).all(|w| w[0] <= w[1]) && col_idx.iter().all(|c| *c < cols)
+            && row_ptr.windows(2).all(|w| col_idx[w[0]..w[1]].windows(2).all(|c| c[0] < c[1]));
+        if !valid {
+            return Err(conversion_error!("the stored sparse structure is inconsistent"));
+        }
+
+        Ok(
+            Self {
+                rows,
+                cols,
+                row_ptr,
+                col_idx,
+                values
+            }
+        )
+    }
+}
+impl VariableType for SparseMatrix {
+    fn required_units(&self) -> usize {
+        3 + (self.rows + 1) + 2 * self.nnz()
+    }
+}
+impl From<&Matrix> for SparseMatrix {
+    fn from(value: &Matrix) -> Self {
+        let mut triplets: Vec<(usize, usize, f64)> = vec![];
+        for i in 0..value.rows() {
+            for j in 0..value.cols() {
+                if value[(i, j)] != 0.0 {
+                    triplets.push((i, j, value[(i, j)]));
+                }
+            }
+        }
+
+        //The triplets come from a matrix of the right size, so they are always in range.
+        Self::from_triplets(value.rows(), value.cols(), triplets).unwrap_or_default()
+    }
+}
+impl From<Matrix> for SparseMatrix {
+    fn from(value: Matrix) -> Self {
+        Self::from(&value)
+    }
+}
+impl Mul<MVector<Scalar>> for SparseMatrix {
+    type Output = Result<MVector<Scalar>, Error>;
+    fn mul(self, rhs: MVector<Scalar>) -> Self::Output {
+        (&self).mul(&rhs)
+    }
+}
+impl Mul<&MVector<Scalar>> for &SparseMatrix {
+    type Output = Result<MVector<Scalar>, Error>;
+    fn mul(self, rhs: &MVector<Scalar>) -> Self::Output {
+        if self.is_error() || rhs.dim() != self.cols {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let x: Vec<f64> = rhs.iter().map(|s| f64::from(s.clone())).collect();
+        Ok( MVector::from(self.apply(&x).into_iter().map(Scalar::from).collect::<Vec<_>>()) )
+    }
+}
+impl Mul<f64> for SparseMatrix {
+    type Output = Result<SparseMatrix, Error>;
+    fn mul(self, rhs: f64) -> Self::Output {
+        if self.is_error() {
+            return Err(operator_error!('*', self, rhs));
+        }
+
+        let mut result = self;
+        for item in result.values.iter_mut() {
+            *item *= rhs;
+        }
+
+        Ok(result)
+    }
+}
+
+/// The outcome of an iterative solver: the solution, how many iterations it took, and the final residual norm `||b - Ax||`.
+#[derive(Clone, Debug)]
+pub struct IterativeSolution {
+    solution: MVector<Scalar>,
+    iterations: usize,
+    residual: f64
+}
+impl IterativeSolution {
+    pub fn solution(&self) -> &MVector<Scalar> {
+        &self.solution
+    }
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+    pub fn residual(&self) -> f64 {
+        self.residual
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+impl SparseMatrix {
+    /// Builds a matrix out of `(row, col, value)` entries, in any order. Entries at the same position are summed, and zeros are dropped.
+    pub fn from_triplets(rows: usize, cols: usize, mut triplets: Vec<(usize, usize, f64)>) -> Result<Self, Error> {
+        if let Some((r, c, _)) = triplets.iter().find(|(r, c, _)| *r >= rows || *c >= cols) {
+            return Err(argument_error!("triplets", "entry ({}, {}) is outside of a {}x{} matrix", r, c, rows, cols));
+        }
+        triplets.sort_by_key(|(r, c, _)| (*r, *c));
+
+        let mut row_ptr = vec![0usize; rows + 1];
+        let mut col_idx: Vec<usize> = Vec::with_capacity(triplets.len());
+        let mut values: Vec<f64> = Vec::with_capacity(triplets.len());
+        let mut last: Option<(usize, usize)> = None;
+        for (r, c, v) in triplets {
+            if last == Some((r, c)) {
+                *values.last_mut().unwrap() += v; //`last` is only set once something has been pushed
+                continue;
+            }
+
+            col_idx.push(c);
+            values.push(v);
+            row_ptr[r + 1] += 1;
+            last = Some((r, c));
+        }
+        for i in 0..rows {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+
+        let mut result = Self {
+            rows,
+            cols,
+            row_ptr,
+            col_idx,
+            values
+        };
+        result.prune();
+
+        Ok(result)
+    }
+    pub fn identity(n: usize) -> Self {
+        Self {
+            rows: n,
+            cols: n,
+            row_ptr: (0..=n).collect(),
+            col_idx: (0..n).collect(),
+            values: vec![1.0; n]
+        }
+    }
+    /// Removes explicitly stored zeros.
+    fn prune(&mut self) {
+        let mut write = 0;
+        let mut start = 0;
+        for i in 0..self.rows {
+            let end = self.row_ptr[i + 1];
+            for k in start..end {
+                if self.values[k] != 0.0 {
+                    self.col_idx[write] = self.col_idx[k];
+                    self.values[write] = self.values[k];
+                    write += 1;
+                }
+            }
+            start = end;
+            self.row_ptr[i + 1] = write;
+        }
+        self.col_idx.truncate(write);
+        self.values.truncate(write);
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+    /// The number of stored (non-zero) entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_error(&self) -> bool {
+        self.rows == 0 || self.cols == 0
+    }
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+    pub fn get(&self, row: usize, col: usize) -> Option<f64> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+
+        let range = self.row_ptr[row]..self.row_ptr[row + 1];
+        match self.col_idx[range.clone()].binary_search(&col) {
+            Ok(k) => Some(self.values[range.start + k]),
+            Err(_) => Some(0.0)
+        }
+    }
+    /// Iterates over the stored entries as `(row, col, value)`, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.rows).flat_map(move |i| (self.row_ptr[i]..self.row_ptr[i + 1]).map(move |k| (i, self.col_idx[k], self.values[k])))
+    }
+
+    pub fn to_dense(&self) -> Matrix {
+        let mut result = Matrix::zeros(self.rows, self.cols);
+        for (i, j, v) in self.iter() {
+            result[(i, j)] = v;
+        }
+
+        result
+    }
+    pub fn transpose(&self) -> Self {
+        let triplets = self.iter().map(|(i, j, v)| (j, i, v)).collect();
+        Self::from_triplets(self.cols, self.rows, triplets).unwrap_or_default()
+    }
+
+    /// Computes `Ax` for a slice with `cols` entries.
+    fn apply(&self, x: &[f64]) -> Vec<f64> {
+        (0..self.rows).map(|i| {
+            (self.row_ptr[i]..self.row_ptr[i + 1]).map(|k| self.values[k] * x[self.col_idx[k]]).sum()
+        }).collect()
+    }
+    fn check_system(&self, b: &MVector<Scalar>, action: &str) -> Result<Vec<f64>, Error> {
+        if self.is_error() {
+            return Err(operation_error!(action, "no data loaded (error state)"));
+        }
+        else if !self.is_square() {
+            return Err(operation_error!(action, "matrix must be square, got {}x{}", self.rows, self.cols));
+        }
+        else if b.dim() != self.rows {
+            return Err(operation_error!(action, "right hand side has dim = {}, expected dim = {}", b.dim(), self.rows));
+        }
+
+        Ok( b.iter().map(|s| f64::from(s.clone())).collect() )
+    }
+    fn finish(x: Vec<f64>, iterations: usize, residual: f64) -> IterativeSolution {
+        IterativeSolution {
+            solution: MVector::from(x.into_iter().map(Scalar::from).collect::<Vec<_>>()),
+            iterations,
+            residual
+        }
+    }
+
+    /// Solves `Ax = b` with the conjugate gradient method, which requires `A` to be symmetric positive definite.
+    /// Iteration stops once `||b - Ax|| <= tol * ||b||`, and fails if that has not happened within `max_iter` iterations.
+    pub fn conjugate_gradient(&self, b: &MVector<Scalar>, tol: f64, max_iter: usize) -> Result<IterativeSolution, Error> {
+        let b = self.check_system(b, "conjugate gradient")?;
+        let n = self.rows;
+        let target = tol * norm(&b);
+
+        let mut x = vec![0.0; n];
+        let mut r = b.clone();
+        let mut p = r.clone();
+        let mut rs = dot(&r, &r);
+        if rs.sqrt() <= target {
+            return Ok( Self::finish(x, 0, rs.sqrt()) );
+        }
+
+        for iteration in 1..=max_iter {
+            let ap = self.apply(&p);
+            let curvature = dot(&p, &ap);
+            if curvature <= 0.0 {
+                return Err(operation_error!("conjugate gradient", "matrix is not positive definite"));
+            }
+
+            let alpha = rs / curvature;
+            for i in 0..n {
+                x[i] += alpha * p[i];
+                r[i] -= alpha * ap[i];
+            }
+
+            let rs_new = dot(&r, &r);
+            if rs_new.sqrt() <= target {
+                return Ok( Self::finish(x, iteration, rs_new.sqrt()) );
+            }
+
+            let beta = rs_new / rs;
+            for (pi, ri) in p.iter_mut().zip(r.iter()) {
+                *pi = ri + beta * *pi;
+            }
+            rs = rs_new;
+        }
+
+        Err(operation_error!("conjugate gradient", "failed to converge in {} iterations (residual = {})", max_iter, rs.sqrt()))
+    }
+    /// Solves `Ax = b` with the restarted GMRES method, which works for any non-singular `A`.
+    /// The Krylov basis is rebuilt every `restart` iterations. Iteration stops once `||b - Ax|| <= tol * ||b||`, and fails if that has not happened within `max_iter` iterations.
+    pub fn gmres(&self, b: &MVector<Scalar>, tol: f64, max_iter: usize, restart: usize) -> Result<IterativeSolution, Error> {
+        let b = self.check_system(b, "GMRES")?;
+        if restart == 0 {
+            return Err(argument_error!("restart", restart));
+        }
+
+        let n = self.rows;
+        let target = tol * norm(&b);
+        let mut x = vec![0.0; n];
+        let mut iterations = 0usize;
+
+        loop {
+            let ax = self.apply(&x);
+            let r: Vec<f64> = b.iter().zip(ax.iter()).map(|(bi, axi)| bi - axi).collect();
+            let beta = norm(&r);
+            if beta <= target {
+                return Ok( Self::finish(x, iterations, beta) );
+            }
+            else if iterations >= max_iter {
+                return Err(operation_error!("GMRES", "failed to converge in {} iterations (residual = {})", max_iter, beta));
+            }
+
+            //Arnoldi process, with the least squares problem kept upper triangular by Givens rotations.
+            let mut basis: Vec<Vec<f64>> = vec![r.into_iter().map(|v| v / beta).collect()];
+            let mut h = vec![vec![0.0; restart]; restart + 1];
+            let (mut cs, mut sn) = (vec![0.0; restart], vec![0.0; restart]);
+            let mut g = vec![0.0; restart + 1];
+            g[0] = beta;
+
+            let mut k = 0;
+            while k < restart && iterations < max_iter {
+                let mut w = self.apply(&basis[k]);
+                for (i, v) in basis.iter().enumerate() {
+                    h[i][k] = dot(&w, v);
+                    for (wj, vj) in w.iter_mut().zip(v.iter()) {
+                        *wj -= h[i][k] * vj;
+                    }
+                }
+                let w_norm = norm(&w);
+                h[k + 1][k] = w_norm;
+
+                for i in 0..k {
+                    let temp = cs[i] * h[i][k] + sn[i] * h[i + 1][k];
+                    h[i + 1][k] = -sn[i] * h[i][k] + cs[i] * h[i + 1][k];
+                    h[i][k] = temp;
+                }
+                let denom = h[k][k].hypot(h[k + 1][k]);
+                if denom == 0.0 {
+                    return Err(operation_error!("GMRES", "matrix is singular"));
+                }
+                cs[k] = h[k][k] / denom;
+                sn[k] = h[k + 1][k] / denom;
+                h[k][k] = denom;
+                h[k + 1][k] = 0.0;
+                g[k + 1] = -sn[k] * g[k];
+                g[k] *= cs[k];
+
+                k += 1;
+                iterations += 1;
+                //A zero `w` means the Krylov space is invariant, so the solution within it is exact.
+                if g[k].abs() <= target || w_norm == 0.0 {
+                    break;
+                }
+                basis.push(w.into_iter().map(|v| v / w_norm).collect());
+            }
+
+            let mut y = vec![0.0; k];
+            for i in (0..k).rev() {
+                let sum: f64 = ((i + 1)..k).map(|j| h[i][j] * y[j]).sum();
+                y[i] = (g[i] - sum) / h[i][i];
+            }
+            for (yi, v) in y.iter().zip(basis.iter()) {
+                for (xj, vj) in x.iter_mut().zip(v.iter()) {
+                    *xj += yi * vj;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sparse_matrix() {
+    let dense = Matrix::from_rows(vec![vec![4.0, 0.0, 1.0], vec![0.0, 0.0, 0.0], vec![2.0, 0.0, 3.0]]).unwrap();
+    let sparse = SparseMatrix::from(&dense);
+    assert_eq!(sparse.nnz(), 4);
+    assert_eq!(sparse.get(2, 0), Some(2.0));
+    assert_eq!(sparse.get(1, 1), Some(0.0));
+    assert_eq!(sparse.to_dense(), dense);
+    assert_eq!(sparse.transpose().to_dense(), dense.transpose());
+
+    let x = MVector::from(vec![Scalar::from(1.0), Scalar::from(2.0), Scalar::from(3.0)]);
+    assert_eq!((&sparse * &x).unwrap(), (dense.clone() * x.clone()).unwrap());
+    assert!((sparse.clone() * MVector::from(vec![Scalar::from(1.0)])).is_err());
+
+    let summed = SparseMatrix::from_triplets(2, 2, vec![(1, 1, 2.0), (0, 0, 1.0), (1, 1, 3.0), (0, 1, 0.0)]).unwrap();
+    assert_eq!(summed.to_dense(), Matrix::from_rows(vec![vec![1.0, 0.0], vec![0.0, 5.0]]).unwrap());
+    assert!(SparseMatrix::from_triplets(2, 2, vec![(2, 0, 1.0)]).is_err());
+
+    let units: Vec<BinaryUnit> = sparse.clone().into();
+    assert_eq!(SparseMatrix::try_from(units.clone()).unwrap(), sparse);
+    let mut unsorted = units.clone();
+    unsorted.swap(7, 9);
+    assert!(SparseMatrix::try_from(unsorted).is_err());
+    let mut repeated = units;
+    repeated[9] = binary_unit!(0usize);
+    assert!(SparseMatrix::try_from(repeated).is_err());
+    assert!(SparseMatrix::try_from(vec![binary_unit!(usize::MAX), binary_unit!(1usize), binary_unit!(usize::MAX / 2)]).is_err());
+}
+
+#[test]
+fn test_sparse_solvers() {
+    //1D Poisson problem: tridiagonal (-1, 2, -1), which is symmetric positive definite.
+    let n = 200;
+    let mut triplets = vec![];
+    for i in 0..n {
+        triplets.push((i, i, 2.0));
+        if i > 0 {
+            triplets.push((i, i - 1, -1.0));
+        }
+        if i + 1 < n {
+            triplets.push((i, i + 1, -1.0));
+        }
+    }
+    let poisson = SparseMatrix::from_triplets(n, n, triplets).unwrap();
+    let b = MVector::from(vec![Scalar::from(1.0); n]);
+
+    let check = |a: &SparseMatrix, result: &IterativeSolution| {
+        let ax = (a * result.solution()).unwrap();
+        let error = ax.sub_with(b.clone(), super::vector::DimensionPolicy::Strict).unwrap().magnitude().unwrap();
+        assert!(error <= 1e-8 * b.magnitude().unwrap(), "residual {} too large", error);
+    };
+
+    let cg = poisson.conjugate_gradient(&b, 1e-10, 1000).unwrap();
+    check(&poisson, &cg);
+    assert!(cg.iterations() <= n);
+    assert!(poisson.conjugate_gradient(&b, 1e-10, 3).is_err());
+
+    let gmres = poisson.gmres(&b, 1e-10, 2000, n).unwrap();
+    check(&poisson, &gmres);
+
+    //Convection-diffusion: tridiagonal (-1.5, 2, -0.5), which is not symmetric, so only GMRES applies.
+    let mut triplets = vec![];
+    for i in 0..n {
+        triplets.push((i, i, 2.0));
+        if i > 0 {
+            triplets.push((i, i - 1, -1.5));
+        }
+        if i + 1 < n {
+            triplets.push((i, i + 1, -0.5));
+        }
+    }
+    let convection = SparseMatrix::from_triplets(n, n, triplets).unwrap();
+    let gmres = convection.gmres(&b, 1e-10, 5000, 30).unwrap();
+    check(&convection, &gmres);
+}
@@ -0,0 +1,515 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Mul, Div, Neg, Range};
+use std::sync::Arc;
+
+use crate::{argument_error, binary_unit, conversion_error, operation_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, scalar::Scalar, vector::MVector, matrix::Matrix};
+
+/// An N-dimensional array of `f64`, stored as a flat buffer with a shape and per-axis strides.
+/// Transposes, slices and broadcasts only change the strides, and share the buffer with the tensor they came from until one of them is written to. Element-wise results are always laid out contiguously in row-major order.
+#[derive(Clone)]
+pub struct Tensor {
+    data: Arc<[f64]>,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    offset: usize
+}
+impl Debug for Tensor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let shape: Vec<String> = self.shape.iter().map(|d| d.to_string()).collect();
+        write!(f, "(Tensor:{} {})", shape.join("x"), self)
+    }
+}
+impl Display for Tensor {
+    /// Nested brackets, one level per axis, such as `[[1, 2], [3, 4]]`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_axis(f: &mut std::fmt::Formatter<'_>, tensor: &Tensor, axis: usize, offset: usize) -> std::fmt::Result {
+            if axis == tensor.rank() {
+                return write!(f, "{}", tensor.data[offset]);
+            }
+
+            write!(f, "[")?;
+            for i in 0..tensor.shape[axis] {
+                if i != 0 {
+                    write!(f, ", ")?;
+                }
+                write_axis(f, tensor, axis + 1, offset + i * tensor.strides[axis])?;
+            }
+            write!(f, "]")
+        }
+
+        if self.is_empty() {
+            return write!(f, "[]");
+        }
+        write_axis(f, self, 0, self.offset)
+    }
+}
+impl PartialEq for Tensor {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && self.values().eq(other.values())
+    }
+}
+impl Default for Tensor {
+    fn default() -> Self {
+        Self::scalar(0.0)
+    }
+}
+impl From<MVector<Scalar>> for Tensor {
+    fn from(value: MVector<Scalar>) -> Self {
+        let data: Vec<f64> = value.iter().map(|s| f64::from(s.clone())).collect();
+        let len = data.len();
+        Self::contiguous(data, vec![len])
+    }
+}
+impl From<Matrix> for Tensor {
+    fn from(value: Matrix) -> Self {
+        let (rows, cols) = (value.rows(), value.cols());
+        let data: Vec<f64> = (0..rows).flat_map(|i| value.row(i).unwrap_or(&[]).to_vec()).collect();
+        Self::contiguous(data, vec![rows, cols])
+    }
+}
+impl TryFrom<Tensor> for Matrix {
+    type Error = Error;
+    fn try_from(value: Tensor) -> Result<Self, Self::Error> {
+        if value.rank() != 2 {
+            return Err(conversion_error!("only a rank 2 tensor can become a matrix, got rank {}", value.rank()));
+        }
+
+        let cols = value.shape[1];
+        let values: Vec<f64> = value.values().collect();
+        Matrix::from_rows(values.chunks(cols.max(1)).map(|row| row.to_vec()).collect())
+    }
+}
+impl From<Tensor> for Vec<BinaryUnit> {
+    /// Encoded as the rank, each dimension, and then the elements in row-major order.
+    fn from(value: Tensor) -> Self {
+        let mut result = vec![ binary_unit!(value.rank()) ];
+        result.extend(value.shape.iter().map(|d| binary_unit!(*d)));
+        result.extend(value.values().map(|x| binary_unit!(x)));
+
+        result
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Tensor {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        let mut iter = value.into_iter();
+        let mut next = || iter.next().ok_or_else(|| conversion_error!("ran out of units while decoding a tensor"));
+
+        let rank: usize = next()?.try_into()?;
+        let shape = (0..rank).map(|_| next()?.try_into()).collect::<Result<Vec<usize>, Error>>()?;
+        let len = Self::checked_len(&shape).ok_or_else(|| conversion_error!("the shape {:?} holds too many elements", shape))?;
+        let data = (0..len).map(|_| next()?.try_into()).collect::<Result<Vec<f64>, Error>>()?;
+
+        Ok( Self::contiguous(data, shape) )
+    }
+}
+impl VariableType for Tensor {
+    fn required_units(&self) -> usize {
+        1 + self.rank() + self.len()
+    }
+}
+impl Add for Tensor {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, '+', |a, b| a + b)
+    }
+}
+impl Sub for Tensor {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, '-', |a, b| a - b)
+    }
+}
+impl Mul for Tensor {
+    type Output = Result<Self, Error>;
+    /// The element-wise (Hadamard) product.
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, '*', |a, b| a * b)
+    }
+}
+impl Div for Tensor {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, '/', |a, b| a / b)
+    }
+}
+impl Mul<f64> for Tensor {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self::Output {
+        self.map(|x| x * rhs)
+    }
+}
+impl Div<f64> for Tensor {
+    type Output = Self;
+    fn div(self, rhs: f64) -> Self::Output {
+        self.map(|x| x / rhs)
+    }
+}
+impl Neg for Tensor {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        self.map(|x| -x)
+    }
+}
+
+impl Tensor {
+    fn row_major_strides(shape: &[usize]) -> Vec<usize> {
+        let mut strides = vec![1; shape.len()];
+        for i in (0..shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1];
+        }
+
+        strides
+    }
+    /// The number of elements in `shape`, or `None` if it does not fit in a `usize`.
+    fn checked_len(shape: &[usize]) -> Option<usize> {
+        shape.iter().try_fold(1usize, |len, d| len.checked_mul(*d))
+    }
+    fn contiguous(data: Vec<f64>, shape: Vec<usize>) -> Self {
+        Self {
+            data: data.into(),
+            strides: Self::row_major_strides(&shape),
+            shape,
+            offset: 0
+        }
+    }
+
+    /// Lays out `data` in row-major order with the given `shape`.
+    pub fn new(data: Vec<f64>, shape: Vec<usize>) -> Result<Self, Error> {
+        let len = Self::checked_len(&shape).ok_or_else(|| argument_error!("shape", "{:?} holds too many elements", shape))?;
+        if len != data.len() {
+            return Err(argument_error!("shape", "{:?} holds {} elements, but {} were given", shape, len, data.len()));
+        }
+
+        Ok( Self::contiguous(data, shape) )
+    }
+    pub fn filled(shape: Vec<usize>, value: f64) -> Result<Self, Error> {
+        let len = Self::checked_len(&shape).ok_or_else(|| argument_error!("shape", "{:?} holds too many elements", shape))?;
+        Ok( Self::contiguous(vec![value; len], shape) )
+    }
+    pub fn zeros(shape: Vec<usize>) -> Result<Self, Error> {
+        Self::filled(shape, 0.0)
+    }
+    /// A rank 0 tensor holding one value.
+    pub fn scalar(value: f64) -> Self {
+        Self::contiguous(vec![value], vec![])
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+    pub fn rank(&self) -> usize {
+        self.shape.len()
+    }
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn is_contiguous(&self) -> bool {
+        self.offset == 0 && self.data.len() == self.len() && self.strides == Self::row_major_strides(&self.shape)
+    }
+
+    pub fn get(&self, index: &[usize]) -> Option<f64> {
+        if index.len() != self.rank() || index.iter().zip(self.shape.iter()).any(|(i, d)| i >= d) {
+            return None;
+        }
+
+        Some( self.data[self.offset + index.iter().zip(self.strides.iter()).map(|(i, s)| i * s).sum::<usize>()] )
+    }
+    /// Writes one element. A view is first copied into a buffer of its own, so the tensors it shares with are unchanged.
+    pub fn set(&mut self, index: &[usize], value: f64) -> Result<(), Error> {
+        if self.get(index).is_none() {
+            return Err(argument_error!("index", "{:?} is out of bounds for shape {:?}", index, self.shape));
+        }
+        if !self.is_contiguous() {
+            *self = self.to_contiguous();
+        }
+
+        let position = index.iter().zip(self.strides.iter()).map(|(i, s)| i * s).sum::<usize>();
+        Arc::make_mut(&mut self.data)[position] = value;
+        Ok(())
+    }
+    /// Whether `self` and `other` read from the same buffer.
+    pub fn shares_data(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+
+    /// The elements in row-major order, following the strides.
+    pub fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        let len = self.len();
+        let mut index = vec![0usize; self.rank()];
+        let mut position = self.offset;
+        (0..len).map(move |n| {
+            if n != 0 {
+                //Odometer step: bump the last axis, carrying into earlier ones.
+                for axis in (0..index.len()).rev() {
+                    index[axis] += 1;
+                    position += self.strides[axis];
+                    if index[axis] < self.shape[axis] {
+                        break;
+                    }
+                    position -= self.strides[axis] * index[axis];
+                    index[axis] = 0;
+                }
+            }
+            self.data[position]
+        })
+    }
+    /// A copy laid out in row-major order, with no offset.
+    pub fn to_contiguous(&self) -> Self {
+        Self::contiguous(self.values().collect(), self.shape.clone())
+    }
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+        Self::contiguous(self.values().map(f).collect(), self.shape.clone())
+    }
+
+    /// The shape that `a` and `b` broadcast to, following NumPy: shapes are aligned from the last axis, and each pair of dimensions must match or contain a 1.
+    pub fn broadcast_shape(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+        let rank = a.len().max(b.len());
+        let dim = |s: &[usize], i: usize| if i + s.len() < rank { 1 } else { s[i + s.len() - rank] };
+        (0..rank).map(|i| match (dim(a, i), dim(b, i)) {
+            (x, y) if x == y => Some(x),
+            (1, y) => Some(y),
+            (x, 1) => Some(x),
+            _ => None
+        }).collect()
+    }
+    /// A view with the given shape, repeating along new and length 1 axes without copying.
+    pub fn broadcast_to(&self, shape: &[usize]) -> Result<Self, Error> {
+        if shape.len() < self.rank() {
+            return Err(operation_error!("broadcast", "cannot broadcast shape {:?} to the lower rank shape {:?}", self.shape, shape));
+        }
+        if Self::checked_len(shape).is_none() {
+            return Err(operation_error!("broadcast", "the shape {:?} holds too many elements", shape));
+        }
+
+        let extra = shape.len() - self.rank();
+        let mut strides = vec![0usize; shape.len()];
+        for (i, target) in shape.iter().enumerate().skip(extra) {
+            let source = self.shape[i - extra];
+            if source == *target {
+                strides[i] = self.strides[i - extra];
+            }
+            else if source != 1 {
+                return Err(operation_error!("broadcast", "cannot broadcast shape {:?} to {:?}", self.shape, shape));
+            }
+        }
+
+        Ok( Self {
+            data: self.data.clone(),
+            shape: shape.to_vec(),
+            strides,
+            offset: self.offset
+        })
+    }
+    fn zip_with<F: Fn(f64, f64) -> f64>(&self, rhs: &Self, op: char, f: F) -> Result<Self, Error> {
+        let shape = Self::broadcast_shape(&self.shape, &rhs.shape).ok_or_else(|| operator_error!(op, self, rhs))?;
+        let (a, b) = (self.broadcast_to(&shape)?, rhs.broadcast_to(&shape)?);
+        let data = a.values().zip(b.values()).map(|(x, y)| f(x, y)).collect();
+
+        Ok( Self::contiguous(data, shape) )
+    }
+
+    /// The same elements, read in row-major order, with a new shape of the same size.
+    pub fn reshape(&self, shape: Vec<usize>) -> Result<Self, Error> {
+        if Self::checked_len(&shape) != Some(self.len()) {
+            return Err(operation_error!("reshape", "cannot reshape {:?} ({} elements) into {:?}", self.shape, self.len(), shape));
+        }
+
+        Ok( Self::contiguous(self.values().collect(), shape) )
+    }
+    /// Reorders the axes, so that axis `i` of the result is axis `axes[i]` of `self`.
+    pub fn permute(&self, axes: &[usize]) -> Result<Self, Error> {
+        let mut seen = vec![false; self.rank()];
+        if axes.len() != self.rank() || axes.iter().any(|a| *a >= self.rank() || std::mem::replace(&mut seen[*a], true)) {
+            return Err(argument_error!("axes", "{:?} is not a permutation of the {} axes", axes, self.rank()));
+        }
+
+        let mut result = self.clone();
+        result.shape = axes.iter().map(|a| self.shape[*a]).collect();
+        result.strides = axes.iter().map(|a| self.strides[*a]).collect();
+        Ok(result)
+    }
+    /// Reverses the order of the axes.
+    pub fn transpose(&self) -> Self {
+        let mut result = self.clone();
+        result.shape.reverse();
+        result.strides.reverse();
+        result
+    }
+
+    fn check_axis(&self, axis: usize) -> Result<(), Error> {
+        if axis >= self.rank() {
+            return Err(argument_error!("axis", "{} is out of range for a rank {} tensor", axis, self.rank()));
+        }
+
+        Ok(())
+    }
+    /// Takes every `step`th element from `start` up to, but not including, `end` along `axis`. `end` is clamped to the length of the axis.
+    pub fn slice_axis(&self, axis: usize, start: usize, end: usize, step: usize) -> Result<Self, Error> {
+        self.check_axis(axis)?;
+        let end = end.min(self.shape[axis]);
+        if step == 0 {
+            return Err(argument_error!("step", "must be at least 1"));
+        }
+        if start > end {
+            return Err(argument_error!("start", "{} is past the end {} of axis {}", start, end, axis));
+        }
+
+        let mut result = self.clone();
+        result.offset += start * self.strides[axis];
+        result.shape[axis] = (end - start).div_ceil(step);
+        //With at most one element left the stride is never used, so a huge step must not overflow it.
+        if result.shape[axis] > 1 {
+            result.strides[axis] = self.strides[axis].checked_mul(step).ok_or_else(|| argument_error!("step", "{} is too large for axis {}", step, axis))?;
+        }
+        Ok(result)
+    }
+    /// Slices the leading axes by the given ranges, leaving the rest whole, so `t.slice(&[0..2, 1..3])` is `t[0:2, 1:3]`.
+    pub fn slice(&self, ranges: &[Range<usize>]) -> Result<Self, Error> {
+        if ranges.len() > self.rank() {
+            return Err(argument_error!("ranges", "{} ranges given for a rank {} tensor", ranges.len(), self.rank()));
+        }
+
+        let mut result = self.clone();
+        for (axis, range) in ranges.iter().enumerate() {
+            result = result.slice_axis(axis, range.start, range.end, 1)?;
+        }
+        Ok(result)
+    }
+    /// Fixes `axis` at `index`, removing that axis. For a stack of images, `index_axis(0, i)` is the `i`th image.
+    pub fn index_axis(&self, axis: usize, index: usize) -> Result<Self, Error> {
+        self.check_axis(axis)?;
+        if index >= self.shape[axis] {
+            return Err(argument_error!("index", "{} is out of range for axis {} of length {}", index, axis, self.shape[axis]));
+        }
+
+        let mut result = self.clone();
+        result.offset += index * self.strides[axis];
+        result.shape.remove(axis);
+        result.strides.remove(axis);
+        Ok(result)
+    }
+
+    fn reduce_axis<F: Fn(&[f64]) -> f64>(&self, axis: usize, f: F) -> Result<Self, Error> {
+        self.check_axis(axis)?;
+
+        //Moving the axis last puts each group of reduced elements next to each other.
+        let mut axes: Vec<usize> = (0..self.rank()).filter(|a| *a != axis).collect();
+        axes.push(axis);
+        let values: Vec<f64> = self.permute(&axes)?.values().collect();
+
+        let mut shape = self.shape.clone();
+        let length = shape.remove(axis);
+        let data = if length == 0 {
+            vec![f(&[]); shape.iter().product()]
+        }
+        else {
+            values.chunks(length).map(f).collect()
+        };
+
+        Ok( Self::contiguous(data, shape) )
+    }
+    /// Sums along `axis`, removing it.
+    pub fn sum_axis(&self, axis: usize) -> Result<Self, Error> {
+        self.reduce_axis(axis, |v| v.iter().sum())
+    }
+    /// Averages along `axis`, removing it.
+    pub fn mean_axis(&self, axis: usize) -> Result<Self, Error> {
+        self.reduce_axis(axis, |v| v.iter().sum::<f64>() / v.len() as f64)
+    }
+    /// The largest value along `axis`, removing it. Fails if the axis is empty.
+    pub fn max_axis(&self, axis: usize) -> Result<Self, Error> {
+        self.check_axis(axis)?;
+        if self.shape[axis] == 0 {
+            return Err(operation_error!("max", "axis {} is empty", axis));
+        }
+
+        self.reduce_axis(axis, |v| v.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+    }
+    pub fn sum(&self) -> f64 {
+        self.values().sum()
+    }
+    pub fn mean(&self) -> f64 {
+        self.sum() / self.len() as f64
+    }
+    pub fn max(&self) -> Option<f64> {
+        self.values().reduce(f64::max)
+    }
+}
+
+#[test]
+fn test_tensor_operations() {
+    let t = |data: Vec<f64>, shape: Vec<usize>| Tensor::new(data, shape).unwrap();
+    let cube = t((0..24).map(|x| x as f64).collect(), vec![2, 3, 4]);
+    assert_eq!(cube.strides(), &[12, 4, 1]);
+    assert_eq!(cube.get(&[1, 2, 3]), Some(23.0));
+    assert_eq!(cube.get(&[2, 0, 0]), None);
+    assert!(Tensor::new(vec![1.0, 2.0], vec![3]).is_err());
+    assert!(Tensor::new(vec![], vec![1 << 40, 1 << 40]).is_err());
+    assert!(Tensor::zeros(vec![1 << 40, 1 << 40]).is_err());
+    assert_eq!(Tensor::zeros(vec![2, 0]).unwrap().len(), 0);
+    assert!(Tensor::scalar(1.0).broadcast_to(&[1 << 40, 1 << 40]).is_err());
+    assert!(Tensor::try_from(vec![binary_unit!(2usize), binary_unit!(1usize << 40), binary_unit!(1usize << 40)]).is_err());
+
+    //Broadcasting a row across every matrix and row, and a column across every column.
+    let row = t(vec![1.0, 2.0, 3.0, 4.0], vec![4]);
+    let shifted = (cube.clone() + row).unwrap();
+    assert_eq!(shifted.shape(), &[2, 3, 4]);
+    assert_eq!(shifted.get(&[1, 1, 3]), Some(23.0));
+    let column = t(vec![10.0, 20.0, 30.0], vec![3, 1]);
+    assert_eq!((cube.clone() * column).unwrap().get(&[1, 2, 1]), Some(21.0 * 30.0));
+    assert!((cube.clone() + t(vec![1.0, 2.0], vec![2])).is_err());
+    assert_eq!(Tensor::broadcast_shape(&[8, 1, 6, 1], &[7, 1, 5]), Some(vec![8, 7, 6, 5]));
+
+    let swapped = cube.permute(&[2, 0, 1]).unwrap();
+    assert_eq!(swapped.shape(), &[4, 2, 3]);
+    assert_eq!(swapped.get(&[3, 1, 2]), cube.get(&[1, 2, 3]));
+    assert!(!swapped.is_contiguous());
+    assert_eq!(cube.transpose().shape(), &[4, 3, 2]);
+    assert!(cube.permute(&[0, 0, 1]).is_err());
+    let flat = swapped.reshape(vec![24]).unwrap();
+    assert_eq!(flat.get(&[1]), Some(4.0));
+    assert!(cube.reshape(vec![5, 5]).is_err());
+
+    assert_eq!(cube.sum_axis(0).unwrap(), t((0..12).map(|x| 12.0 + 2.0 * x as f64).collect(), vec![3, 4]));
+    assert_eq!(cube.mean_axis(2).unwrap(), t(vec![1.5, 5.5, 9.5, 13.5, 17.5, 21.5], vec![2, 3]));
+    assert_eq!(cube.max_axis(1).unwrap(), t(vec![8.0, 9.0, 10.0, 11.0, 20.0, 21.0, 22.0, 23.0], vec![2, 4]));
+    assert_eq!(cube.sum(), 276.0);
+    assert_eq!(cube.max(), Some(23.0));
+    assert!(cube.sum_axis(3).is_err());
+
+    let window = cube.slice(&[1..2, 0..3]).unwrap().slice_axis(2, 1, 10, 2).unwrap();
+    assert_eq!(window, t(vec![13.0, 15.0, 17.0, 19.0, 21.0, 23.0], vec![1, 3, 2]));
+    let first = cube.slice_axis(0, 0, 2, usize::MAX).unwrap();
+    assert_eq!(first, cube.slice_axis(0, 0, 1, 1).unwrap());
+    let image = cube.index_axis(0, 1).unwrap();
+    assert_eq!(image.shape(), &[3, 4]);
+    assert_eq!(image.to_string(), "[[12, 13, 14, 15], [16, 17, 18, 19], [20, 21, 22, 23]]");
+    assert!(cube.index_axis(0, 2).is_err());
+    assert!(image.shares_data(&cube) && window.shares_data(&cube) && swapped.shares_data(&cube));
+    assert!(cube.broadcast_to(&[5, 2, 3, 4]).unwrap().shares_data(&cube));
+
+    let mut copy = window.clone();
+    copy.set(&[0, 2, 1], -1.0).unwrap();
+    assert_eq!(copy.get(&[0, 2, 1]), Some(-1.0));
+    assert_eq!(window.get(&[0, 2, 1]), Some(23.0));
+    let mut whole = cube.clone();
+    whole.set(&[0, 0, 0], 100.0).unwrap();
+    assert!(!whole.shares_data(&cube));
+    assert_eq!((whole.get(&[0, 0, 0]), cube.get(&[0, 0, 0])), (Some(100.0), Some(0.0)));
+
+    let matrix = Matrix::try_from(image.clone()).unwrap();
+    assert_eq!(Tensor::from(matrix), image);
+
+    let units: Vec<BinaryUnit> = swapped.clone().into();
+    assert_eq!(units.len(), swapped.required_units());
+    assert_eq!(Tensor::try_from(units).unwrap(), swapped);
+}
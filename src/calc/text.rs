@@ -0,0 +1,152 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign};
+
+use crate::{binary_unit, conversion_error, format_error, range_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, variable_data::VariableData, scalar::Scalar, rational::Rational};
+
+/// A UTF-8 string. Lengths and indices count characters, not bytes.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct Text {
+    data: String
+}
+impl Debug for Text {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Text:{:?})", self.data)
+    }
+}
+impl Display for Text {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data)
+    }
+}
+impl From<&str> for Text {
+    fn from(value: &str) -> Self {
+        Self {
+            data: value.to_string()
+        }
+    }
+}
+impl From<String> for Text {
+    fn from(value: String) -> Self {
+        Self {
+            data: value
+        }
+    }
+}
+impl From<Text> for String {
+    fn from(value: Text) -> Self {
+        value.data
+    }
+}
+impl From<Text> for Vec<BinaryUnit> {
+    /// Encoded as the length in bytes, followed by the UTF-8 bytes themselves.
+    fn from(value: Text) -> Self {
+        vec![ binary_unit!(value.data.len()), binary_unit!(value.data.into_bytes()) ]
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for Text {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        let mut iter = value.into_iter();
+        let mut next = || iter.next().ok_or_else(|| conversion_error!("ran out of units while decoding text"));
+
+        let len: usize = next()?.try_into()?;
+        let bytes = next()?.expose().to_vec();
+        if bytes.len() != len {
+            return Err(conversion_error!("expected {} bytes of text, but got {}", len, bytes.len()));
+        }
+
+        let data = String::from_utf8(bytes).map_err(|_| conversion_error!("text is not valid UTF-8"))?;
+        Ok( Self::from(data) )
+    }
+}
+impl VariableType for Text {
+    fn required_units(&self) -> usize {
+        2usize
+    }
+}
+impl Add for Text {
+    type Output = Self;
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+impl Add<&str> for Text {
+    type Output = Self;
+    fn add(mut self, rhs: &str) -> Self::Output {
+        self.data.push_str(rhs);
+        self
+    }
+}
+impl AddAssign for Text {
+    fn add_assign(&mut self, rhs: Self) {
+        self.data.push_str(&rhs.data)
+    }
+}
+
+impl Text {
+    pub fn as_str(&self) -> &str {
+        &self.data
+    }
+    /// The number of characters.
+    pub fn len(&self) -> usize {
+        self.data.chars().count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The characters from `start` up to, but not including, `end`.
+    pub fn slice(&self, start: usize, end: usize) -> Result<Self, Error> {
+        let len = self.len();
+        if end > len {
+            return Err(range_error!("end", end, start, len));
+        }
+        if start > end {
+            return Err(range_error!("start", start, 0, end));
+        }
+
+        Ok( Self::from(self.data.chars().skip(start).take(end - start).collect::<String>()) )
+    }
+
+    /// Writes any value the way it is displayed.
+    pub fn format(value: &VariableData) -> Self {
+        Self::from(value.to_string())
+    }
+    pub fn to_scalar(&self) -> Result<Scalar, Error> {
+        let trimmed = self.data.trim();
+        trimmed.parse::<f64>().map(Scalar::from).map_err(|_| format_error!(trimmed, "not a number"))
+    }
+    /// Reads a number, producing a `Rational` for text such as `"2/3"` and a `Scalar` otherwise.
+    pub fn to_number(&self) -> Result<VariableData, Error> {
+        let trimmed = self.data.trim();
+        if trimmed.contains('/') {
+            Ok( VariableData::Rational(trimmed.parse::<Rational>()?) )
+        }
+        else {
+            Ok( VariableData::Scalar(self.to_scalar()?) )
+        }
+    }
+}
+
+#[test]
+fn test_text_operations() {
+    let greeting = Text::from("héllo") + Text::from(", ") + "wörld";
+    assert_eq!(greeting.to_string(), "héllo, wörld");
+    assert_eq!(greeting.len(), 12);
+    assert_eq!(greeting.slice(7, 12).unwrap(), Text::from("wörld"));
+    assert_eq!(greeting.slice(1, 1).unwrap(), Text::from(""));
+    assert!(greeting.slice(4, 13).is_err());
+    assert!(greeting.slice(5, 4).is_err());
+
+    assert_eq!(Text::from(" 2.5 ").to_scalar().unwrap(), Scalar::from(2.5));
+    assert_eq!(Text::from("2/6").to_number().unwrap(), VariableData::Rational(Rational::new(1, 3).unwrap()));
+    assert_eq!(Text::from("-4e2").to_number().unwrap(), VariableData::Scalar(Scalar::from(-400.0)));
+    assert!(Text::from("four").to_number().is_err());
+    assert_eq!(Text::format(&VariableData::Scalar(Scalar::from(1.5))), Text::from("1.5"));
+
+    let units: Vec<BinaryUnit> = greeting.clone().into();
+    assert_eq!(units.len(), greeting.required_units());
+    assert_eq!(Text::try_from(units).unwrap(), greeting);
+}
@@ -0,0 +1,553 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Mul, Div};
+use std::str::FromStr;
+
+use crate::{binary_unit, conversion_error, format_error, operator_error, operation_error, core::{io::BinaryUnit, errors::Error}};
+use super::{variable_type::VariableType, scalar::Scalar, vector::MVector, matrix::Matrix};
+
+/// The exponents of the seven SI base dimensions, in the order length, mass, time, current, temperature, amount and luminous intensity.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Dimension {
+    exponents: [i32; 7]
+}
+impl Debug for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Dimension:{})", self)
+    }
+}
+impl Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = BASE_SYMBOLS.iter().zip(self.exponents).filter(|(_, e)| *e != 0).map(|(s, e)| {
+            if e == 1 { s.to_string() } else { format!("{}^{}", s, e) }
+        }).collect();
+
+        if parts.is_empty() {
+            write!(f, "1")
+        }
+        else {
+            write!(f, "{}", parts.join("·"))
+        }
+    }
+}
+impl Mul for Dimension {
+    type Output = Result<Self, Error>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.combine(&rhs, i32::checked_add).ok_or_else(|| operator_error!('*', self, rhs))
+    }
+}
+impl Div for Dimension {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.combine(&rhs, i32::checked_sub).ok_or_else(|| operator_error!('/', self, rhs))
+    }
+}
+impl Dimension {
+    pub const fn new(exponents: [i32; 7]) -> Self {
+        Self {
+            exponents
+        }
+    }
+    pub fn exponents(&self) -> &[i32; 7] {
+        &self.exponents
+    }
+    pub fn is_dimensionless(&self) -> bool {
+        self.exponents.iter().all(|e| *e == 0)
+    }
+    /// Applies `f` to each pair of exponents, failing if any result overflows.
+    fn combine<F>(&self, rhs: &Self, f: F) -> Option<Self> where F: Fn(i32, i32) -> Option<i32> {
+        let mut exponents = [0; 7];
+        for (i, e) in exponents.iter_mut().enumerate() {
+            *e = f(self.exponents[i], rhs.exponents[i])?;
+        }
+
+        Some( Self::new(exponents) )
+    }
+    pub fn powi(&self, n: i32) -> Result<Self, Error> {
+        let mut exponents = self.exponents;
+        for e in exponents.iter_mut() {
+            *e = e.checked_mul(n).ok_or_else(|| operation_error!("powi", "raising {} to the power {} overflows its exponents", self, n))?;
+        }
+
+        Ok( Self::new(exponents) )
+    }
+}
+
+/// The coherent SI unit of each base dimension.
+const BASE_SYMBOLS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+const fn dim(exponents: [i32; 7]) -> Dimension {
+    Dimension::new(exponents)
+}
+
+/// One entry in the unit library: a symbol, its dimension, its size in coherent SI units, an offset for temperature scales, and whether it takes SI prefixes.
+struct LibraryUnit(&'static str, Dimension, f64, f64, bool);
+
+const LIBRARY: &[LibraryUnit] = &[
+    //SI base units. The kilogram is the prefixed gram.
+    LibraryUnit("m", dim([1, 0, 0, 0, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("g", dim([0, 1, 0, 0, 0, 0, 0]), 1e-3, 0.0, true),
+    LibraryUnit("s", dim([0, 0, 1, 0, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("A", dim([0, 0, 0, 1, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("K", dim([0, 0, 0, 0, 1, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("mol", dim([0, 0, 0, 0, 0, 1, 0]), 1.0, 0.0, true),
+    LibraryUnit("cd", dim([0, 0, 0, 0, 0, 0, 1]), 1.0, 0.0, true),
+    //Derived SI units
+    LibraryUnit("Hz", dim([0, 0, -1, 0, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("N", dim([1, 1, -2, 0, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("Pa", dim([-1, 1, -2, 0, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("J", dim([2, 1, -2, 0, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("W", dim([2, 1, -3, 0, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("C", dim([0, 0, 1, 1, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("V", dim([2, 1, -3, -1, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("Ω", dim([2, 1, -3, -2, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("ohm", dim([2, 1, -3, -2, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("S", dim([-2, -1, 3, 2, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("F", dim([-2, -1, 4, 2, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("Wb", dim([2, 1, -2, -1, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("T", dim([0, 1, -2, -1, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("H", dim([2, 1, -2, -2, 0, 0, 0]), 1.0, 0.0, true),
+    LibraryUnit("L", dim([3, 0, 0, 0, 0, 0, 0]), 1e-3, 0.0, true),
+    LibraryUnit("eV", dim([2, 1, -2, 0, 0, 0, 0]), 1.602176634e-19, 0.0, true),
+    LibraryUnit("bar", dim([-1, 1, -2, 0, 0, 0, 0]), 1e5, 0.0, true),
+    //Accepted non-SI units
+    LibraryUnit("min", dim([0, 0, 1, 0, 0, 0, 0]), 60.0, 0.0, false),
+    LibraryUnit("h", dim([0, 0, 1, 0, 0, 0, 0]), 3600.0, 0.0, false),
+    LibraryUnit("day", dim([0, 0, 1, 0, 0, 0, 0]), 86400.0, 0.0, false),
+    LibraryUnit("°C", dim([0, 0, 0, 0, 1, 0, 0]), 1.0, 273.15, false),
+    LibraryUnit("degC", dim([0, 0, 0, 0, 1, 0, 0]), 1.0, 273.15, false),
+    LibraryUnit("atm", dim([-1, 1, -2, 0, 0, 0, 0]), 101325.0, 0.0, false),
+    LibraryUnit("cal", dim([2, 1, -2, 0, 0, 0, 0]), 4.184, 0.0, true),
+    //Imperial and US customary units
+    LibraryUnit("in", dim([1, 0, 0, 0, 0, 0, 0]), 0.0254, 0.0, false),
+    LibraryUnit("ft", dim([1, 0, 0, 0, 0, 0, 0]), 0.3048, 0.0, false),
+    LibraryUnit("yd", dim([1, 0, 0, 0, 0, 0, 0]), 0.9144, 0.0, false),
+    LibraryUnit("mi", dim([1, 0, 0, 0, 0, 0, 0]), 1609.344, 0.0, false),
+    LibraryUnit("oz", dim([0, 1, 0, 0, 0, 0, 0]), 0.028349523125, 0.0, false),
+    LibraryUnit("lb", dim([0, 1, 0, 0, 0, 0, 0]), 0.45359237, 0.0, false),
+    LibraryUnit("gal", dim([3, 0, 0, 0, 0, 0, 0]), 3.785411784e-3, 0.0, false),
+    LibraryUnit("mph", dim([1, 0, -1, 0, 0, 0, 0]), 0.44704, 0.0, false),
+    LibraryUnit("lbf", dim([1, 1, -2, 0, 0, 0, 0]), 4.4482216152605, 0.0, false),
+    LibraryUnit("psi", dim([-1, 1, -2, 0, 0, 0, 0]), 6894.757293168361, 0.0, false),
+    LibraryUnit("BTU", dim([2, 1, -2, 0, 0, 0, 0]), 1055.05585262, 0.0, false),
+    LibraryUnit("hp", dim([2, 1, -3, 0, 0, 0, 0]), 745.6998715822702, 0.0, false),
+    LibraryUnit("°F", dim([0, 0, 0, 0, 1, 0, 0]), 5.0 / 9.0, 459.67 * 5.0 / 9.0, false),
+    LibraryUnit("degF", dim([0, 0, 0, 0, 1, 0, 0]), 5.0 / 9.0, 459.67 * 5.0 / 9.0, false),
+];
+
+/// SI prefixes and their factors. `da` comes before `d` so that it is matched first.
+const PREFIXES: &[(&str, f64)] = &[
+    ("Q", 1e30), ("R", 1e27), ("Y", 1e24), ("Z", 1e21), ("E", 1e18), ("P", 1e15), ("T", 1e12), ("G", 1e9), ("M", 1e6), ("k", 1e3), ("h", 1e2), ("da", 1e1),
+    ("d", 1e-1), ("c", 1e-2), ("m", 1e-3), ("µ", 1e-6), ("u", 1e-6), ("n", 1e-9), ("p", 1e-12), ("f", 1e-15), ("a", 1e-18), ("z", 1e-21), ("y", 1e-24), ("r", 1e-27), ("q", 1e-30)
+];
+
+/// A single, possibly prefixed, unit from the library, such as `km` or `°F`.
+#[derive(Clone, PartialEq, Debug)]
+struct NamedUnit {
+    symbol: String,
+    dimension: Dimension,
+    scale: f64,
+    offset: f64
+}
+impl NamedUnit {
+    fn lookup(symbol: &str) -> Option<Self> {
+        let make = |entry: &LibraryUnit, factor: f64| Self {
+            symbol: symbol.to_string(),
+            dimension: entry.1,
+            scale: entry.2 * factor,
+            offset: entry.3 * factor
+        };
+
+        if let Some(entry) = LIBRARY.iter().find(|u| u.0 == symbol) {
+            return Some(make(entry, 1.0));
+        }
+
+        PREFIXES.iter().find_map(|(prefix, factor)| {
+            let rest = symbol.strip_prefix(prefix)?;
+            let entry = LIBRARY.iter().find(|u| u.0 == rest && u.4)?;
+            Some(make(entry, *factor))
+        })
+    }
+}
+
+/// A unit of measure, kept as a product of library units raised to integer powers, such as `kg·m/s^2`.
+#[derive(Clone, PartialEq, Default)]
+pub struct Unit {
+    factors: Vec<(NamedUnit, i32)>
+}
+impl Debug for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.dimension() {
+            Ok(dimension) => write!(f, "(Unit:{} [{}])", self, dimension),
+            Err(_) => write!(f, "(Unit:{} [overflow])", self)
+        }
+    }
+}
+impl Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let power = |u: &NamedUnit, e: u32| if e == 1 { u.symbol.clone() } else { format!("{}^{}", u.symbol, e) };
+        let numerator: Vec<String> = self.factors.iter().filter(|(_, e)| *e > 0).map(|(u, e)| power(u, e.unsigned_abs())).collect();
+        let denominator: Vec<String> = self.factors.iter().filter(|(_, e)| *e < 0).map(|(u, e)| format!("/{}", power(u, e.unsigned_abs()))).collect();
+
+        if numerator.is_empty() && !denominator.is_empty() {
+            write!(f, "1")?;
+        }
+        write!(f, "{}{}", numerator.join("·"), denominator.concat())
+    }
+}
+impl FromStr for Unit {
+    type Err = Error;
+    /// Parses products and quotients of library units, such as `km/h`, `kg·m/s^2` or `N*m`. Each `/` divides by the factor directly after it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::dimensionless();
+        let mut divide = false;
+        let mut token = String::new();
+
+        let mut finish = |token: &mut String, divide: bool| -> Result<(), Error> {
+            if token.is_empty() || token == "1" {
+                token.clear();
+                return Ok(());
+            }
+
+            let (symbol, power) = match token.split_once('^') {
+                Some((symbol, power)) => (symbol, power.parse::<i32>().map_err(|_| format_error!(s, "'{}' is not an integer power", power))?),
+                None => (token.as_str(), 1)
+            };
+            let named = NamedUnit::lookup(symbol).ok_or_else(|| format_error!(s, "'{}' is not a known unit", symbol))?;
+            let power = if divide { power.checked_neg() } else { Some(power) };
+            power.and_then(|p| result.push(named, p).ok()).ok_or_else(|| format_error!(s, "the power of '{}' is out of range", symbol))?;
+
+            token.clear();
+            Ok(())
+        };
+
+        for c in s.trim().chars() {
+            match c {
+                '*' | '·' | ' ' => {
+                    finish(&mut token, divide)?;
+                    divide = false;
+                },
+                '/' => {
+                    finish(&mut token, divide)?;
+                    divide = true;
+                },
+                c => token.push(c)
+            }
+        }
+        finish(&mut token, divide)?;
+
+        Ok(result)
+    }
+}
+impl Mul for Unit {
+    type Output = Result<Self, Error>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        for (u, e) in &rhs.factors {
+            if result.push(u.clone(), *e).is_err() {
+                return Err(operator_error!('*', self, rhs));
+            }
+        }
+
+        Ok( result )
+    }
+}
+impl Div for Unit {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        for (u, e) in &rhs.factors {
+            if e.checked_neg().is_none_or(|e| result.push(u.clone(), e).is_err()) {
+                return Err(operator_error!('/', self, rhs));
+            }
+        }
+
+        Ok( result )
+    }
+}
+impl Unit {
+    /// Multiplies in `unit^power`, which fails if the combined power does not fit in an `i32`.
+    fn push(&mut self, unit: NamedUnit, power: i32) -> Result<(), Error> {
+        match self.factors.iter().position(|(u, _)| u.symbol == unit.symbol) {
+            Some(i) => {
+                self.factors[i].1 = self.factors[i].1.checked_add(power).ok_or_else(|| operation_error!("combine", "the power of '{}' is out of range", unit.symbol))?;
+                if self.factors[i].1 == 0 {
+                    self.factors.remove(i);
+                }
+            },
+            None if power != 0 => self.factors.push((unit, power)),
+            None => ()
+        }
+
+        Ok(())
+    }
+
+    pub fn dimensionless() -> Self {
+        Self::default()
+    }
+    /// The coherent SI unit for `dimension`, written in base units, such as `kg·m/s^2` for force.
+    pub fn si(dimension: Dimension) -> Self {
+        Self {
+            factors: BASE_SYMBOLS.iter().zip(dimension.exponents).filter(|(_, e)| *e != 0).map(|(symbol, e)| (NamedUnit::lookup(symbol).unwrap(), e)).collect()
+        }
+    }
+
+    /// The dimension this unit measures, which fails if an exponent does not fit in an `i32`.
+    pub fn dimension(&self) -> Result<Dimension, Error> {
+        self.factors.iter().try_fold(Dimension::default(), |acc, (u, e)| acc * u.dimension.powi(*e)?)
+    }
+    /// The size of this unit in coherent SI units.
+    pub fn scale(&self) -> f64 {
+        self.factors.iter().map(|(u, e)| u.scale.powi(*e)).product()
+    }
+    /// The SI value of zero in this unit. Only a lone temperature scale such as `°C` has one; in any compound it measures a difference, and the offset is dropped.
+    pub fn offset(&self) -> f64 {
+        match self.factors.as_slice() {
+            [(u, 1)] => u.offset,
+            _ => 0.0
+        }
+    }
+    /// Whether both units measure the same dimension. A unit whose dimension overflows is compatible with nothing.
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        matches!((self.dimension(), other.dimension()), (Ok(a), Ok(b)) if a == b)
+    }
+    pub fn powi(&self, n: i32) -> Result<Self, Error> {
+        if n == 0 {
+            return Ok( Self::dimensionless() );
+        }
+
+        let factors = self.factors.iter().map(|(u, e)| {
+            e.checked_mul(n).map(|e| (u.clone(), e)).ok_or_else(|| operation_error!("powi", "the power of '{}' is out of range", u.symbol))
+        }).collect::<Result<Vec<(NamedUnit, i32)>, Error>>()?;
+
+        Ok( Self { factors } )
+    }
+}
+
+/// Values that can carry a unit. Conversion only needs to apply an affine map to every element.
+pub trait Measurable: VariableType {
+    /// Applies `f` to every element.
+    fn map_elements<F: Fn(f64) -> f64>(&self, f: F) -> Self;
+    /// The element-wise sum, which fails if the shapes differ.
+    fn add_elements(&self, rhs: &Self) -> Result<Self, Error>;
+}
+impl Measurable for Scalar {
+    fn map_elements<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+        Scalar::from(f(f64::from(self.clone())))
+    }
+    fn add_elements(&self, rhs: &Self) -> Result<Self, Error> {
+        Ok( Scalar::from(f64::from(self.clone()) + f64::from(rhs.clone())) )
+    }
+}
+impl Measurable for MVector<Scalar> {
+    fn map_elements<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+        MVector::from(self.iter().map(|x| x.map_elements(&f)).collect::<Vec<Scalar>>())
+    }
+    fn add_elements(&self, rhs: &Self) -> Result<Self, Error> {
+        self.clone() + rhs.clone()
+    }
+}
+impl Measurable for Matrix {
+    fn map_elements<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+        let data = (0..self.rows()).flat_map(|i| (0..self.cols()).map(move |j| (i, j))).map(|(i, j)| f(self[(i, j)])).collect();
+        Matrix::from_flat(self.rows(), self.cols(), data)
+    }
+    fn add_elements(&self, rhs: &Self) -> Result<Self, Error> {
+        self.clone() + rhs.clone()
+    }
+}
+
+/// A value together with the unit it is measured in.
+#[derive(Clone, PartialEq)]
+pub struct Quantity<T: Measurable> {
+    value: T,
+    unit: Unit
+}
+impl<T: Measurable> Debug for Quantity<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Quantity:{:?} {:?})", &self.value, &self.unit)
+    }
+}
+impl<T: Measurable> Display for Quantity<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.unit.factors.is_empty() {
+            write!(f, "{}", &self.value)
+        }
+        else {
+            write!(f, "{} {}", &self.value, &self.unit)
+        }
+    }
+}
+impl<T: Measurable> From<Quantity<T>> for Vec<BinaryUnit> {
+    /// Encoded as the length of the value, its units, the number of unit factors, and then each factor's symbol and power.
+    fn from(value: Quantity<T>) -> Self {
+        let inner: Vec<BinaryUnit> = value.value.into();
+        let mut result = vec![ binary_unit!(inner.len()) ];
+        result.extend(inner);
+        result.push(binary_unit!(value.unit.factors.len()));
+        for (u, e) in value.unit.factors {
+            result.push(binary_unit!(u.symbol.into_bytes()));
+            result.push(binary_unit!(e));
+        }
+
+        result
+    }
+}
+impl<T: Measurable> TryFrom<Vec<BinaryUnit>> for Quantity<T> {
+    type Error = Error;
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        let mut iter = value.into_iter();
+        let mut next = || iter.next().ok_or_else(|| conversion_error!("ran out of units while decoding a quantity"));
+
+        let len: usize = next()?.try_into()?;
+        let inner = (0..len).map(|_| next()).collect::<Result<Vec<BinaryUnit>, Error>>()?;
+        let value = T::try_from(inner)?;
+
+        let count: usize = next()?.try_into()?;
+        let mut unit = Unit::dimensionless();
+        for _ in 0..count {
+            let symbol = String::from_utf8(next()?.expose().to_vec()).map_err(|_| conversion_error!("unit symbol is not valid UTF-8"))?;
+            let power: i32 = next()?.try_into()?;
+            let named = NamedUnit::lookup(&symbol).ok_or_else(|| conversion_error!("'{}' is not a known unit", symbol))?;
+            unit.push(named, power).map_err(|_| conversion_error!("the power of '{}' is out of range", symbol))?;
+        }
+
+        Ok( Self::new(value, unit) )
+    }
+}
+impl<T: Measurable> VariableType for Quantity<T> {
+    fn required_units(&self) -> usize {
+        2 + self.value.required_units() + 2 * self.unit.factors.len()
+    }
+}
+impl<T: Measurable> Add for Quantity<T> {
+    type Output = Result<Self, Error>;
+    /// Adds `rhs` after expressing it in the unit of `self`. Temperatures on the right are treated as differences, so `20 °C + 5 K` is `25 °C`.
+    fn add(self, rhs: Self) -> Self::Output {
+        if !self.unit.is_compatible(&rhs.unit) {
+            return Err(operator_error!('+', self, rhs));
+        }
+
+        let factor = rhs.unit.scale() / self.unit.scale();
+        let value = self.value.add_elements(&rhs.value.map_elements(|x| x * factor))?;
+        Ok( Self::new(value, self.unit) )
+    }
+}
+impl<T: Measurable> Sub for Quantity<T> {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        if !self.unit.is_compatible(&rhs.unit) {
+            return Err(operator_error!('-', self, rhs));
+        }
+
+        let factor = rhs.unit.scale() / self.unit.scale();
+        let value = self.value.add_elements(&rhs.value.map_elements(|x| -x * factor))?;
+        Ok( Self::new(value, self.unit) )
+    }
+}
+impl<T: Measurable> Mul<Quantity<Scalar>> for Quantity<T> {
+    type Output = Result<Self, Error>;
+    fn mul(self, rhs: Quantity<Scalar>) -> Self::Output {
+        let factor = f64::from(rhs.value);
+        Ok( Self::new(self.value.map_elements(|x| x * factor), (self.unit * rhs.unit)?) )
+    }
+}
+impl<T: Measurable> Div<Quantity<Scalar>> for Quantity<T> {
+    type Output = Result<Self, Error>;
+    fn div(self, rhs: Quantity<Scalar>) -> Self::Output {
+        let divisor = f64::from(rhs.value.clone());
+        if divisor == 0.0 {
+            return Err(operator_error!('/', self, rhs));
+        }
+
+        Ok( Self::new(self.value.map_elements(|x| x / divisor), (self.unit / rhs.unit)?) )
+    }
+}
+impl<T: Measurable> Quantity<T> {
+    pub fn new(value: T, unit: Unit) -> Self {
+        Self {
+            value,
+            unit
+        }
+    }
+    /// Pairs `value` with a unit parsed from `unit`, such as `"km/h"`.
+    pub fn parse(value: T, unit: &str) -> Result<Self, Error> {
+        Ok( Self::new(value, unit.parse()?) )
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+    pub fn unit(&self) -> &Unit {
+        &self.unit
+    }
+    pub fn dimension(&self) -> Result<Dimension, Error> {
+        self.unit.dimension()
+    }
+
+    /// Expresses the same quantity in `target`, which must measure the same dimension.
+    pub fn convert_to(&self, target: &Unit) -> Result<Self, Error> {
+        let (from, to) = (self.unit.dimension()?, target.dimension()?);
+        if from != to {
+            return Err(operation_error!("convert", "'{}' and '{}' measure different dimensions ({} and {})", &self.unit, target, from, to));
+        }
+
+        let (scale, offset) = (self.unit.scale(), self.unit.offset());
+        let (target_scale, target_offset) = (target.scale(), target.offset());
+        Ok( Self::new(self.value.map_elements(|x| (x * scale + offset - target_offset) / target_scale), target.clone()) )
+    }
+    /// Expresses the quantity in coherent SI base units.
+    pub fn to_si(&self) -> Result<Self, Error> {
+        self.convert_to(&Unit::si(self.dimension()?))
+    }
+}
+impl Quantity<Scalar> {
+    pub fn powi(&self, n: i32) -> Result<Self, Error> {
+        Ok( Self::new(self.value.map_elements(|x| x.powi(n)), self.unit.powi(n)?) )
+    }
+}
+
+#[test]
+fn test_quantity_conversion() {
+    let close = |q: &Quantity<Scalar>, expected: f64| (f64::from(q.value().clone()) - expected).abs() < 1e-9 * expected.abs().max(1.0);
+    let q = |x: f64, unit: &str| Quantity::parse(Scalar::from(x), unit).unwrap();
+    let unit = |s: &str| s.parse::<Unit>().unwrap();
+
+    assert!(close(&q(36.0, "km/h").convert_to(&unit("m/s")).unwrap(), 10.0));
+    assert!(close(&q(1.0, "mi").convert_to(&unit("km")).unwrap(), 1.609344));
+    assert!(close(&q(100.0, "°F").convert_to(&unit("°C")).unwrap(), 37.77777777777778));
+    assert!(close(&q(0.0, "degC").to_si().unwrap(), 273.15));
+    assert!(close(&q(1.0, "kW·h").convert_to(&unit("MJ")).unwrap(), 3.6));
+    assert!(close(&q(1.0, "N").convert_to(&unit("kg·m/s^2")).unwrap(), 1.0));
+    assert_eq!(unit("N").dimension().unwrap(), unit("kg*m/s^2").dimension().unwrap());
+    assert!(q(1.0, "m").convert_to(&unit("s")).is_err());
+    assert!("furlong".parse::<Unit>().is_err());
+
+    let sum = (q(1.0, "km") + q(500.0, "m")).unwrap();
+    assert!(close(&sum, 1.5));
+    assert_eq!(format!("{}", sum), "1.5 km");
+    assert!((q(1.0, "m") + q(1.0, "s")).is_err());
+    assert!(close(&(q(20.0, "°C") + q(5.0, "K")).unwrap(), 25.0));
+
+    let work = (q(3.0, "N") * q(2.0, "m")).unwrap();
+    assert_eq!(format!("{}", work), "6 N·m");
+    assert_eq!(format!("{}", (q(9.8, "m") / q(1.0, "s").powi(2).unwrap()).unwrap().unit()), "m/s^2");
+
+    let offsets = Quantity::parse(MVector::from(vec![Scalar::from(1.0), Scalar::from(2.0)]), "m").unwrap();
+    let extra = Quantity::parse(MVector::from(vec![Scalar::from(100.0), Scalar::from(200.0)]), "cm").unwrap();
+    assert_eq!((offsets + extra).unwrap().value(), &MVector::from(vec![Scalar::from(2.0), Scalar::from(4.0)]));
+
+    let units: Vec<BinaryUnit> = q(9.8, "m/s^2").into();
+    assert_eq!(Quantity::<Scalar>::try_from(units).unwrap(), q(9.8, "m/s^2"));
+
+    assert!("1/m^-2147483648".parse::<Unit>().is_err());
+    assert_eq!(format!("{}", unit("m^-2147483648")), "1/m^2147483648");
+    assert!(q(1.0, "m^2").powi(i32::MAX).is_err());
+    assert!((q(1.0, "m^2147483647") * q(1.0, "m")).is_err());
+    assert!(unit("F^2147483647").dimension().is_err());
+    assert!(!unit("F^2147483647").is_compatible(&unit("F^2147483647")));
+    assert!(Dimension::new([i32::MAX, 0, 0, 0, 0, 0, 0]).powi(2).is_err());
+    let mut units: Vec<BinaryUnit> = q(1.0, "m").into();
+    units.truncate(units.len() - 3);
+    units.extend([ binary_unit!(2usize), binary_unit!(b"m".to_vec()), binary_unit!(i32::MAX), binary_unit!(b"m".to_vec()), binary_unit!(i32::MAX) ]);
+    assert!(Quantity::<Scalar>::try_from(units).is_err());
+}
@@ -1,17 +1,56 @@
 pub use super::scalar::Scalar;
 pub use super::vector::MVector;
 pub use super::matrix::Matrix;
+pub use super::cmatrix::CMatrix;
+pub use super::sparse::SparseMatrix;
 pub use super::complex::Complex;
+pub use super::rational::Rational;
+pub use super::bigint::BigInt;
+pub use super::bigdecimal::BigDecimal;
+pub use super::units::{Quantity, Unit};
+pub use super::interval::Interval;
+pub use super::measurement::{Measurement, SourceMap};
+pub use super::polynomial::Polynomial;
+pub use super::boolean::{Boolean, Comparison};
+pub use super::text::Text;
+pub use super::datetime::{DateTime, Duration};
+pub use super::quaternion::Quaternion;
+pub use super::tensor::Tensor;
+pub use super::integer::Integer;
+pub use super::modular::ModInt;
 
+use std::cmp::Ordering;
 use std::fmt::{Display, Debug};
 
+use crate::{argument_error, binary_unit, conversion_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+
 #[derive(PartialEq, Clone)]
 pub enum VariableData {
     Scalar(Scalar),
     Complex(Complex),
+    Rational(Rational),
+    BigInt(BigInt),
+    BigDecimal(BigDecimal),
+    Quantity(Quantity<Scalar>),
+    Interval(Interval),
+    IVector(MVector<Interval>),
+    Measurement(Measurement),
+    Polynomial(Polynomial<Scalar>),
+    CPolynomial(Polynomial<Complex>),
     Vector(MVector<Scalar>), 
     CVector(MVector<Complex>),
     Matrix(Matrix),
+    CMatrix(CMatrix),
+    Sparse(SparseMatrix),
+    Bool(Boolean),
+    Text(Text),
+    DateTime(DateTime),
+    Duration(Duration),
+    Quaternion(Quaternion),
+    Tensor(Tensor),
+    Integer(Integer),
+    ModInt(ModInt),
+    List(Vec<VariableData>),
 }
 impl Debug for VariableData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -20,7 +59,27 @@ impl Debug for VariableData {
             Self::Vector(v) => (v as &dyn Debug).fmt(f),
             Self::CVector(v) => (v as &dyn Debug).fmt(f),
             Self::Matrix(m) => (m as &dyn Debug).fmt(f),
-            Self::Complex(c) => (c as &dyn Debug).fmt(f)
+            Self::CMatrix(m) => (m as &dyn Debug).fmt(f),
+            Self::Sparse(m) => (m as &dyn Debug).fmt(f),
+            Self::Complex(c) => (c as &dyn Debug).fmt(f),
+            Self::Rational(r) => (r as &dyn Debug).fmt(f),
+            Self::BigInt(i) => (i as &dyn Debug).fmt(f),
+            Self::BigDecimal(d) => (d as &dyn Debug).fmt(f),
+            Self::Quantity(q) => (q as &dyn Debug).fmt(f),
+            Self::Interval(i) => (i as &dyn Debug).fmt(f),
+            Self::IVector(v) => (v as &dyn Debug).fmt(f),
+            Self::Measurement(m) => (m as &dyn Debug).fmt(f),
+            Self::Polynomial(p) => (p as &dyn Debug).fmt(f),
+            Self::CPolynomial(p) => (p as &dyn Debug).fmt(f),
+            Self::Bool(b) => (b as &dyn Debug).fmt(f),
+            Self::Text(t) => (t as &dyn Debug).fmt(f),
+            Self::DateTime(d) => (d as &dyn Debug).fmt(f),
+            Self::Duration(d) => (d as &dyn Debug).fmt(f),
+            Self::Quaternion(q) => (q as &dyn Debug).fmt(f),
+            Self::Tensor(t) => (t as &dyn Debug).fmt(f),
+            Self::Integer(i) => (i as &dyn Debug).fmt(f),
+            Self::ModInt(m) => (m as &dyn Debug).fmt(f),
+            Self::List(l) => write!(f, "(List:{:?})", l)
         }
     }
 }
@@ -31,7 +90,278 @@ impl Display for VariableData {
             Self::Vector(v) => (v as &dyn Display).fmt(f),
             Self::CVector(v) => (v as &dyn Display).fmt(f),
             Self::Matrix(m) => (m as &dyn Display).fmt(f),
-            Self::Complex(c) => (c as &dyn Display).fmt(f)
+            Self::CMatrix(m) => (m as &dyn Display).fmt(f),
+            Self::Sparse(m) => (m as &dyn Display).fmt(f),
+            Self::Complex(c) => (c as &dyn Display).fmt(f),
+            Self::Rational(r) => (r as &dyn Display).fmt(f),
+            Self::BigInt(i) => (i as &dyn Display).fmt(f),
+            Self::BigDecimal(d) => (d as &dyn Display).fmt(f),
+            Self::Quantity(q) => (q as &dyn Display).fmt(f),
+            Self::Interval(i) => (i as &dyn Display).fmt(f),
+            Self::IVector(v) => (v as &dyn Display).fmt(f),
+            Self::Measurement(m) => (m as &dyn Display).fmt(f),
+            Self::Polynomial(p) => (p as &dyn Display).fmt(f),
+            Self::CPolynomial(p) => (p as &dyn Display).fmt(f),
+            Self::Bool(b) => (b as &dyn Display).fmt(f),
+            Self::Text(t) => (t as &dyn Display).fmt(f),
+            Self::DateTime(d) => (d as &dyn Display).fmt(f),
+            Self::Duration(d) => (d as &dyn Display).fmt(f),
+            Self::Quaternion(q) => (q as &dyn Display).fmt(f),
+            Self::Tensor(t) => (t as &dyn Display).fmt(f),
+            Self::Integer(i) => (i as &dyn Display).fmt(f),
+            Self::ModInt(m) => (m as &dyn Display).fmt(f),
+            Self::List(l) => {
+                write!(f, "[")?;
+                for (i, item) in l.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    (item as &dyn Display).fmt(f)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+const LIST_TAG: u8 = 255;
+/// How deeply lists may nest in stored data. Decoding recurses once per level, so this keeps a crafted file from overflowing the stack.
+const MAX_LIST_DEPTH: usize = 64;
+
+//Decodes a variant's units, through its own decoder when it needs the shared decoding state.
+macro_rules! decode_variant {
+    ($t: ty, $units: expr, $sources: expr) => { <$t>::try_from($units) };
+    ($t: ty, $units: expr, $sources: expr, $decoder: path) => { $decoder($units, $sources) };
+}
+
+//Assigns each variant a fixed type tag, used to tell values apart when they are serialized together. Tags must never be reused or renumbered.
+macro_rules! tagged_variants {
+    ($($tag: literal => $variant: ident($t: ty) $(using $decoder: path)?),* $(,)?) => {
+        impl VariableData {
+            fn type_tag(&self) -> u8 {
+                match self {
+                    $(Self::$variant(_) => $tag,)*
+                    Self::List(_) => LIST_TAG
+                }
+            }
+            fn encode_value(self) -> Vec<BinaryUnit> {
+                match self {
+                    $(Self::$variant(x) => x.into(),)*
+                    Self::List(l) => {
+                        let mut result = vec![ binary_unit!(l.len()) ];
+                        for item in l {
+                            result.extend(Vec::<BinaryUnit>::from(item));
+                        }
+                        result
+                    }
+                }
+            }
+            fn decode_value(tag: u8, units: Vec<BinaryUnit>, sources: &mut SourceMap, depth: usize) -> Result<Self, Error> {
+                match tag {
+                    $($tag => Ok( Self::$variant(decode_variant!($t, units, sources $(, $decoder)?)?) ),)*
+                    LIST_TAG => {
+                        if depth >= MAX_LIST_DEPTH {
+                            return Err(conversion_error!("lists are nested more than {} levels deep", MAX_LIST_DEPTH));
+                        }
+                        let mut iter = units.into_iter();
+                        let count: usize = iter.next().ok_or_else(|| conversion_error!("ran out of units while decoding a list"))?.try_into()?;
+                        let items = (0..count).map(|_| Self::decode_from(&mut iter, sources, depth + 1)).collect::<Result<Vec<Self>, Error>>()?;
+                        if iter.next().is_some() {
+                            return Err(conversion_error!("unexpected units after the end of a list"));
+                        }
+                        Ok( Self::List(items) )
+                    },
+                    t => Err(conversion_error!("unknown type tag {}", t))
+                }
+            }
+        }
+    };
+}
+tagged_variants! {
+    0 => Scalar(Scalar),
+    1 => Complex(Complex),
+    2 => Rational(Rational),
+    3 => BigInt(BigInt),
+    4 => BigDecimal(BigDecimal),
+    5 => Quantity(Quantity<Scalar>),
+    6 => Interval(Interval),
+    7 => IVector(MVector<Interval>),
+    8 => Measurement(Measurement) using Measurement::decode,
+    9 => Polynomial(Polynomial<Scalar>),
+    10 => CPolynomial(Polynomial<Complex>),
+    11 => Vector(MVector<Scalar>),
+    12 => CVector(MVector<Complex>),
+    13 => Matrix(Matrix),
+    14 => CMatrix(CMatrix),
+    15 => Sparse(SparseMatrix),
+    16 => Bool(Boolean),
+    17 => Text(Text),
+    18 => DateTime(DateTime),
+    19 => Duration(Duration),
+    20 => Quaternion(Quaternion),
+    21 => Tensor(Tensor),
+    22 => Integer(Integer),
+    23 => ModInt(ModInt),
+}
+
+impl From<VariableData> for Vec<BinaryUnit> {
+    /// Encoded as a type tag, the number of units that follow, and then the units of the value itself. Lists hold their length followed by each encoded element.
+    fn from(value: VariableData) -> Self {
+        let tag = value.type_tag();
+        let inner = value.encode_value();
+        let mut result = vec![ binary_unit!(tag), binary_unit!(inner.len()) ];
+        result.extend(inner);
+
+        result
+    }
+}
+impl TryFrom<Vec<BinaryUnit>> for VariableData {
+    type Error = Error;
+    /// Reads a single value with its own `SourceMap`. Use `VariableData::decode` to keep measurements in several values correlated.
+    fn try_from(value: Vec<BinaryUnit>) -> Result<Self, Self::Error> {
+        Self::decode(value, &mut SourceMap::new())
+    }
+}
+
+impl VariableData {
+    /// Reads a value, giving the sources of any measurements in it fresh identifiers through `sources`.
+    pub fn decode(value: Vec<BinaryUnit>, sources: &mut SourceMap) -> Result<Self, Error> {
+        let mut iter = value.into_iter();
+        let result = Self::decode_from(&mut iter, sources, 0)?;
+        if iter.next().is_some() {
+            return Err(conversion_error!("unexpected units after the end of a value"));
+        }
+
+        Ok(result)
+    }
+    fn decode_from(iter: &mut impl Iterator<Item = BinaryUnit>, sources: &mut SourceMap, depth: usize) -> Result<Self, Error> {
+        let mut next = || iter.next().ok_or_else(|| conversion_error!("ran out of units while decoding a value"));
+        let tag: u8 = next()?.try_into()?;
+        let len: usize = next()?.try_into()?;
+        let units = (0..len).map(|_| next()).collect::<Result<Vec<BinaryUnit>, Error>>()?;
+
+        Self::decode_value(tag, units, sources, depth)
+    }
+
+    fn real_value(&self) -> Option<f64> {
+        match self {
+            Self::Scalar(s) => Some(f64::from(s.clone())),
+            Self::Rational(r) => Some(r.to_f64()),
+            Self::BigInt(i) => Some(i.to_f64()),
+            Self::BigDecimal(d) => Some(d.to_f64()),
+            Self::Measurement(m) => Some(m.value()),
+            Self::Integer(i) => Some(f64::from(*i)),
+            _ => None
+        }
+    }
+    fn complex_value(&self) -> Option<Complex> {
+        match self {
+            Self::Complex(c) => Some(*c),
+            _ => self.real_value().map(|x| Complex::new(x, 0.0))
+        }
+    }
+    fn is_complex(&self) -> bool {
+        matches!(self, Self::Complex(_) | Self::CVector(_) | Self::CMatrix(_) | Self::CPolynomial(_))
+    }
+    //Orderings that can be decided without going through f64.
+    fn exact_ordering(&self, rhs: &Self) -> Option<Option<Ordering>> {
+        match (self, rhs) {
+            (Self::Rational(a), Self::Rational(b)) => Some(a.partial_cmp(b)),
+            (Self::Integer(a), Self::Integer(b)) => Some(Some(a.cmp(b))),
+            (Self::Integer(a), Self::Rational(b)) => Some(Rational::from(*a).partial_cmp(b)),
+            (Self::Rational(a), Self::Integer(b)) => Some(a.partial_cmp(&Rational::from(*b))),
+            (Self::BigInt(a), Self::BigInt(b)) => Some(Some(a.cmp(b))),
+            (Self::BigDecimal(a), Self::BigDecimal(b)) => Some(Some(a.cmp(b))),
+            (Self::BigInt(a), Self::BigDecimal(b)) => Some(Some(BigDecimal::from(a.clone()).cmp(b))),
+            (Self::BigDecimal(a), Self::BigInt(b)) => Some(Some(a.cmp(&BigDecimal::from(b.clone())))),
+            (Self::Integer(a), Self::BigInt(b)) => Some(Some(BigInt::from(*a).cmp(b))),
+            (Self::BigInt(a), Self::Integer(b)) => Some(Some(a.cmp(&BigInt::from(*b)))),
+            (Self::Integer(a), Self::BigDecimal(b)) => Some(Some(BigDecimal::from(BigInt::from(*a)).cmp(b))),
+            (Self::BigDecimal(a), Self::Integer(b)) => Some(Some(a.cmp(&BigDecimal::from(BigInt::from(*b))))),
+            (Self::Rational(a), Self::BigInt(b)) => Some(Self::rational_ordering(a, b)),
+            (Self::BigInt(a), Self::Rational(b)) => Some(Self::rational_ordering(b, a).map(Ordering::reverse)),
+            (Self::DateTime(a), Self::DateTime(b)) => Some(Some(a.cmp(b))),
+            (Self::Duration(a), Self::Duration(b)) => Some(Some(a.cmp(b))),
+            _ => None
+        }
+    }
+    /// Orders `a` against `b` by cross multiplying, which keeps the order since the denominator is positive.
+    fn rational_ordering(a: &Rational, b: &BigInt) -> Option<Ordering> {
+        if a.is_nan() {
+            return None;
+        }
+
+        Some( BigInt::from(a.numerator()).cmp(&(b.clone() * BigInt::from(a.denominator()))) )
+    }
+    fn tolerant_ordering(a: f64, b: f64, tolerance: f64) -> Option<Ordering> {
+        if (a - b).abs() <= tolerance {
+            Some(Ordering::Equal)
+        }
+        else {
+            a.partial_cmp(&b)
+        }
+    }
+
+    /// Compares two values exactly, producing a `Bool`.
+    pub fn compare(&self, rhs: &Self, op: Comparison) -> Result<Self, Error> {
+        self.compare_with(rhs, op, 0.0)
+    }
+    /// Compares two values, treating numbers that differ by at most `tolerance` as equal.
+    /// Real numeric types can be mixed freely, and quantities are compared after converting to a common unit. Dates and durations are ordered, with `tolerance` in seconds.
+    /// Complex values only support `==` and `!=`, and any other type only compares equal to a value of the same type.
+    pub fn compare_with(&self, rhs: &Self, op: Comparison, tolerance: f64) -> Result<Self, Error> {
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return Err(argument_error!("tolerance", "must be non-negative, but got {}", tolerance));
+        }
+        if op.is_ordering() && (self.is_complex() || rhs.is_complex()) {
+            return Err(operator_error!(op, self, rhs));
+        }
+
+        let ordering = match (self, rhs) {
+            (Self::Quantity(a), Self::Quantity(b)) => {
+                let b = b.convert_to(a.unit()).map_err(|_| operator_error!(op, self, rhs))?;
+                Self::tolerant_ordering(f64::from(a.value().clone()), f64::from(b.value().clone()), tolerance)
+            },
+            (Self::DateTime(a), Self::DateTime(b)) if tolerance > 0.0 => Self::tolerant_ordering((*a - *b).as_seconds(), 0.0, tolerance),
+            (Self::Duration(a), Self::Duration(b)) if tolerance > 0.0 => Self::tolerant_ordering(a.as_seconds(), b.as_seconds(), tolerance),
+            _ => match (self.exact_ordering(rhs), self.real_value(), rhs.real_value()) {
+                (Some(ordering), _, _) if tolerance == 0.0 => ordering,
+                (_, Some(a), Some(b)) => Self::tolerant_ordering(a, b, tolerance),
+                _ if op.is_ordering() => return Err(operator_error!(op, self, rhs)),
+                _ => {
+                    let equal = match (self.complex_value(), rhs.complex_value()) {
+                        (Some(a), Some(b)) => (a - b).modulus() <= tolerance,
+                        _ if std::mem::discriminant(self) == std::mem::discriminant(rhs) => self == rhs,
+                        _ => return Err(operator_error!(op, self, rhs))
+                    };
+                    if equal { Some(Ordering::Equal) } else { None }
+                }
+            }
+        };
+
+        Ok( Self::Bool(Boolean::from(op.evaluate(ordering))) )
+    }
+
+    fn logical(&self, rhs: &Self, op: &str, f: fn(Boolean, Boolean) -> Boolean) -> Result<Self, Error> {
+        match (self, rhs) {
+            (Self::Bool(a), Self::Bool(b)) => Ok( Self::Bool(f(*a, *b)) ),
+            _ => Err(operator_error!(op, self, rhs))
+        }
+    }
+
+    pub fn and(&self, rhs: &Self) -> Result<Self, Error> {
+        self.logical(rhs, "and", |a, b| a & b)
+    }
+    pub fn or(&self, rhs: &Self) -> Result<Self, Error> {
+        self.logical(rhs, "or", |a, b| a | b)
+    }
+    pub fn xor(&self, rhs: &Self) -> Result<Self, Error> {
+        self.logical(rhs, "xor", |a, b| a ^ b)
+    }
+    pub fn not(&self) -> Result<Self, Error> {
+        match self {
+            Self::Bool(b) => Ok( Self::Bool(!*b) ),
+            _ => Err(operator_error!("not", self))
         }
     }
 }
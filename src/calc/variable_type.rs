@@ -1,12 +1,17 @@
 use std::fmt::{Debug, Display};
 use std::convert::{Into, TryFrom};
 use std::ops::{AddAssign, SubAssign, MulAssign, DivAssign};
-use crate::core::io::BinaryUnit;
+use crate::core::{io::BinaryUnit, errors::Error};
 
-pub trait VariableType : Display + Debug + PartialEq + Clone + Into<Vec<BinaryUnit>> + TryFrom<Vec<BinaryUnit>> {
+pub trait VariableType : Display + Debug + PartialEq + Clone + Into<Vec<BinaryUnit>> + TryFrom<Vec<BinaryUnit>, Error = Error> {
     fn required_units(&self) -> usize;
 } 
 
-pub trait SimpleNumerical : VariableType + AddAssign + AddAssign<f64> + SubAssign + SubAssign<f64> + MulAssign + MulAssign<f64> + DivAssign + DivAssign<f64> {
-
+pub trait SimpleNumerical : VariableType + Default + AddAssign + AddAssign<f64> + SubAssign + SubAssign<f64> + MulAssign + MulAssign<f64> + DivAssign + DivAssign<f64> {
+    /// The absolute value (or modulus) of the element.
+    fn abs(&self) -> f64;
+    /// The complex conjugate of the element. For real types, this is the element itself.
+    fn conjugate(&self) -> Self {
+        self.clone()
+    }
 }
\ No newline at end of file
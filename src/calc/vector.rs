@@ -1,9 +1,22 @@
 use std::fmt::{Display, Debug};
-use std::ops::{Add, Sub, Div, Mul};
+use std::ops::{Add, Sub, Div, Mul, Index, IndexMut};
 
 use crate::{binary_unit, operation_error, conversion_error, operator_error, core::{io::BinaryUnit, errors::Error}};
+use crate::io::sesssion::session_settings;
 use super::{variable_type::{VariableType, SimpleNumerical}, scalar::Scalar};
 
+/// Decides how element-wise operations treat two vectors of different dimensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DimensionPolicy {
+    /// Dimensions must match exactly, otherwise the operation fails.
+    #[default]
+    Strict,
+    /// The shorter vector is padded with zeros, so `(i + 2j) + (i - 3j + 4k) = (2i - j + 4k)`.
+    ZeroPad,
+    /// A vector of dim = 1 is applied to every element of the other vector. Otherwise, dimensions must match.
+    Broadcast
+}
+
 
 #[derive(Clone)]
 pub struct MVector<T> where T: SimpleNumerical{
@@ -11,12 +24,19 @@ pub struct MVector<T> where T: SimpleNumerical{
 }
 impl<T: SimpleNumerical> Debug for MVector<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        write!(f, "(MVector:{:?})", &self.data)
     }
 }
 impl<T: SimpleNumerical> Display for MVector<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        write!(f, "[")?;
+        for (i, item) in self.data.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        write!(f, "]")
     }
 }
 impl<T: SimpleNumerical> PartialEq for MVector<T> {
@@ -24,13 +44,12 @@ impl<T: SimpleNumerical> PartialEq for MVector<T> {
         self.data == other.data
     }
 }
-impl<T: SimpleNumerical> Into<Vec<BinaryUnit>> for MVector<T> {
-    fn into(self) -> Vec<BinaryUnit> {
-        let mut result = vec![binary_unit!(self.dim())];
-        if self.dim() != 0 {
-            for item in self.data {
-                result.push(binary_unit!(item))
-            }
+impl<T: SimpleNumerical> From<MVector<T>> for Vec<BinaryUnit> {
+    fn from(value: MVector<T>) -> Self {
+        let mut result = vec![binary_unit!(value.dim())];
+        for item in value.data {
+            let units: Vec<BinaryUnit> = item.into();
+            result.extend(units);
         }
 
         result
@@ -50,13 +69,15 @@ impl<T: SimpleNumerical> TryFrom<Vec<BinaryUnit>> for MVector<T> {
             Ok(Self::default())
         }
         else {
-            let mut data: Vec<T> = vec![];
-            for item in iter {
-                let converted = match T::try_from(vec![item]) {
-                    Ok(t) => t,
-                    Err(e) => return Err(e)
-                }
-                data.push(converted);
+            let units: Vec<BinaryUnit> = iter.collect();
+            let per_item = T::default().required_units();
+            if units.len() != dim * per_item {
+                return Err(conversion_error!("expected {} units for a vector of dim = {}, got {}", dim * per_item, dim, units.len()));
+            }
+
+            let mut data: Vec<T> = Vec::with_capacity(dim);
+            for chunk in units.chunks(per_item) {
+                data.push(T::try_from(chunk.to_vec())?);
             }
 
             Ok(
@@ -67,6 +88,24 @@ impl<T: SimpleNumerical> TryFrom<Vec<BinaryUnit>> for MVector<T> {
         }
     }
 }
+impl<T: SimpleNumerical> From<Vec<T>> for MVector<T> {
+    fn from(value: Vec<T>) -> Self {
+        Self {
+            data: value
+        }
+    }
+}
+impl<T: SimpleNumerical> Index<usize> for MVector<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+impl<T: SimpleNumerical> IndexMut<usize> for MVector<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index]
+    }
+}
 impl<T: SimpleNumerical> Default for MVector<T> {
     fn default() -> Self {
         Self {
@@ -82,53 +121,13 @@ impl<T: SimpleNumerical> VariableType for MVector<T> {
 impl<T: SimpleNumerical> Add for MVector<T> {
     type Output = Result<Self, Error>;
     fn add(self, rhs: Self) -> Self::Output {
-        if self.is_error() || rhs.is_error() {
-            Err(operator_error!('+', self, rhs))
-        }
-        else {
-            let mut result: MVector<T>;
-            let minor: MVector<T>;
-            if self.dim() >= rhs.dim() { //Ours is larger
-                result = self;
-                minor = rhs;
-            } else {
-                result = rhs;
-                minor = self;
-            }
-
-            //If we would have (i + 2j) + (i - 3j + 4k), we would expect this to be (2i - j + 4k), not fail. This is why we add to the larger one, and return it.
-            for (i, e) in minor.data.into_iter().enumerate() {
-                result.data[i] -= e;
-            }
-
-            Ok(result)
-        }
+        self.add_with(rhs, session_settings.dimension_policy())
     }
 }
 impl<T: SimpleNumerical> Sub for MVector<T> {
     type Output = Result<Self, Error>;
     fn sub(self, rhs: Self) -> Self::Output {
-        if self.is_error() || rhs.is_error() {
-            Err(operator_error!('-', self, rhs))
-        }
-        else {
-            let mut result: MVector<T>;
-            let minor: MVector<T>;
-            if self.dim() >= rhs.dim() { //Ours is larger
-                result = self;
-                minor = rhs;
-            } else {
-                result = rhs;
-                minor = self;
-            }
-
-            //If we would have (i + 2j) + (i - 3j + 4k), we would expect this to be (2i - j + 4k), not fail. This is why we add to the larger one, and return it.
-            for (i, e) in minor.data.into_iter().enumerate() {
-                result.data[i] -= e;
-            }
-
-            Ok(result)
-        }
+        self.sub_with(rhs, session_settings.dimension_policy())
     }
 }
 impl<T: SimpleNumerical> Mul<Scalar> for MVector<T> {
@@ -184,6 +183,9 @@ impl<T: SimpleNumerical> MVector<T> {
     pub fn is_error(&self) -> bool {
         self.data.is_empty()
     }
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
 
     pub fn magnitude(&self) -> Option<f64> {
         if self.is_error() {
@@ -192,12 +194,216 @@ impl<T: SimpleNumerical> MVector<T> {
 
         let mut result: f64  = 0.00;
         for item in &self.data {
-            result += item.powi(2);
+            result += item.abs().powi(2);
         }
         result = result.sqrt();
 
         Some(result)
     }
+
+    /// Applies `op` to each pair of elements, after lining the two vectors up according to `policy`.
+    fn combine<F>(self, rhs: Self, policy: DimensionPolicy, operator: char, op: F) -> Result<Self, Error> where F: Fn(&mut T, T) -> Result<(), ()> {
+        if self.is_error() || rhs.is_error() {
+            return Err(operator_error!(operator, self, rhs));
+        }
+
+        let (lhs, rhs) = match policy {
+            _ if self.dim() == rhs.dim() => (self, rhs),
+            DimensionPolicy::Strict => return Err(operator_error!(operator, self, rhs)),
+            DimensionPolicy::ZeroPad => {
+                let dim = self.dim().max(rhs.dim());
+                let (mut lhs, mut rhs) = (self, rhs);
+                lhs.data.resize(dim, T::default());
+                rhs.data.resize(dim, T::default());
+                (lhs, rhs)
+            },
+            DimensionPolicy::Broadcast => {
+                if self.dim() == 1 {
+                    (Self::from(vec![self.data[0].clone(); rhs.dim()]), rhs)
+                }
+                else if rhs.dim() == 1 {
+                    let dim = self.dim();
+                    (self, Self::from(vec![rhs.data[0].clone(); dim]))
+                }
+                else {
+                    return Err(operator_error!(operator, self, rhs));
+                }
+            }
+        };
+
+        let mut result = lhs.clone();
+        for (item, x) in result.data.iter_mut().zip(rhs.data.iter()) {
+            if op(item, x.clone()).is_err() {
+                return Err(operator_error!(operator, lhs, rhs));
+            }
+        }
+
+        Ok(result)
+    }
+    pub fn add_with(self, rhs: Self, policy: DimensionPolicy) -> Result<Self, Error> {
+        self.combine(rhs, policy, '+', |a, b| { *a += b; Ok(()) })
+    }
+    pub fn sub_with(self, rhs: Self, policy: DimensionPolicy) -> Result<Self, Error> {
+        self.combine(rhs, policy, '-', |a, b| { *a -= b; Ok(()) })
+    }
+    /// Multiplies each element of `self` by the matching element of `rhs` (the Hadamard product), using the session's dimension policy.
+    pub fn mul_elementwise(self, rhs: Self) -> Result<Self, Error> {
+        self.mul_elementwise_with(rhs, session_settings.dimension_policy())
+    }
+    pub fn mul_elementwise_with(self, rhs: Self, policy: DimensionPolicy) -> Result<Self, Error> {
+        self.combine(rhs, policy, '*', |a, b| { *a *= b; Ok(()) })
+    }
+    /// Divides each element of `self` by the matching element of `rhs`, using the session's dimension policy.
+    /// This fails if any divisor is zero, which includes the padding added by `DimensionPolicy::ZeroPad`.
+    pub fn div_elementwise(self, rhs: Self) -> Result<Self, Error> {
+        self.div_elementwise_with(rhs, session_settings.dimension_policy())
+    }
+    pub fn div_elementwise_with(self, rhs: Self, policy: DimensionPolicy) -> Result<Self, Error> {
+        self.combine(rhs, policy, '/', |a, b| {
+            if b.abs() == 0.0 {
+                return Err(());
+            }
+            *a /= b;
+            Ok(())
+        })
+    }
+
+    /// The product `a * b` of two elements, through the assignment operators that `SimpleNumerical` guarantees.
+    fn product(a: &T, b: &T) -> T {
+        let mut result = a.clone();
+        result *= b.clone();
+        result
+    }
+
+    /// The inner product `sum(a_i * conj(b_i))`. For real vectors this is the usual dot product.
+    pub fn dot(&self, rhs: &Self) -> Result<T, Error> {
+        if self.is_error() || rhs.is_error() {
+            return Err(operation_error!("dot", "no data loaded (error state)"));
+        }
+        else if self.dim() != rhs.dim() {
+            return Err(operation_error!("dot", "dimensions must match, got dim = {} and dim = {}", self.dim(), rhs.dim()));
+        }
+
+        let mut result = T::default();
+        for (a, b) in self.data.iter().zip(rhs.data.iter()) {
+            result += Self::product(a, &b.conjugate());
+        }
+
+        Ok(result)
+    }
+    /// The cross product, which only exists for dim = 3 and dim = 7.
+    pub fn cross(&self, rhs: &Self) -> Result<Self, Error> {
+        // Each triple (a, b, c) means that e_a x e_b = e_c, along with its cyclic permutations.
+        const TRIPLES_3: [(usize, usize, usize); 1] = [(0, 1, 2)];
+        const TRIPLES_7: [(usize, usize, usize); 7] = [(0, 1, 3), (1, 2, 4), (2, 3, 5), (3, 4, 6), (4, 5, 0), (5, 6, 1), (6, 0, 2)];
+
+        if self.dim() != rhs.dim() {
+            return Err(operation_error!("cross", "dimensions must match, got dim = {} and dim = {}", self.dim(), rhs.dim()));
+        }
+        let triples: &[(usize, usize, usize)] = match self.dim() {
+            3 => &TRIPLES_3,
+            7 => &TRIPLES_7,
+            d => return Err(operation_error!("cross", "the cross product is only defined for dim = 3 or dim = 7, got dim = {}", d))
+        };
+
+        let (x, y) = (&self.data, &rhs.data);
+        let mut result: Vec<T> = vec![T::default(); self.dim()];
+        for (a, b, c) in triples {
+            for (i, j, k) in [(*a, *b, *c), (*b, *c, *a), (*c, *a, *b)] {
+                result[k] += Self::product(&x[i], &y[j]);
+                result[k] -= Self::product(&x[j], &y[i]);
+            }
+        }
+
+        Ok( Self::from(result) )
+    }
+    pub fn to_unit(self) -> Result<Self, Error> {
+        match self.magnitude() {
+            None => Err(operation_error!("unit", "no data loaded (error state)")),
+            Some(0.0) => Err(operation_error!("unit", "the zero vector has no direction")),
+            Some(m) => {
+                let mut result = self;
+                for element in result.data.iter_mut() {
+                    (*element) /= m;
+                }
+
+                Ok(result)
+            }
+        }
+    }
+
+    /// The component of `self` that lies along `onto`, `(self . onto / onto . onto) onto`.
+    pub fn projection(&self, onto: &Self) -> Result<Self, Error> {
+        let mut scale = self.dot(onto)?;
+        let denom = onto.dot(onto)?;
+        if denom.abs() == 0.0 {
+            return Err(operation_error!("projection", "cannot project onto the zero vector"));
+        }
+
+        scale /= denom;
+        Ok( Self::from(onto.data.iter().map(|x| Self::product(x, &scale)).collect::<Vec<_>>()) )
+    }
+    /// The component of `self` that is orthogonal to `from`, `self - projection(self, from)`.
+    pub fn rejection(&self, from: &Self) -> Result<Self, Error> {
+        let proj = self.projection(from)?;
+        let mut result = self.clone();
+        for (item, p) in result.data.iter_mut().zip(proj.data) {
+            *item -= p;
+        }
+
+        Ok(result)
+    }
+    /// The Euclidean distance between the points `self` and `rhs`.
+    pub fn distance(&self, rhs: &Self) -> Result<f64, Error> {
+        if self.is_error() || rhs.is_error() {
+            return Err(operation_error!("distance", "no data loaded (error state)"));
+        }
+        else if self.dim() != rhs.dim() {
+            return Err(operation_error!("distance", "dimensions must match, got dim = {} and dim = {}", self.dim(), rhs.dim()));
+        }
+
+        let mut result: f64 = 0.0;
+        for (a, b) in self.data.iter().zip(rhs.data.iter()) {
+            let mut diff = a.clone();
+            diff -= b.clone();
+            result += diff.abs().powi(2);
+        }
+
+        Ok( result.sqrt() )
+    }
+    /// Orthonormalizes `vectors` with the modified Gram-Schmidt process.
+    /// Vectors that are (numerically) dependent on the ones before them are dropped, so the result spans the same space but may be shorter.
+    pub fn gram_schmidt(vectors: &[Self]) -> Result<Vec<Self>, Error> {
+        let dim = match vectors.first() {
+            None => return Ok(vec![]),
+            Some(v) => v.dim()
+        };
+        if let Some(v) = vectors.iter().find(|v| v.dim() != dim || v.is_error()) {
+            return Err(operation_error!("Gram-Schmidt", "all vectors must share dim = {}, got dim = {}", dim, v.dim()));
+        }
+
+        let largest = vectors.iter().filter_map(|v| v.magnitude()).fold(0.0, f64::max);
+        let tol = largest * f64::EPSILON * (dim.max(vectors.len()) as f64) * 16.0;
+
+        let mut result: Vec<Self> = Vec::with_capacity(vectors.len());
+        for v in vectors {
+            let mut w = v.clone();
+            //Two passes of orthogonalization keep the result orthogonal to working precision.
+            for _ in 0..2 {
+                for q in result.iter() {
+                    w = w.rejection(q)?;
+                }
+            }
+
+            if w.magnitude().unwrap_or(0.0) > tol {
+                result.push(w.to_unit()?);
+            }
+        }
+
+        Ok(result)
+    }
+}
+impl MVector<Scalar> {
     pub fn angle(&self) -> Result<f64, Error> {
         if self.is_error() {
             return Err(operation_error!('θ', "no data loaded (error state)"));
@@ -206,16 +412,65 @@ impl<T: SimpleNumerical> MVector<T> {
             return Err(operation_error!('θ', "can only find angle for dim = 2, got dim = {}", self.dim()));
         }
 
-        Ok( self.data[1].atan2(self.data[0]) )
-    }
-
-    pub fn dot(self, rhs: Self) -> Result<Self, Error> {
-        todo!()
-    }
-    pub fn cross(self, rhs: Self) -> Result<Self, Error> {
-        todo!()
+        let x: f64 = self.data[0].clone().into();
+        let y: f64 = self.data[1].clone().into();
+        Ok( y.atan2(x) )
     }
-    pub fn to_unit(self) -> Self {
-        todo!()
+    /// The angle between two vectors, in radians, in the range `[0, pi]`.
+    pub fn angle_between(&self, rhs: &Self) -> Result<f64, Error> {
+        let dot: f64 = self.dot(rhs)?.into();
+        match (self.magnitude(), rhs.magnitude()) {
+            (Some(a), Some(b)) if a != 0.0 && b != 0.0 => Ok( (dot / (a * b)).clamp(-1.0, 1.0).acos() ),
+            _ => Err(operation_error!("angle", "the zero vector has no direction"))
+        }
     }
 }
+
+#[test]
+fn test_vector_geometry() {
+    use std::f64::consts::FRAC_PI_2;
+    use super::complex::Complex;
+
+    let v = |data: &[f64]| MVector::from(data.iter().map(|x| Scalar::from(*x)).collect::<Vec<_>>());
+    let close = |a: &MVector<Scalar>, b: &MVector<Scalar>| a.distance(b).unwrap() < 1e-12;
+
+    let i = v(&[1.0, 0.0, 0.0]);
+    let j = v(&[0.0, 1.0, 0.0]);
+    assert_eq!(i.cross(&j).unwrap(), v(&[0.0, 0.0, 1.0]));
+    assert_eq!((i.clone() + j.clone()).unwrap(), v(&[1.0, 1.0, 0.0]));
+    assert_eq!(v(&[1.0, 2.0, 3.0]).dot(&v(&[4.0, 5.0, 6.0])).unwrap(), 32.0);
+    assert!(v(&[1.0, 2.0]).cross(&v(&[3.0, 4.0])).is_err());
+    assert!(i.dot(&v(&[1.0, 2.0])).is_err());
+
+    assert!((i.clone() + v(&[1.0, 2.0])).is_err());
+    assert_eq!(i.clone().add_with(v(&[1.0, 2.0]), DimensionPolicy::ZeroPad).unwrap(), v(&[2.0, 2.0, 0.0]));
+    assert_eq!(v(&[1.0, 2.0]).sub_with(i.clone(), DimensionPolicy::ZeroPad).unwrap(), v(&[0.0, 2.0, 0.0]));
+    assert_eq!(v(&[2.0]).sub_with(v(&[1.0, 2.0, 3.0]), DimensionPolicy::Broadcast).unwrap(), v(&[1.0, 0.0, -1.0]));
+    assert!(v(&[1.0, 2.0]).add_with(v(&[1.0, 2.0, 3.0]), DimensionPolicy::Broadcast).is_err());
+    assert_eq!(v(&[1.0, 2.0, 3.0]).mul_elementwise(v(&[2.0, 2.0, 2.0])).unwrap(), v(&[2.0, 4.0, 6.0]));
+    assert_eq!(v(&[1.0, 2.0, 3.0]).div_elementwise_with(v(&[2.0]), DimensionPolicy::Broadcast).unwrap(), v(&[0.5, 1.0, 1.5]));
+    assert!(v(&[1.0, 2.0, 3.0]).div_elementwise_with(v(&[1.0, 2.0]), DimensionPolicy::ZeroPad).is_err());
+
+    //The 7-dimensional cross product is orthogonal to both of its operands.
+    let a = v(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+    let b = v(&[-2.0, 0.5, 1.0, 0.0, 3.0, -1.0, 2.0]);
+    let c = a.cross(&b).unwrap();
+    assert!(f64::from(c.dot(&a).unwrap()).abs() < 1e-12 && f64::from(c.dot(&b).unwrap()).abs() < 1e-12);
+
+    assert!(close(&v(&[3.0, 4.0]).to_unit().unwrap(), &v(&[0.6, 0.8])));
+    assert!(v(&[0.0, 0.0]).to_unit().is_err());
+    assert!(close(&v(&[2.0, 3.0]).projection(&v(&[1.0, 0.0])).unwrap(), &v(&[2.0, 0.0])));
+    assert!(close(&v(&[2.0, 3.0]).rejection(&v(&[1.0, 0.0])).unwrap(), &v(&[0.0, 3.0])));
+    assert!((i.angle_between(&j).unwrap() - FRAC_PI_2).abs() < 1e-12);
+    assert_eq!(v(&[1.0, 1.0]).distance(&v(&[4.0, 5.0])).unwrap(), 5.0);
+
+    let basis = MVector::gram_schmidt(&[v(&[1.0, 1.0, 0.0]), v(&[2.0, 2.0, 0.0]), v(&[1.0, 0.0, 1.0])]).unwrap();
+    assert_eq!(basis.len(), 2);
+    assert!(f64::from(basis[0].dot(&basis[1]).unwrap()).abs() < 1e-12);
+    assert!(basis.iter().all(|q| (q.magnitude().unwrap() - 1.0).abs() < 1e-12));
+
+    let z = MVector::from(vec![Complex::new(1.0, 1.0), Complex::new(0.0, 2.0)]);
+    assert_eq!(z.dot(&z).unwrap(), Complex::from(6.0));
+    let orthonormal = MVector::gram_schmidt(&[z.clone(), MVector::from(vec![Complex::from(1.0), Complex::default()])]).unwrap();
+    assert!(orthonormal[0].dot(&orthonormal[1]).unwrap().modulus() < 1e-12);
+}
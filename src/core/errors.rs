@@ -51,18 +51,19 @@ macro_rules! argument_error {
     // name, value
     ($name: expr, $value: expr) => { // name, value
         {
-            Error::ArgumentError($name.to_string(), format!("{:?}", &$value))
+            $crate::core::errors::Error::ArgumentError($name.to_string(), format!("{:?}", &$value))
         }
     };
     ($name: expr, $fmt_str: expr, $($v: expr), *) => {
         {
-            Error::ArgumentError($name.to_string(), format!($fmt_str, $(&$v)* ))
+            $crate::core::errors::Error::ArgumentError($name.to_string(), format!($fmt_str, $(&$v), *))
         }
     }
 }
 /// Returns Error of NullError, containing a name passed
 /// ```
-/// assert_eq!(null_error!("arg0"), Error::NullError("arg0".to_string()))
+/// # use jason_lib::{null_error, core::errors::Error};
+/// assert!(matches!(null_error!("arg0"), Error::NullError(name) if name == "arg0"))
 /// ```
 #[macro_export]
 macro_rules! null_error {
@@ -79,7 +80,7 @@ macro_rules! format_error {
     ($content: expr, $reason_str: expr, $($v: expr), *) => {
         {
             // content, reason formatting string, values...
-            $crate::core::errors::Error::FormatError($content.to_string(), format!($reason_str, $(&$v)*))
+            $crate::core::errors::Error::FormatError($content.to_string(), format!($reason_str, $(&$v), *))
         }
     };
     ($content: expr, $reason: expr) => {
@@ -148,7 +149,7 @@ macro_rules! conversion_error {
 macro_rules! unexpected_error {
     ($fmt_str: expr, $( $v: expr), *) => {
         {
-            $crate::core::errors::Error::UnexpectedError(format!($fmt_str, $(&$v)*))
+            $crate::core::errors::Error::UnexpectedError(format!($fmt_str, $(&$v), *))
         }
     };
     ($reason: expr) => {
@@ -161,7 +162,7 @@ macro_rules! unexpected_error {
 macro_rules! operation_error {
     ($action: expr, $fmt_str: expr, $( $v: expr), *) => {
         {
-            $crate::core::errors::Error::OperationError($action.to_string(), format!($fmt_str, $(&$v)*))
+            $crate::core::errors::Error::OperationError($action.to_string(), format!($fmt_str, $(&$v), *))
         }
     };
     ($action: expr, $reason: expr) => {
@@ -174,7 +175,7 @@ macro_rules! operation_error {
 macro_rules! io_error {
     ($kind: expr, $fmt_str: expr, $( $v: expr), *) => {
         {
-            $crate::core::errors::Error::IOError(std::io::Error($kind, format!($fmt_str, $(&$v)*)))
+            $crate::core::errors::Error::IOError(std::io::Error($kind, format!($fmt_str, $(&$v), *)))
         }
     };
     ($kind: expr, $reason: expr) => {
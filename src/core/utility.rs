@@ -1,5 +1,6 @@
 /// Takes a specific number of elements out of a vec, and returns it as a concrete [type; count] type.
 /// ```
+/// # use jason_lib::take_from_vec;
 /// let vec: Vec<u32> = vec![1, 2, 3, 4];
 /// assert_eq!(take_from_vec!(3, vec, u32), [1, 2, 3])
 /// ```
@@ -0,0 +1,101 @@
+use std::sync::{Arc, Mutex};
+use lazy_static::lazy_static;
+
+use crate::calc::{vector::DimensionPolicy, radix::WordFormat};
+
+/// The settings that apply to every calculation in a session, unless a call overrides them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SessionSettingsData {
+    pub dimension_policy: DimensionPolicy,
+    /// The number of significant digits kept by `BigDecimal` arithmetic.
+    pub decimal_precision: usize,
+    /// The word width and signedness used by bitwise operations and radix input and output on integers.
+    pub word_format: WordFormat
+}
+impl Default for SessionSettingsData {
+    fn default() -> Self {
+        Self {
+            dimension_policy: DimensionPolicy::default(),
+            decimal_precision: 50,
+            word_format: WordFormat::default()
+        }
+    }
+}
+
+pub struct SessionSettings {
+    data: Arc<Mutex<SessionSettingsData>>
+}
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SessionSettings {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(SessionSettingsData::default()))
+        }
+    }
+
+    /// A copy of every setting, as they currently stand.
+    pub fn snapshot(&self) -> SessionSettingsData {
+        let data = self.data.lock().unwrap();
+        *data
+    }
+    pub fn reset(&self) {
+        let mut data = self.data.lock().unwrap();
+        *data = SessionSettingsData::default();
+    }
+
+    pub fn dimension_policy(&self) -> DimensionPolicy {
+        let data = self.data.lock().unwrap();
+        data.dimension_policy
+    }
+    pub fn set_dimension_policy(&self, policy: DimensionPolicy) {
+        let mut data = self.data.lock().unwrap();
+        data.dimension_policy = policy;
+    }
+
+    pub fn decimal_precision(&self) -> usize {
+        let data = self.data.lock().unwrap();
+        data.decimal_precision
+    }
+    /// Sets the number of significant digits used by `BigDecimal`. A precision of zero is raised to one.
+    pub fn set_decimal_precision(&self, precision: usize) {
+        let mut data = self.data.lock().unwrap();
+        data.decimal_precision = precision.max(1);
+    }
+
+    pub fn word_format(&self) -> WordFormat {
+        let data = self.data.lock().unwrap();
+        data.word_format
+    }
+    pub fn set_word_format(&self, format: WordFormat) {
+        let mut data = self.data.lock().unwrap();
+        data.word_format = format;
+    }
+}
+
+lazy_static! {
+    pub static ref session_settings: SessionSettings = SessionSettings::new();
+}
+
+#[test]
+fn test_session_settings() {
+    let settings = SessionSettings::new();
+    assert_eq!(settings.dimension_policy(), DimensionPolicy::Strict);
+
+    settings.set_dimension_policy(DimensionPolicy::Broadcast);
+    assert_eq!(settings.snapshot().dimension_policy, DimensionPolicy::Broadcast);
+
+    assert_eq!(settings.decimal_precision(), 50);
+    settings.set_decimal_precision(0);
+    assert_eq!(settings.decimal_precision(), 1);
+
+    assert_eq!(settings.word_format(), WordFormat::new(64, true).unwrap());
+    settings.set_word_format(WordFormat::new(16, false).unwrap());
+    assert_eq!(settings.snapshot().word_format.to_string(), "u16");
+
+    settings.reset();
+    assert_eq!(settings.snapshot(), SessionSettingsData::default());
+}